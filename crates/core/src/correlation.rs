@@ -0,0 +1,201 @@
+//! Keyed Correlation Map
+//!
+//! Realtime events arrive as streams that must be stitched back together by ID
+//! (item IDs, response IDs). If the closing event never arrives, e.g. because a
+//! response was cancelled, the partial state would otherwise leak for the rest of
+//! the session. `CorrelationMap` bounds that state both by age and by size.
+
+use crate::clock::{Clock, SystemClock};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+struct Entry<V> {
+    inserted_at: Instant,
+    value: V,
+}
+
+/// A thread-safe map whose entries expire after a TTL, holding at most `capacity` entries.
+///
+/// When full, inserting a new key evicts the oldest entry.
+pub struct CorrelationMap<K, V> {
+    entries: Mutex<HashMap<K, Entry<V>>>,
+    ttl: Duration,
+    capacity: usize,
+    clock: Arc<dyn Clock>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> CorrelationMap<K, V> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self::with_clock(ttl, capacity, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(ttl: Duration, capacity: usize, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+            capacity,
+            clock,
+        }
+    }
+
+    /// Inserts a value, returning the previous live value for the key, if any.
+    /// Re-inserting a key refreshes its TTL.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let now = self.clock.now();
+        let mut entries = self.live_entries(now);
+        self.make_room(&mut entries, &key);
+        entries
+            .insert(
+                key,
+                Entry {
+                    inserted_at: now,
+                    value,
+                },
+            )
+            .map(|entry| entry.value)
+    }
+
+    /// Returns a copy of the value for `key` if it exists and hasn't expired.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = self.live_entries(self.clock.now());
+        entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    /// Updates the value for `key` in place, inserting `V::default()` first if absent.
+    /// Returns a copy of the updated value. Updating does not refresh the TTL.
+    pub fn upsert(&self, key: K, f: impl FnOnce(&mut V)) -> V
+    where
+        V: Default,
+    {
+        let now = self.clock.now();
+        let mut entries = self.live_entries(now);
+        self.make_room(&mut entries, &key);
+        let entry = entries.entry(key).or_insert_with(|| Entry {
+            inserted_at: now,
+            value: V::default(),
+        });
+        f(&mut entry.value);
+        entry.value.clone()
+    }
+
+    /// Removes and returns the value for `key` if it exists and hasn't expired.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let mut entries = self.live_entries(self.clock.now());
+        entries.remove(key).map(|entry| entry.value)
+    }
+
+    /// The number of live entries.
+    pub fn len(&self) -> usize {
+        self.live_entries(self.clock.now()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<K, Entry<V>>> {
+        // The map holds no invariants that a panicking holder could break.
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Evicts the oldest entry if the map is full and `key` would be a new entry.
+    fn make_room(&self, entries: &mut HashMap<K, Entry<V>>, key: &K) {
+        if !entries.contains_key(key)
+            && entries.len() >= self.capacity
+            && let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted_at)
+                .map(|(key, _)| key.clone())
+        {
+            entries.remove(&oldest);
+        }
+    }
+
+    /// Locks the map after evicting entries older than the TTL.
+    fn live_entries(&self, now: Instant) -> MutexGuard<'_, HashMap<K, Entry<V>>> {
+        let mut entries = self.lock();
+        entries.retain(|_, entry| now.duration_since(entry.inserted_at) < self.ttl);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn map_with_clock(
+        ttl: Duration,
+        capacity: usize,
+    ) -> (Arc<ManualClock>, CorrelationMap<String, u32>) {
+        let clock = Arc::new(ManualClock::new());
+        let map = CorrelationMap::with_clock(ttl, capacity, clock.clone());
+        (clock, map)
+    }
+
+    #[test]
+    fn test_insert_get_remove() {
+        let (_clock, map) = map_with_clock(Duration::from_secs(60), 8);
+
+        assert_eq!(map.insert("a".to_string(), 1), None);
+        assert_eq!(map.insert("a".to_string(), 2), Some(1));
+        assert_eq!(map.get(&"a".to_string()), Some(2));
+        assert_eq!(map.get(&"b".to_string()), None);
+
+        assert_eq!(map.remove(&"a".to_string()), Some(2));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let (clock, map) = map_with_clock(Duration::from_secs(10), 8);
+        map.insert("old".to_string(), 1);
+
+        clock.advance(Duration::from_secs(6));
+        map.insert("new".to_string(), 2);
+        assert_eq!(map.len(), 2);
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(map.get(&"old".to_string()), None);
+        assert_eq!(map.get(&"new".to_string()), Some(2));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let (clock, map) = map_with_clock(Duration::from_secs(60), 2);
+        map.insert("a".to_string(), 1);
+        clock.advance(Duration::from_secs(1));
+        map.insert("b".to_string(), 2);
+        clock.advance(Duration::from_secs(1));
+        map.insert("c".to_string(), 3);
+
+        assert_eq!(map.get(&"a".to_string()), None);
+        assert_eq!(map.get(&"b".to_string()), Some(2));
+        assert_eq!(map.get(&"c".to_string()), Some(3));
+    }
+
+    #[test]
+    fn test_upsert_accumulates() {
+        let (_clock, map) = map_with_clock(Duration::from_secs(60), 8);
+        assert_eq!(map.upsert("a".to_string(), |v| *v += 2), 2);
+        assert_eq!(map.upsert("a".to_string(), |v| *v += 3), 5);
+    }
+
+    #[test]
+    fn test_upsert_evicts_oldest_when_full() {
+        let (clock, map) = map_with_clock(Duration::from_secs(60), 2);
+        map.insert("a".to_string(), 1);
+        clock.advance(Duration::from_secs(1));
+        map.insert("b".to_string(), 2);
+        clock.advance(Duration::from_secs(1));
+
+        assert_eq!(map.upsert("b".to_string(), |v| *v += 1), 3);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.upsert("c".to_string(), |v| *v += 4), 4);
+        assert_eq!(map.get(&"a".to_string()), None);
+        assert_eq!(map.len(), 2);
+    }
+}
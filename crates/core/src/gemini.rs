@@ -0,0 +1,371 @@
+use crate::prompts::PromptStore;
+use crate::reviewer::{
+    BLANK_SEGMENT_NUDGE, DEFAULT_CONNECT_TIMEOUT, DEFAULT_MAX_RETRIES, DEFAULT_REQUEST_TIMEOUT,
+    DEFAULT_RETRY_BASE_DELAY, Reviewer, ReviewerError, dedup_subtopics, http_client,
+    last_explained_context_prompt, normalize_topic_analysis, parse_verdict, render_prompt,
+    send_with_retry, summarize_session_prompt,
+};
+use crate::topic::{SubTopic, parse_numbered_list};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// The default Gemini API base URL used by `GeminiReviewer`.
+pub const DEFAULT_GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+#[derive(Debug, Deserialize)]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Candidate {
+    content: Option<Content>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Content {
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Part {
+    text: Option<String>,
+}
+
+/// A `Reviewer` backed by Google's Gemini `generateContent` API.
+///
+/// It uses the same prompt templates as `ReviewerClient` and normalizes Gemini's replies
+/// into the same shapes, so `FeynmanSession` can't tell the two apart.
+pub struct GeminiReviewer {
+    client: Client,
+    api_key: String,
+    model: String,
+    base_url: String,
+    prompts: PromptStore,
+    max_retries: u32,
+    retry_base_delay: Duration,
+}
+
+impl GeminiReviewer {
+    pub fn new(
+        api_key: String,
+        model: String,
+        prompts: impl Into<PromptStore>,
+    ) -> Result<Self, ReviewerError> {
+        Ok(Self {
+            client: http_client(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT)?,
+            api_key,
+            model,
+            base_url: DEFAULT_GEMINI_BASE_URL.to_string(),
+            prompts: prompts.into(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+        })
+    }
+
+    /// Points the client at a different Gemini-compatible API, e.g. a proxy.
+    pub fn with_base_url(mut self, base_url: &str) -> Result<Self> {
+        let trimmed = base_url.trim().trim_end_matches('/');
+        let url = reqwest::Url::parse(trimmed)
+            .with_context(|| format!("Invalid Gemini base URL: '{base_url}'"))?;
+        if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+            anyhow::bail!("Invalid Gemini base URL: '{base_url}' must be an http(s) URL");
+        }
+        self.base_url = trimmed.to_string();
+        Ok(self)
+    }
+
+    /// Replaces the underlying HTTP client, e.g. to configure timeouts or proxies.
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Sets how long to wait for a connection and for a whole request before giving up with
+    /// `ReviewerError::Network`. Replaces any client set with `with_http_client`.
+    pub fn with_timeouts(
+        mut self,
+        connect_timeout: Duration,
+        request_timeout: Duration,
+    ) -> Result<Self, ReviewerError> {
+        self.client = http_client(connect_timeout, request_timeout)?;
+        Ok(self)
+    }
+
+    /// Sets how many times a call is retried after a 429, a 5xx, or a network error.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry. Each later retry waits twice as long.
+    pub fn with_retry_base_delay(mut self, retry_base_delay: Duration) -> Self {
+        self.retry_base_delay = retry_base_delay;
+        self
+    }
+
+    /// The `generateContent` endpoint for the configured model.
+    pub fn generate_content_url(&self) -> String {
+        format!("{}/models/{}:generateContent", self.base_url, self.model)
+    }
+
+    /// Sends a single-turn prompt and returns the text of the first candidate.
+    ///
+    /// `json` asks Gemini for a JSON reply, the counterpart of OpenAI's `json_object` format.
+    async fn generate(
+        &self,
+        prompt: String,
+        json: bool,
+        temperature: Option<f32>,
+    ) -> Result<String, ReviewerError> {
+        let mut generation_config = serde_json::Map::new();
+        if json {
+            generation_config.insert("responseMimeType".into(), "application/json".into());
+        }
+        if let Some(temperature) = temperature {
+            generation_config.insert("temperature".into(), temperature.into());
+        }
+        let body = serde_json::json!({
+            "contents": [
+                { "role": "user", "parts": [{ "text": prompt }] }
+            ],
+            "generationConfig": generation_config,
+        });
+
+        let url = self.generate_content_url();
+        let request = || {
+            self.client
+                .post(&url)
+                .header("x-goog-api-key", &self.api_key)
+                .json(&body)
+        };
+        let resp = send_with_retry(request, self.max_retries, self.retry_base_delay)
+            .await?
+            .json::<GenerateContentResponse>()
+            .await?;
+
+        // A candidate's text can be split across several parts.
+        let text: String = resp
+            .candidates
+            .into_iter()
+            .next()
+            .and_then(|candidate| candidate.content)
+            .ok_or(ReviewerError::EmptyResponse)?
+            .parts
+            .into_iter()
+            .filter_map(|part| part.text)
+            .collect();
+        Ok(text)
+    }
+}
+
+#[async_trait]
+impl Reviewer for GeminiReviewer {
+    async fn looks_like_topic_change(
+        &self,
+        context_buffer: &str,
+        new_segment: &str,
+    ) -> Result<String, ReviewerError> {
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "looks_like_topic_change",
+            &[
+                ("context_buffer", context_buffer),
+                ("new_segment", new_segment),
+            ],
+        )?;
+        self.generate(prompt, false, None).await
+    }
+
+    async fn analyze_topic(
+        &self,
+        segment: &str,
+        detected_subtopics: &[SubTopic],
+    ) -> Result<String, ReviewerError> {
+        let subtopic_names = detected_subtopics
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "analyze_topic",
+            &[("subtopic_names", &subtopic_names), ("segment", segment)],
+        )?;
+        let answer = self.generate(prompt, true, Some(0.2)).await?;
+        normalize_topic_analysis(&answer)
+    }
+
+    async fn check_answer_satisfies_question(
+        &self,
+        segment: &str,
+        question: &str,
+    ) -> Result<bool, ReviewerError> {
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "check_answer_satisfies_question",
+            &[("segment", segment), ("question", question)],
+        )?;
+        let answer = self.generate(prompt, true, Some(0.0)).await?;
+        parse_verdict(&answer, "satisfies")
+    }
+
+    async fn generate_subtopics(
+        &self,
+        topic: &str,
+        count: usize,
+    ) -> Result<Vec<String>, ReviewerError> {
+        let count_str = count.to_string();
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "generate_subtopics",
+            &[("topic", topic), ("count", &count_str)],
+        )?;
+        let answer = self.generate(prompt, false, None).await?;
+        Ok(dedup_subtopics(parse_numbered_list(&answer), count))
+    }
+
+    async fn analyze_last_explained_context(
+        &self,
+        segment: &str,
+        main_topic: &str,
+        subtopic_list: &[String],
+    ) -> Result<String, ReviewerError> {
+        if segment.trim().is_empty() {
+            return Ok(BLANK_SEGMENT_NUDGE.to_string());
+        }
+
+        let prompt = last_explained_context_prompt(
+            &self.prompts.read(),
+            segment,
+            main_topic,
+            subtopic_list,
+        )?;
+        let answer = self.generate(prompt, false, Some(0.2)).await?;
+        Ok(answer.trim().to_string())
+    }
+
+    async fn analyze_answer(&self, question: &str, answer: &str) -> Result<bool, ReviewerError> {
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "analyze_answer",
+            &[("question", question), ("answer", answer)],
+        )?;
+        let answer = self.generate(prompt, true, Some(0.1)).await?;
+        parse_verdict(&answer, "correct")
+    }
+
+    async fn summarize_session(
+        &self,
+        topic: &str,
+        transcript: &str,
+        covered: &[String],
+        incomplete: &[String],
+    ) -> Result<String, ReviewerError> {
+        let prompt =
+            summarize_session_prompt(&self.prompts.read(), topic, transcript, covered, incomplete)?;
+        let answer = self.generate(prompt, false, Some(0.3)).await?;
+        Ok(answer.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use wiremock::matchers::{header, method, path};
+
+    fn candidate(parts: &[&str]) -> serde_json::Value {
+        let parts: Vec<_> = parts
+            .iter()
+            .map(|text| serde_json::json!({ "text": text }))
+            .collect();
+        serde_json::json!({
+            "candidates": [{ "content": { "role": "model", "parts": parts } }]
+        })
+    }
+
+    /// Starts a mock Gemini server that replies with `reply`, and returns a reviewer pointed at it.
+    async fn mock_gemini(reply: serde_json::Value) -> (wiremock::MockServer, GeminiReviewer) {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("POST"))
+            .and(path("/v1beta/models/gemini-test:generateContent"))
+            .and(header("x-goog-api-key", "key"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(reply))
+            .mount(&server)
+            .await;
+
+        let prompts = HashMap::from([
+            (
+                "analyze_topic".to_string(),
+                "{subtopic_names}: {segment}".to_string(),
+            ),
+            ("generate_subtopics".to_string(), "{topic}".to_string()),
+            (
+                "analyze_answer".to_string(),
+                "{question} {answer}".to_string(),
+            ),
+        ]);
+        let reviewer = GeminiReviewer::new("key".to_string(), "gemini-test".to_string(), prompts)
+            .unwrap()
+            .with_retry_base_delay(Duration::from_millis(1))
+            .with_base_url(&format!("{}/v1beta", server.uri()))
+            .unwrap();
+        (server, reviewer)
+    }
+
+    #[test]
+    fn test_default_endpoint() {
+        let reviewer = GeminiReviewer::new(
+            "key".to_string(),
+            "gemini-2.0-flash".to_string(),
+            HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            reviewer.generate_content_url(),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:generateContent"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_subtopics_joins_parts() {
+        let (_server, reviewer) = mock_gemini(candidate(&["1. Processes\n", "2. Threads"])).await;
+        let subtopics = reviewer.generate_subtopics("OS", 10).await.unwrap();
+        assert_eq!(subtopics, vec!["Processes", "Threads"]);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_topic_requests_json_and_wraps_object() {
+        let (server, reviewer) = mock_gemini(candidate(&[r#"{"status": "ok"}"#])).await;
+        let result = reviewer.analyze_topic("segment", &[]).await.unwrap();
+        assert_eq!(result, r#"[{"status": "ok"}]"#);
+
+        // The request carried the prompt and asked for JSON output.
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = requests[0].body_json().unwrap();
+        assert_eq!(body["contents"][0]["parts"][0]["text"], ": segment");
+        assert_eq!(
+            body["generationConfig"]["responseMimeType"],
+            "application/json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_analyze_answer_reads_verdict() {
+        let (_server, reviewer) =
+            mock_gemini(candidate(&["```json\n{\"correct\": true}\n```"])).await;
+        assert!(reviewer.analyze_answer("question", "answer").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_no_candidates_is_an_error() {
+        let (_server, reviewer) = mock_gemini(serde_json::json!({ "candidates": [] })).await;
+        assert!(reviewer.generate_subtopics("OS", 10).await.is_err());
+    }
+}
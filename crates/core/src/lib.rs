@@ -1,7 +1,14 @@
+pub mod clock;
+pub mod correlation;
+pub mod gemini;
+pub mod prompts;
 pub mod reviewer;
 pub mod session_state;
 pub mod topic;
 
+use session_state::SubtopicStats;
+use std::collections::HashMap;
+
 /// Represents commands that the core logic (`FeynmanSession`) issues to the runtime.
 ///
 /// This enum is the primary API for decoupling the session's decision-making
@@ -11,6 +18,31 @@ pub enum Command {
     /// Command the runtime to speak the given text to the user.
     SpeakText(String),
     /// Command indicating the session (or a phase of it) is complete.
-    /// Includes a final message for the user.
-    SessionComplete(String),
+    /// Includes a final message to speak to the user, and per-subtopic stats for the teacher
+    /// that are not meant to be spoken.
+    SessionComplete {
+        message: String,
+        stats: HashMap<String, SubtopicStats>,
+    },
+    /// Command the runtime to ask the user a follow-up question about a subtopic.
+    /// Unlike `SpeakText`, it says which subtopic and which gap (`field`) the question is for.
+    /// Once it has been spoken, the runtime passes `question_id` back to
+    /// `FeynmanSession::question_delivered`.
+    QuestionAsked {
+        question_id: u64,
+        subtopic: String,
+        field: String,
+        question: String,
+    },
+    /// Command indicating the learner skipped the current question, which stays unanswered.
+    /// Informational only: the session says what happens next itself, either by asking the
+    /// next question or, when the batch is over, with a `SpeakText` acknowledgement.
+    SkipQuestion,
+    /// Command reporting how many subtopics are covered so far, and which one is being
+    /// questioned, if any.
+    Progress {
+        covered: usize,
+        total: usize,
+        current_subtopic: Option<String>,
+    },
 }
@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+
+/// Reads every `.md` file in `dir_path` into a map keyed by file stem.
+pub fn load_dir(dir_path: &Path) -> Result<HashMap<String, String>> {
+    let mut prompts = HashMap::new();
+
+    for entry in fs::read_dir(dir_path)
+        .with_context(|| format!("Failed to read prompts directory: {}", dir_path.display()))?
+    {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file() && is_prompt_file(&path) {
+            let prompt_key = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .context("Could not get file stem for prompt file")?
+                .to_string();
+
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read prompt file: {}", path.display()))?;
+
+            prompts.insert(prompt_key, content);
+        }
+    }
+
+    Ok(prompts)
+}
+
+fn is_prompt_file(path: &Path) -> bool {
+    path.extension().and_then(|s| s.to_str()) == Some("md")
+}
+
+/// A shared, swappable set of prompt templates.
+///
+/// Clones share the same prompts, so a reviewer built from a store sees every reload.
+#[derive(Debug, Clone, Default)]
+pub struct PromptStore {
+    prompts: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl From<HashMap<String, String>> for PromptStore {
+    fn from(prompts: HashMap<String, String>) -> Self {
+        Self::new(prompts)
+    }
+}
+
+impl PromptStore {
+    pub fn new(prompts: HashMap<String, String>) -> Self {
+        Self {
+            prompts: Arc::new(RwLock::new(prompts)),
+        }
+    }
+
+    /// Creates a store from the `.md` files in `dir_path`.
+    pub fn load(dir_path: &Path) -> Result<Self> {
+        Ok(Self::new(load_dir(dir_path)?))
+    }
+
+    /// Borrows the current prompts. Don't hold the guard across an `.await`.
+    pub fn read(&self) -> RwLockReadGuard<'_, HashMap<String, String>> {
+        // A writer only ever swaps in a whole map, so a poisoned lock still holds a valid one.
+        self.prompts.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.read().get(name).cloned()
+    }
+
+    /// Replaces every prompt at once.
+    pub fn replace(&self, prompts: HashMap<String, String>) {
+        *self.prompts.write().unwrap_or_else(PoisonError::into_inner) = prompts;
+    }
+
+    /// Reloads the prompts whenever a `.md` file in `dir_path` changes, until the returned
+    /// watcher is dropped.
+    ///
+    /// If a reload fails, e.g. because a file is mid-save, the previous prompts are kept and
+    /// the next change tries again.
+    pub fn watch(&self, dir_path: impl AsRef<Path>) -> Result<PromptWatcher> {
+        let dir_path: PathBuf = dir_path.as_ref().to_path_buf();
+        let store = self.clone();
+        let reload_dir = dir_path.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        tracing::warn!("Prompt watcher error: {}", e);
+                        return;
+                    }
+                };
+                let changed = matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                );
+                if !changed || !event.paths.iter().any(|path| is_prompt_file(path)) {
+                    return;
+                }
+                match load_dir(&reload_dir) {
+                    Ok(prompts) => {
+                        tracing::info!("Reloaded {} prompts", prompts.len());
+                        store.replace(prompts);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to reload prompts, keeping the old ones: {}", e)
+                    }
+                }
+            })
+            .context("Failed to create prompt watcher")?;
+        watcher
+            .watch(&dir_path, RecursiveMode::NonRecursive)
+            .with_context(|| {
+                format!("Failed to watch prompts directory: {}", dir_path.display())
+            })?;
+        Ok(PromptWatcher { _watcher: watcher })
+    }
+}
+
+/// Keeps a `PromptStore` in sync with its directory; reloading stops when this is dropped.
+pub struct PromptWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_modifying_a_file_updates_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("greeting.md"), "Hello, {name}").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a prompt").unwrap();
+
+        let store = PromptStore::load(dir.path()).unwrap();
+        assert_eq!(store.get("greeting").as_deref(), Some("Hello, {name}"));
+        assert!(store.get("notes").is_none());
+
+        let reviewer_view = store.clone();
+        let _watcher = store.watch(dir.path()).unwrap();
+        fs::write(dir.path().join("greeting.md"), "Hi, {name}").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while reviewer_view.get("greeting").as_deref() != Some("Hi, {name}") {
+            assert!(
+                Instant::now() < deadline,
+                "prompt was not reloaded within 5 seconds"
+            );
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
@@ -1,11 +1,68 @@
-use crate::topic::SubTopic;
+use crate::correlation::CorrelationMap;
+use crate::prompts::PromptStore;
+use crate::topic::{SubTopic, parse_numbered_list};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 #[cfg(test)]
 use mockall::automock;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Why a `Reviewer` call failed.
+#[derive(Debug, thiserror::Error)]
+pub enum ReviewerError {
+    /// The request didn't get a response, e.g. the connection failed or timed out.
+    #[error("Network error talking to the LLM")]
+    Network(#[source] reqwest::Error),
+    /// The reply wasn't in the expected shape.
+    #[error("Failed to parse LLM response: {0}")]
+    Deserialize(String),
+    /// The API answered without any content.
+    #[error("No response from LLM")]
+    EmptyResponse,
+    /// The API answered with an error status.
+    #[error("LLM request failed with {status}: {body}")]
+    Api { status: StatusCode, body: String },
+    /// The reviewer has no prompt template with this name.
+    #[error("Missing prompt template: '{0}'")]
+    MissingPrompt(String),
+    /// The HTTP client couldn't be set up, e.g. because no TLS backend could be initialized.
+    #[error("Failed to build the HTTP client")]
+    HttpClient(#[source] reqwest::Error),
+}
+
+impl ReviewerError {
+    /// Whether trying the same call again might succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, ReviewerError::Network(e) if is_retryable(e))
+    }
+}
+
+/// Whether a request that failed with `e` might succeed if sent again. Timeouts and failures
+/// to connect or send might; a request that couldn't even be built never will.
+fn is_retryable(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.is_request()
+}
+
+impl From<reqwest::Error> for ReviewerError {
+    fn from(e: reqwest::Error) -> Self {
+        // A body that arrived but didn't decode is a bad reply, not a network problem.
+        if e.is_decode() {
+            ReviewerError::Deserialize(e.to_string())
+        } else {
+            ReviewerError::Network(e)
+        }
+    }
+}
+
+impl From<serde_json::Error> for ReviewerError {
+    fn from(e: serde_json::Error) -> Self {
+        ReviewerError::Deserialize(e.to_string())
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct LlmResponse {
@@ -43,30 +100,87 @@ pub trait Reviewer {
         &self,
         context_buffer: &str,
         new_segment: &str,
-    ) -> Result<String>;
+    ) -> Result<String, ReviewerError>;
 
-    async fn analyze_topic(&self, segment: &str, detected_subtopics: &[SubTopic])
-    -> Result<String>;
+    async fn analyze_topic(
+        &self,
+        segment: &str,
+        detected_subtopics: &[SubTopic],
+    ) -> Result<String, ReviewerError>;
 
-    async fn check_answer_satisfies_question(&self, segment: &str, question: &str) -> Result<bool>;
+    async fn check_answer_satisfies_question(
+        &self,
+        segment: &str,
+        question: &str,
+    ) -> Result<bool, ReviewerError>;
 
-    async fn generate_subtopics(&self, topic: &str) -> Result<Vec<String>>;
+    /// Asks for `count` subtopics of `topic`, returning at most that many distinct names.
+    async fn generate_subtopics(
+        &self,
+        topic: &str,
+        count: usize,
+    ) -> Result<Vec<String>, ReviewerError>;
 
     async fn analyze_last_explained_context(
         &self,
         segment: &str,
         main_topic: &str,
         subtopic_list: &[String],
-    ) -> Result<String>;
+    ) -> Result<String, ReviewerError>;
+
+    async fn analyze_answer(&self, question: &str, answer: &str) -> Result<bool, ReviewerError>;
 
-    async fn analyze_answer(&self, question: &str, answer: &str) -> Result<bool>;
+    /// Recaps a finished lesson for the learner: what they explained well and what was shaky.
+    async fn summarize_session(
+        &self,
+        topic: &str,
+        transcript: &str,
+        covered: &[String],
+        incomplete: &[String],
+    ) -> Result<String, ReviewerError>;
 }
 
+/// How many subtopics to ask for when the caller has no preference.
+pub const DEFAULT_SUBTOPIC_COUNT: usize = 8;
+
+/// The prompt templates the built-in reviewers render, by file stem.
+pub const REQUIRED_PROMPTS: &[&str] = &[
+    "looks_like_topic_change",
+    "analyze_topic",
+    "check_answer_satisfies_question",
+    "generate_subtopics",
+    "analyze_last_explained_context",
+    "analyze_answer",
+    "summarize_session",
+];
+
+/// The default OpenAI API base URL used by `ReviewerClient`.
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+const CHAT_COMPLETIONS_PATH: &str = "chat/completions";
+
+/// How many times a failed LLM call is retried by default.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+/// The delay before the first retry by default; later retries back off exponentially.
+pub const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// The longest the client backs off between retries, unless the server asks for longer.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+/// How long to wait for a connection to the LLM API by default.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a whole LLM request, including reading the reply, may take by default. A streamed
+/// reply may take longer, as long as no single read takes this long.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub struct ReviewerClient {
     client: Client,
+    /// Used for streamed replies, which a whole-request timeout would cut off mid-stream.
+    stream_client: Client,
     api_key: String,
     model: String,
-    prompts: HashMap<String, String>,
+    completions_url: String,
+    prompts: PromptStore,
+    max_retries: u32,
+    retry_base_delay: Duration,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -76,13 +190,419 @@ pub struct AnalysisOut {
 }
 
 impl ReviewerClient {
-    pub fn new(api_key: String, model: String, prompts: HashMap<String, String>) -> Self {
-        Self {
-            client: Client::new(),
+    pub fn new(
+        api_key: String,
+        model: String,
+        prompts: impl Into<PromptStore>,
+    ) -> Result<Self, ReviewerError> {
+        Ok(Self {
+            client: http_client(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT)?,
+            stream_client: streaming_http_client(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT)?,
             api_key,
             model,
-            prompts,
+            completions_url: format!("{DEFAULT_BASE_URL}/{CHAT_COMPLETIONS_PATH}"),
+            prompts: prompts.into(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+        })
+    }
+
+    /// Points the client at an OpenAI-compatible API instead of the default.
+    ///
+    /// Accepts either a base URL (e.g. `https://host/v1`, with or without a trailing slash)
+    /// or the full `.../chat/completions` endpoint. Returns an error if the URL is malformed.
+    pub fn with_base_url(mut self, base_url: &str) -> Result<Self> {
+        self.completions_url = normalize_completions_url(base_url)?;
+        Ok(self)
+    }
+
+    /// Replaces the underlying HTTP client, e.g. to configure timeouts or proxies.
+    ///
+    /// Streamed replies use it too, so a whole-request timeout set on it also limits those.
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.stream_client = client.clone();
+        self.client = client;
+        self
+    }
+
+    /// Sets how many times a call is retried after a 429, a 5xx, or a network error.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry. Each later retry waits twice as long.
+    pub fn with_retry_base_delay(mut self, retry_base_delay: Duration) -> Self {
+        self.retry_base_delay = retry_base_delay;
+        self
+    }
+
+    /// Sets how long to wait for a connection and for a whole request before giving up with
+    /// `ReviewerError::Network`. A streamed reply instead gives up once a single read takes
+    /// longer than `request_timeout`. Replaces any client set with `with_http_client`.
+    pub fn with_timeouts(
+        mut self,
+        connect_timeout: Duration,
+        request_timeout: Duration,
+    ) -> Result<Self, ReviewerError> {
+        self.client = http_client(connect_timeout, request_timeout)?;
+        self.stream_client = streaming_http_client(connect_timeout, request_timeout)?;
+        Ok(self)
+    }
+
+    /// The chat completions endpoint this client sends requests to.
+    pub fn completions_url(&self) -> &str {
+        &self.completions_url
+    }
+
+    /// Sends a chat completions request, retrying transient failures.
+    async fn post_with_retry(&self, body: serde_json::Value) -> Result<LlmResponse, ReviewerError> {
+        let request = || {
+            self.client
+                .post(&self.completions_url)
+                .bearer_auth(&self.api_key)
+                .json(&body)
+        };
+        let resp = send_with_retry(request, self.max_retries, self.retry_base_delay).await?;
+        Ok(resp.json::<LlmResponse>().await?)
+    }
+}
+
+/// Builds an HTTP client that gives up on connecting after `connect_timeout` and on a whole
+/// request after `request_timeout`. A timeout surfaces as `ReviewerError::Network`.
+pub(crate) fn http_client(
+    connect_timeout: Duration,
+    request_timeout: Duration,
+) -> Result<Client, ReviewerError> {
+    Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .build()
+        .map_err(ReviewerError::HttpClient)
+}
+
+/// Builds an HTTP client for streamed replies. It gives up on connecting after
+/// `connect_timeout` and when a single read takes longer than `read_timeout`, but has no
+/// limit on the whole request.
+fn streaming_http_client(
+    connect_timeout: Duration,
+    read_timeout: Duration,
+) -> Result<Client, ReviewerError> {
+    Client::builder()
+        .connect_timeout(connect_timeout)
+        .read_timeout(read_timeout)
+        .build()
+        .map_err(ReviewerError::HttpClient)
+}
+
+/// Sends the request built by `request` until it succeeds or can't be retried.
+///
+/// Rate limits (429), server errors (5xx), and retryable network errors are retried up to
+/// `max_retries` times with jittered exponential backoff, waiting for the server's
+/// `Retry-After` instead when it sends one. A `Retry-After` longer than `MAX_RETRY_DELAY`
+/// fails immediately rather than stalling the session. Other error statuses fail immediately.
+pub(crate) async fn send_with_retry(
+    request: impl Fn() -> reqwest::RequestBuilder,
+    max_retries: u32,
+    retry_base_delay: Duration,
+) -> Result<reqwest::Response, ReviewerError> {
+    let mut attempt = 0;
+    loop {
+        let (reason, retry_after) = match request().send().await {
+            Ok(resp) if resp.status().is_success() => return Ok(resp),
+            Ok(resp) => {
+                let status = resp.status();
+                let retryable = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+                let retry_after = retry_after(&resp);
+                let too_long = retry_after.is_some_and(|delay| delay > MAX_RETRY_DELAY);
+                if !retryable || too_long || attempt >= max_retries {
+                    let body = resp.text().await.unwrap_or_default();
+                    return Err(ReviewerError::Api { status, body });
+                }
+                (status.to_string(), retry_after)
+            }
+            Err(e) if is_retryable(&e) && attempt < max_retries => (e.to_string(), None),
+            Err(e) => return Err(e.into()),
+        };
+        attempt += 1;
+        let delay = retry_after.unwrap_or_else(|| backoff(retry_base_delay, attempt));
+        tracing::warn!("LLM request failed ({reason}); retry {attempt}/{max_retries} in {delay:?}");
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// The jittered delay before the given retry, counting from 1.
+fn backoff(retry_base_delay: Duration, attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    let delay = retry_base_delay.saturating_mul(factor).min(MAX_RETRY_DELAY);
+    // Spread retries over the upper half of the window so concurrent calls don't line up.
+    delay.mul_f64(0.5 + fastrand::f64() / 2.0)
+}
+
+/// Reads a `Retry-After` header.
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    let value = resp.headers().get(reqwest::header::RETRY_AFTER)?;
+    parse_retry_after(value.to_str().ok()?, SystemTime::now())
+}
+
+/// Parses a `Retry-After` value given either in seconds or as an HTTP date. A date that has
+/// already passed means retrying straight away.
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// Extracts the JSON value from an LLM reply, even when `response_format` is ignored.
+///
+/// Models sometimes wrap JSON in a markdown code fence (```` ```json ````) or add a sentence
+/// before or after it. This returns the text from the first `{` or `[` to the last `}` or `]`,
+/// looking inside the first code fence if there is one. Replies with no JSON-looking content
+/// are returned trimmed, so the caller's parse error still shows what the model said.
+pub(crate) fn extract_json(raw: &str) -> &str {
+    let mut text = raw.trim();
+    if let Some(start) = text.find("```") {
+        // Skip the fence and its language tag, then stop at the closing fence.
+        let body = text[start + 3..].trim_start_matches(|c: char| c.is_ascii_alphanumeric());
+        text = body.find("```").map_or(body, |end| &body[..end]).trim();
+    }
+    let start = text.find(['{', '[']);
+    let end = text.rfind(['}', ']']);
+    match (start, end) {
+        (Some(start), Some(end)) if start <= end => &text[start..=end],
+        _ => text,
+    }
+}
+
+/// Resolves a user-supplied base URL to the chat completions endpoint.
+fn normalize_completions_url(base_url: &str) -> Result<String> {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    let url = reqwest::Url::parse(trimmed)
+        .with_context(|| format!("Invalid reviewer base URL: '{base_url}'"))?;
+    if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+        anyhow::bail!("Invalid reviewer base URL: '{base_url}' must be an http(s) URL");
+    }
+
+    if trimmed.ends_with(CHAT_COMPLETIONS_PATH) {
+        Ok(trimmed.to_string())
+    } else {
+        Ok(format!("{trimmed}/{CHAT_COMPLETIONS_PATH}"))
+    }
+}
+
+/// What the reviewer says when asked to pick up from an empty explanation.
+pub(crate) const BLANK_SEGMENT_NUDGE: &str =
+    "You can continue explaining any part of the topic you'd like. Please keep going!";
+
+/// Fills in the named prompt template, replacing each `{key}` with its value.
+pub(crate) fn render_prompt(
+    prompts: &HashMap<String, String>,
+    name: &str,
+    vars: &[(&str, &str)],
+) -> Result<String, ReviewerError> {
+    let template = prompts
+        .get(name)
+        .ok_or_else(|| ReviewerError::MissingPrompt(name.to_string()))?;
+    Ok(vars.iter().fold(template.clone(), |prompt, (key, value)| {
+        prompt.replace(&format!("{{{key}}}"), value)
+    }))
+}
+
+/// Normalizes a topic analysis reply to a JSON array, wrapping a single object.
+pub(crate) fn normalize_topic_analysis(answer: &str) -> Result<String, ReviewerError> {
+    let json = extract_json(answer);
+    let json_val: serde_json::Value = serde_json::from_str(json)?;
+
+    // If the output is an object, wrap it in an array.
+    if json_val.is_array() {
+        Ok(json.to_string())
+    } else if json_val.is_object() {
+        Ok(format!("[{}]", json))
+    } else {
+        // Unexpected format
+        Err(ReviewerError::Deserialize(format!(
+            "LLM output is not an object or array: {}",
+            answer
+        )))
+    }
+}
+
+/// Reads a JSON reply of the form `{"<field>": true|false}`.
+pub(crate) fn parse_verdict(answer: &str, field: &str) -> Result<bool, ReviewerError> {
+    let result: serde_json::Value = serde_json::from_str(extract_json(answer))?;
+    result
+        .get(field)
+        .and_then(|v| v.as_bool())
+        .ok_or_else(|| ReviewerError::Deserialize(format!("Invalid LLM answer format: {answer}")))
+}
+
+/// Cleans up generated subtopic names: drops empty entries and case-insensitive duplicates,
+/// keeping the first spelling, and keeps at most `count` of them.
+pub(crate) fn dedup_subtopics(names: Vec<String>, count: usize) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    names
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && seen.insert(name.to_lowercase()))
+        .take(count)
+        .collect()
+}
+
+/// Fills in the `analyze_last_explained_context` template.
+pub(crate) fn last_explained_context_prompt(
+    prompts: &HashMap<String, String>,
+    segment: &str,
+    main_topic: &str,
+    subtopic_list: &[String],
+) -> Result<String, ReviewerError> {
+    let subtopics = subtopic_list.join(", ");
+    render_prompt(
+        prompts,
+        "analyze_last_explained_context",
+        &[
+            ("segment", segment),
+            ("main_topic", main_topic),
+            ("subtopics", &subtopics),
+        ],
+    )
+}
+
+/// Fills in the `summarize_session` template.
+pub(crate) fn summarize_session_prompt(
+    prompts: &HashMap<String, String>,
+    topic: &str,
+    transcript: &str,
+    covered: &[String],
+    incomplete: &[String],
+) -> Result<String, ReviewerError> {
+    render_prompt(
+        prompts,
+        "summarize_session",
+        &[
+            ("topic", topic),
+            ("transcript", transcript),
+            ("covered", &covered.join(", ")),
+            ("incomplete", &incomplete.join(", ")),
+        ],
+    )
+}
+
+/// One meaningful line of a streamed chat completion.
+#[derive(Debug, PartialEq)]
+enum StreamLine {
+    /// A piece of the reply's text.
+    Delta(String),
+    /// The `[DONE]` marker that ends the stream.
+    Done,
+}
+
+/// Parses a server-sent event line from a streamed chat completion.
+/// Returns `None` for blank lines, comments, and chunks that carry no text.
+fn parse_stream_line(line: &str) -> Result<Option<StreamLine>, ReviewerError> {
+    let Some(data) = line.trim().strip_prefix("data:") else {
+        return Ok(None);
+    };
+    let data = data.trim();
+    if data == "[DONE]" {
+        return Ok(Some(StreamLine::Done));
+    }
+    let chunk: serde_json::Value = serde_json::from_str(data).map_err(|e| {
+        ReviewerError::Deserialize(format!("Failed to parse streamed LLM chunk {data}: {e}"))
+    })?;
+    let content = chunk["choices"][0]["delta"]["content"]
+        .as_str()
+        .unwrap_or_default();
+    Ok((!content.is_empty()).then(|| StreamLine::Delta(content.to_string())))
+}
+
+impl ReviewerClient {
+    /// Sends a single-message chat completion and returns the first choice's content.
+    async fn complete(
+        &self,
+        prompt: String,
+        options: serde_json::Value,
+    ) -> Result<String, ReviewerError> {
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "messages": [
+                { "role": "user", "content": prompt }
+            ]
+        });
+        if let (Some(body), Some(options)) = (body.as_object_mut(), options.as_object()) {
+            body.extend(options.clone());
         }
+
+        let resp = self.post_with_retry(body).await?;
+
+        let answer = resp
+            .choices
+            .into_iter()
+            .next()
+            .ok_or(ReviewerError::EmptyResponse)?
+            .message
+            .content;
+        Ok(answer)
+    }
+
+    /// Like `Reviewer::analyze_last_explained_context`, but sends the reply over `tx` piece by
+    /// piece as the model streams it, so the runtime can start speaking before it's complete.
+    ///
+    /// Returns once the stream ends. If the receiver is dropped, streaming stops early.
+    pub async fn analyze_last_explained_context_streaming(
+        &self,
+        segment: &str,
+        main_topic: &str,
+        subtopic_list: &[String],
+        tx: tokio::sync::mpsc::Sender<String>,
+    ) -> Result<(), ReviewerError> {
+        // If segment is blank, nudge user to continue
+        if segment.trim().is_empty() {
+            let _ = tx.send(BLANK_SEGMENT_NUDGE.to_string()).await;
+            return Ok(());
+        }
+
+        let prompt = last_explained_context_prompt(
+            &self.prompts.read(),
+            segment,
+            main_topic,
+            subtopic_list,
+        )?;
+        let body = serde_json::json!({
+            "model": self.model,
+            "messages": [
+                { "role": "user", "content": prompt }
+            ],
+            "temperature": 0.2,
+            "stream": true
+        });
+        let request = || {
+            self.stream_client
+                .post(&self.completions_url)
+                .bearer_auth(&self.api_key)
+                .json(&body)
+        };
+        let mut resp = send_with_retry(request, self.max_retries, self.retry_base_delay).await?;
+
+        // Events arrive as `data: {...}` lines, which may be split across chunks.
+        let mut pending: Vec<u8> = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            pending.extend_from_slice(&chunk);
+            while let Some(newline) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=newline).collect();
+                let text = match parse_stream_line(&String::from_utf8_lossy(&line))? {
+                    Some(StreamLine::Delta(text)) => text,
+                    Some(StreamLine::Done) => return Ok(()),
+                    None => continue,
+                };
+                if tx.send(text).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
     }
 }
 
@@ -97,196 +617,78 @@ impl Reviewer for ReviewerClient {
         &self,
         context_buffer: &str,
         new_segment: &str,
-    ) -> Result<String> {
-        let prompt_template = self
-            .prompts
-            .get("looks_like_topic_change")
-            .context("Missing prompt template: 'looks_like_topic_change'")?;
-        let prompt = prompt_template
-            .replace("{context_buffer}", context_buffer)
-            .replace("{new_segment}", new_segment);
-
-        let body = serde_json::json!({
-            "model": self.model,
-            "messages": [
-                {
-                    "role": "user",
-                    "content": prompt
-                }
-            ]
-        });
-
-        let resp = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .bearer_auth(&self.api_key)
-            .json(&body)
-            .send()
-            .await?
-            .json::<LlmResponse>()
-            .await?;
-
-        let answer = &resp
-            .choices
-            .get(0)
-            .ok_or_else(|| anyhow::anyhow!("No response from LLM"))?
-            .message
-            .content;
-        Ok(answer.clone())
+    ) -> Result<String, ReviewerError> {
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "looks_like_topic_change",
+            &[
+                ("context_buffer", context_buffer),
+                ("new_segment", new_segment),
+            ],
+        )?;
+        self.complete(prompt, serde_json::json!({})).await
     }
 
     async fn analyze_topic(
         &self,
         segment: &str,
         detected_subtopics: &[SubTopic],
-    ) -> Result<String> {
+    ) -> Result<String, ReviewerError> {
         let subtopic_names = detected_subtopics
             .iter()
             .map(|s| s.name.as_str())
             .collect::<Vec<_>>()
             .join(", ");
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "analyze_topic",
+            &[("subtopic_names", &subtopic_names), ("segment", segment)],
+        )?;
 
-        let prompt_template = self
-            .prompts
-            .get("analyze_topic")
-            .context("Missing prompt template: 'analyze_topic'")?;
-        let prompt = prompt_template
-            .replace("{subtopic_names}", &subtopic_names)
-            .replace("{segment}", segment);
-
-        let body = serde_json::json!({
-            "model": self.model,
-            "messages": [
-                { "role": "user", "content": prompt }
-            ],
+        let options = serde_json::json!({
             "response_format": { "type": "json_object" },
             "temperature": 0.2
         });
-
-        let resp = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .bearer_auth(&self.api_key)
-            .json(&body)
-            .send()
-            .await?
-            .json::<LlmResponse>()
-            .await?;
-
-        let answer = &resp
-            .choices
-            .get(0)
-            .ok_or_else(|| anyhow::anyhow!("No response from LLM"))?
-            .message
-            .content;
-        let json_val: serde_json::Value = serde_json::from_str(answer)
-            .map_err(|e| anyhow::anyhow!("Failed to parse LLM response: {e}"))?;
-
-        // If the output is an object, wrap it in an array.
-        let normalized = if json_val.is_array() {
-            answer.trim().to_string()
-        } else if json_val.is_object() {
-            format!("[{}]", answer.trim())
-        } else {
-            // Unexpected format
-            return Err(anyhow::anyhow!(
-                "LLM output is not an object or array: {}",
-                answer
-            ));
-        };
-
-        Ok(normalized)
+        let answer = self.complete(prompt, options).await?;
+        normalize_topic_analysis(&answer)
     }
-    async fn check_answer_satisfies_question(&self, segment: &str, question: &str) -> Result<bool> {
-        let prompt_template = self
-            .prompts
-            .get("check_answer_satisfies_question")
-            .context("Missing prompt template: 'check_answer_satisfies_question'")?;
-        let prompt = prompt_template
-            .replace("{segment}", segment)
-            .replace("{question}", question);
 
-        let body = serde_json::json!({
-            "model": self.model,
-            "messages": [
-                { "role": "user", "content": prompt }
-            ],
+    async fn check_answer_satisfies_question(
+        &self,
+        segment: &str,
+        question: &str,
+    ) -> Result<bool, ReviewerError> {
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "check_answer_satisfies_question",
+            &[("segment", segment), ("question", question)],
+        )?;
+
+        let options = serde_json::json!({
             "response_format": { "type": "json_object" },
             "temperature": 0.0 // be as deterministic as possible
         });
-
-        let resp = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .bearer_auth(&self.api_key)
-            .json(&body)
-            .send()
-            .await?
-            .json::<LlmResponse>()
-            .await?;
-
-        let answer = &resp
-            .choices
-            .get(0)
-            .ok_or_else(|| anyhow::anyhow!("No response from LLM"))?
-            .message
-            .content;
+        let answer = self.complete(prompt, options).await?;
 
         // Parse the JSON, expecting {"satisfies": true/false}
-        let result: serde_json::Value = serde_json::from_str(answer)?;
-        let satisfies = result
-            .get("satisfies")
-            .and_then(|v| v.as_bool())
-            .ok_or_else(|| anyhow::anyhow!("Invalid LLM answer format: {}", answer))?;
-
-        Ok(satisfies)
+        parse_verdict(&answer, "satisfies")
     }
-    async fn generate_subtopics(&self, topic: &str) -> Result<Vec<String>> {
-        let prompt_template = self
-            .prompts
-            .get("generate_subtopics")
-            .context("Missing prompt template: 'generate_subtopics'")?;
-        let prompt = prompt_template.replace("{topic}", topic);
 
-        let body = serde_json::json!({
-            "model": self.model,
-            "messages": [
-                { "role": "user", "content": prompt }
-            ]
-        });
-
-        let resp = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .bearer_auth(&self.api_key)
-            .json(&body)
-            .send()
-            .await?
-            .json::<LlmResponse>()
-            .await?;
-
-        let answer = &resp
-            .choices
-            .get(0)
-            .ok_or_else(|| anyhow::anyhow!("No response from LLM"))?
-            .message
-            .content;
+    async fn generate_subtopics(
+        &self,
+        topic: &str,
+        count: usize,
+    ) -> Result<Vec<String>, ReviewerError> {
+        let count_str = count.to_string();
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "generate_subtopics",
+            &[("topic", topic), ("count", &count_str)],
+        )?;
+        let answer = self.complete(prompt, serde_json::json!({})).await?;
 
         // Parse numbered list, extract subtopic names
-        let subtopics: Vec<String> = answer
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if let Some(idx) = line.find('.') {
-                    Some(line[idx + 1..].trim().to_string())
-                } else {
-                    None
-                }
-            })
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        Ok(subtopics)
+        Ok(dedup_subtopics(parse_numbered_list(&answer), count))
     }
 
     async fn analyze_last_explained_context(
@@ -294,97 +696,242 @@ impl Reviewer for ReviewerClient {
         segment: &str,
         main_topic: &str,
         subtopic_list: &[String],
-    ) -> Result<String> {
+    ) -> Result<String, ReviewerError> {
         // If segment is blank, nudge user to continue
         if segment.trim().is_empty() {
-            return Ok(
-                "You can continue explaining any part of the topic you'd like. Please keep going!"
-                    .to_string(),
-            );
+            return Ok(BLANK_SEGMENT_NUDGE.to_string());
         }
 
-        let subtopics = subtopic_list.join(", ");
-        let prompt_template = self
-            .prompts
-            .get("analyze_last_explained_context")
-            .context("Missing prompt template: 'analyze_last_explained_context'")?;
-        let prompt = prompt_template
-            .replace("{segment}", segment)
-            .replace("{main_topic}", main_topic)
-            .replace("{subtopics}", &subtopics);
+        let prompt = last_explained_context_prompt(
+            &self.prompts.read(),
+            segment,
+            main_topic,
+            subtopic_list,
+        )?;
 
-        let body = serde_json::json!({
-            "model": self.model,
-            "messages": [
-                { "role": "user", "content": prompt }
-            ],
+        let options = serde_json::json!({
             "response_format": { "type": "text" }, // Text: not JSON, just message.
             "temperature": 0.2
         });
-
-        let resp = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .bearer_auth(&self.api_key)
-            .json(&body)
-            .send()
-            .await?
-            .json::<LlmResponse>()
-            .await?;
-
-        let answer = &resp
-            .choices
-            .get(0)
-            .ok_or_else(|| anyhow::anyhow!("No response from LLM"))?
-            .message
-            .content;
-
+        let answer = self.complete(prompt, options).await?;
         Ok(answer.trim().to_string())
     }
 
-    async fn analyze_answer(&self, question: &str, answer: &str) -> Result<bool> {
-        let prompt_template = self
-            .prompts
-            .get("analyze_answer")
-            .context("Missing prompt template: 'analyze_answer'")?;
-        let prompt = prompt_template
-            .replace("{question}", question)
-            .replace("{answer}", answer);
+    async fn analyze_answer(&self, question: &str, answer: &str) -> Result<bool, ReviewerError> {
+        let prompt = render_prompt(
+            &self.prompts.read(),
+            "analyze_answer",
+            &[("question", question), ("answer", answer)],
+        )?;
 
-        let body = serde_json::json!({
-            "model": self.model,
-            "messages": [
-                { "role": "user", "content": prompt }
-            ],
+        let options = serde_json::json!({
             "response_format": { "type": "json_object" },
             "temperature": 0.1 // Low temperature for consistent evaluation
         });
-
-        let resp = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .bearer_auth(&self.api_key)
-            .json(&body)
-            .send()
-            .await?
-            .json::<LlmResponse>()
-            .await?;
-
-        let answer = &resp
-            .choices
-            .get(0)
-            .ok_or_else(|| anyhow::anyhow!("No response from LLM"))?
-            .message
-            .content;
+        let answer = self.complete(prompt, options).await?;
 
         // Parse the JSON, expecting {"correct": true/false}
-        let result: serde_json::Value = serde_json::from_str(answer)?;
-        let is_correct = result
-            .get("correct")
-            .and_then(|v| v.as_bool())
-            .ok_or_else(|| anyhow::anyhow!("Invalid LLM answer format: {}", answer))?;
+        parse_verdict(&answer, "correct")
+    }
 
-        Ok(is_correct)
+    async fn summarize_session(
+        &self,
+        topic: &str,
+        transcript: &str,
+        covered: &[String],
+        incomplete: &[String],
+    ) -> Result<String, ReviewerError> {
+        let prompt =
+            summarize_session_prompt(&self.prompts.read(), topic, transcript, covered, incomplete)?;
+        let options = serde_json::json!({
+            "response_format": { "type": "text" },
+            "temperature": 0.3
+        });
+        let answer = self.complete(prompt, options).await?;
+        Ok(answer.trim().to_string())
+    }
+}
+
+/// The most replies a `CachingReviewer` keeps by default.
+pub const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+/// A `Reviewer` that remembers successful replies and returns them for identical calls.
+///
+/// Calls are keyed by the method name and all of its arguments. Errors are never cached,
+/// so a failed call is tried again next time. At most `capacity` replies are kept, dropping
+/// the oldest first. Wraps any reviewer, so it's a drop-in replacement wherever an
+/// `Arc<dyn Reviewer>` is used.
+pub struct CachingReviewer {
+    inner: Arc<dyn Reviewer + Send + Sync>,
+    ttl: Duration,
+    capacity: usize,
+    entries: CorrelationMap<String, CachedReply>,
+}
+
+#[derive(Clone)]
+enum CachedReply {
+    Text(String),
+    Verdict(bool),
+    List(Vec<String>),
+}
+
+impl CachingReviewer {
+    /// Caches `inner`'s replies for as long as this reviewer lives.
+    pub fn new(inner: Arc<dyn Reviewer + Send + Sync>) -> Self {
+        Self {
+            inner,
+            ttl: Duration::MAX,
+            capacity: DEFAULT_CACHE_CAPACITY,
+            entries: CorrelationMap::new(Duration::MAX, DEFAULT_CACHE_CAPACITY),
+        }
+    }
+
+    /// Forgets each reply once it is older than `ttl`.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self.entries = CorrelationMap::new(self.ttl, self.capacity);
+        self
+    }
+
+    /// Keeps at most `capacity` replies.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self.entries = CorrelationMap::new(self.ttl, self.capacity);
+        self
+    }
+
+    // The whole call, serialized, so distinct calls can never share an entry.
+    fn cache_key(call: impl serde::Serialize) -> String {
+        serde_json::to_string(&call).expect("reviewer arguments serialize to JSON")
+    }
+
+    fn lookup(&self, key: &String) -> Option<CachedReply> {
+        self.entries.get(key)
+    }
+
+    fn store(&self, key: String, reply: CachedReply) {
+        self.entries.insert(key, reply);
+    }
+
+    async fn text<F>(&self, key: String, call: impl FnOnce() -> F) -> Result<String, ReviewerError>
+    where
+        F: Future<Output = Result<String, ReviewerError>>,
+    {
+        if let Some(CachedReply::Text(text)) = self.lookup(&key) {
+            return Ok(text);
+        }
+        let text = call().await?;
+        self.store(key, CachedReply::Text(text.clone()));
+        Ok(text)
+    }
+
+    async fn verdict<F>(&self, key: String, call: impl FnOnce() -> F) -> Result<bool, ReviewerError>
+    where
+        F: Future<Output = Result<bool, ReviewerError>>,
+    {
+        if let Some(CachedReply::Verdict(verdict)) = self.lookup(&key) {
+            return Ok(verdict);
+        }
+        let verdict = call().await?;
+        self.store(key, CachedReply::Verdict(verdict));
+        Ok(verdict)
+    }
+}
+
+#[async_trait]
+impl Reviewer for CachingReviewer {
+    async fn looks_like_topic_change(
+        &self,
+        context_buffer: &str,
+        new_segment: &str,
+    ) -> Result<String, ReviewerError> {
+        let key = Self::cache_key(("looks_like_topic_change", context_buffer, new_segment));
+        let call = || {
+            self.inner
+                .looks_like_topic_change(context_buffer, new_segment)
+        };
+        self.text(key, call).await
+    }
+
+    async fn analyze_topic(
+        &self,
+        segment: &str,
+        detected_subtopics: &[SubTopic],
+    ) -> Result<String, ReviewerError> {
+        let subtopics: Vec<_> = detected_subtopics
+            .iter()
+            .map(|s| (&s.name, s.has_definition, s.has_mechanism, s.has_example))
+            .collect();
+        let key = Self::cache_key(("analyze_topic", segment, subtopics));
+        let call = || self.inner.analyze_topic(segment, detected_subtopics);
+        self.text(key, call).await
+    }
+
+    async fn check_answer_satisfies_question(
+        &self,
+        segment: &str,
+        question: &str,
+    ) -> Result<bool, ReviewerError> {
+        let key = Self::cache_key(("check_answer_satisfies_question", segment, question));
+        let call = || {
+            self.inner
+                .check_answer_satisfies_question(segment, question)
+        };
+        self.verdict(key, call).await
+    }
+
+    async fn generate_subtopics(
+        &self,
+        topic: &str,
+        count: usize,
+    ) -> Result<Vec<String>, ReviewerError> {
+        let key = Self::cache_key(("generate_subtopics", topic, count));
+        if let Some(CachedReply::List(subtopics)) = self.lookup(&key) {
+            return Ok(subtopics);
+        }
+        let subtopics = self.inner.generate_subtopics(topic, count).await?;
+        self.store(key, CachedReply::List(subtopics.clone()));
+        Ok(subtopics)
+    }
+
+    async fn analyze_last_explained_context(
+        &self,
+        segment: &str,
+        main_topic: &str,
+        subtopic_list: &[String],
+    ) -> Result<String, ReviewerError> {
+        let key = Self::cache_key((
+            "analyze_last_explained_context",
+            segment,
+            main_topic,
+            subtopic_list,
+        ));
+        let call = || {
+            self.inner
+                .analyze_last_explained_context(segment, main_topic, subtopic_list)
+        };
+        self.text(key, call).await
+    }
+
+    async fn analyze_answer(&self, question: &str, answer: &str) -> Result<bool, ReviewerError> {
+        let key = Self::cache_key(("analyze_answer", question, answer));
+        let call = || self.inner.analyze_answer(question, answer);
+        self.verdict(key, call).await
+    }
+
+    async fn summarize_session(
+        &self,
+        topic: &str,
+        transcript: &str,
+        covered: &[String],
+        incomplete: &[String],
+    ) -> Result<String, ReviewerError> {
+        let key = Self::cache_key(("summarize_session", topic, transcript, covered, incomplete));
+        let call = || {
+            self.inner
+                .summarize_session(topic, transcript, covered, incomplete)
+        };
+        self.text(key, call).await
     }
 }
 
@@ -394,6 +941,531 @@ mod tests {
     use crate::topic::SubTopic;
     use std::env;
 
+    fn client_with_base_url(base_url: &str) -> Result<ReviewerClient> {
+        ReviewerClient::new("key".to_string(), "gpt-4o".to_string(), HashMap::new())?
+            .with_base_url(base_url)
+    }
+
+    #[test]
+    fn test_base_url_variants_resolve_to_same_endpoint() {
+        let expected = "https://x/v1/chat/completions";
+        for base_url in [
+            "https://x/v1",
+            "https://x/v1/",
+            "https://x/v1/chat/completions",
+            "https://x/v1/chat/completions/",
+        ] {
+            let reviewer = client_with_base_url(base_url).unwrap();
+            assert_eq!(reviewer.completions_url(), expected, "for {base_url}");
+        }
+    }
+
+    #[test]
+    fn test_default_base_url() {
+        let reviewer =
+            ReviewerClient::new("key".to_string(), "gpt-4o".to_string(), HashMap::new()).unwrap();
+        assert_eq!(
+            reviewer.completions_url(),
+            "https://api.openai.com/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_malformed_base_url_is_rejected() {
+        assert!(client_with_base_url("not a url").is_err());
+        assert!(client_with_base_url("ftp://x/v1").is_err());
+        assert!(client_with_base_url("").is_err());
+    }
+
+    #[test]
+    fn test_extract_json_unfenced() {
+        assert_eq!(extract_json(r#" {"a": 1} "#), r#"{"a": 1}"#);
+        assert_eq!(extract_json("[1, 2]"), "[1, 2]");
+    }
+
+    #[test]
+    fn test_extract_json_fenced() {
+        let raw = "```json\n{\"status\": \"ok\"}\n```";
+        assert_eq!(extract_json(raw), r#"{"status": "ok"}"#);
+        assert_eq!(extract_json("```\n[1]\n```"), "[1]");
+        assert_eq!(extract_json(r#"```{"a": 1}```"#), r#"{"a": 1}"#);
+    }
+
+    #[test]
+    fn test_extract_json_with_surrounding_prose() {
+        let raw = "Sure! Here is the analysis:\n```json\n{\"satisfies\": true}\n```\nLet me know.";
+        assert_eq!(extract_json(raw), r#"{"satisfies": true}"#);
+        let raw = r#"The answer is {"correct": false} based on the question."#;
+        assert_eq!(extract_json(raw), r#"{"correct": false}"#);
+    }
+
+    #[test]
+    fn test_extract_json_without_json_returns_trimmed_text() {
+        assert_eq!(extract_json("  not json  "), "not json");
+        assert_eq!(extract_json("42"), "42");
+    }
+
+    #[test]
+    fn test_parse_stream_line() {
+        let delta = r#"data: {"choices":[{"delta":{"content":"Hel"}}]}"#;
+        assert_eq!(
+            parse_stream_line(delta).unwrap(),
+            Some(StreamLine::Delta("Hel".to_string()))
+        );
+        assert_eq!(
+            parse_stream_line("data: [DONE]\n").unwrap(),
+            Some(StreamLine::Done)
+        );
+        let role_only = r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#;
+        assert_eq!(parse_stream_line(role_only).unwrap(), None);
+        assert_eq!(parse_stream_line("\n").unwrap(), None);
+        assert!(parse_stream_line("data: {oops").is_err());
+    }
+
+    #[test]
+    fn test_dedup_subtopics_cleans_messy_list() {
+        let answer = "Sure, here are the subtopics:\n\
+                      1. Memory\n\
+                      2.   \n\
+                      3. Processes\n\
+                      4. memory \n\
+                      5. Memory Management\n\
+                      6. PROCESSES\n\
+                      7. Scheduling";
+        let subtopics = dedup_subtopics(parse_numbered_list(answer), 10);
+        assert_eq!(
+            subtopics,
+            vec!["Memory", "Processes", "Memory Management", "Scheduling"]
+        );
+        assert_eq!(
+            dedup_subtopics(parse_numbered_list(answer), 2),
+            vec!["Memory", "Processes"]
+        );
+    }
+
+    /// Starts a mock chat completions server that replies with `content` as the LLM answer,
+    /// and returns a reviewer pointed at it.
+    async fn mock_reviewer(content: &str) -> (wiremock::MockServer, ReviewerClient) {
+        let body = serde_json::json!({
+            "choices": [{ "message": { "content": content } }]
+        });
+        mock_reviewer_with(wiremock::ResponseTemplate::new(200).set_body_json(body)).await
+    }
+
+    async fn mock_reviewer_with(
+        response: wiremock::ResponseTemplate,
+    ) -> (wiremock::MockServer, ReviewerClient) {
+        use wiremock::matchers::{method, path};
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+
+        let prompts = HashMap::from([
+            (
+                "analyze_topic".to_string(),
+                "{subtopic_names}: {segment}".to_string(),
+            ),
+            ("generate_subtopics".to_string(), "{topic}".to_string()),
+        ]);
+        let reviewer = ReviewerClient::new("key".to_string(), "gpt-4o".to_string(), prompts)
+            .unwrap()
+            .with_http_client(Client::new())
+            .with_retry_base_delay(Duration::from_millis(1))
+            .with_base_url(&format!("{}/v1", server.uri()))
+            .unwrap();
+        (server, reviewer)
+    }
+
+    #[tokio::test]
+    async fn test_analyze_topic_wraps_object_in_array() {
+        let (_server, reviewer) = mock_reviewer(r#"{"status": "ok", "questions": []}"#).await;
+        let result = reviewer.analyze_topic("segment", &[]).await.unwrap();
+        assert_eq!(result, r#"[{"status": "ok", "questions": []}]"#);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_topic_passes_array_through() {
+        let (_server, reviewer) = mock_reviewer(r#" [{"status": "ask"}] "#).await;
+        let result = reviewer.analyze_topic("segment", &[]).await.unwrap();
+        assert_eq!(result, r#"[{"status": "ask"}]"#);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_topic_accepts_fenced_output() {
+        let (_server, reviewer) = mock_reviewer("```json\n{\"status\": \"ok\"}\n```").await;
+        let result = reviewer.analyze_topic("segment", &[]).await.unwrap();
+        assert_eq!(result, r#"[{"status": "ok"}]"#);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_topic_rejects_non_object_output() {
+        let (_server, reviewer) = mock_reviewer("42").await;
+        assert!(matches!(
+            reviewer.analyze_topic("segment", &[]).await,
+            Err(ReviewerError::Deserialize(_))
+        ));
+
+        let (_server, reviewer) = mock_reviewer("not json").await;
+        assert!(matches!(
+            reviewer.analyze_topic("segment", &[]).await,
+            Err(ReviewerError::Deserialize(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_generate_subtopics_parses_numbered_list() {
+        let (_server, reviewer) =
+            mock_reviewer("Here you go:\n1. Processes\n2.  Threads \n\n3.\n10. Memory").await;
+        let subtopics = reviewer.generate_subtopics("OS", 10).await.unwrap();
+        assert_eq!(subtopics, vec!["Processes", "Threads", "Memory"]);
+    }
+
+    #[tokio::test]
+    async fn test_empty_choices_is_an_error() {
+        let body = serde_json::json!({ "choices": [] });
+        let (_server, reviewer) =
+            mock_reviewer_with(wiremock::ResponseTemplate::new(200).set_body_json(body)).await;
+        assert!(matches!(
+            reviewer.generate_subtopics("OS", 10).await,
+            Err(ReviewerError::EmptyResponse)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_error_response_is_an_error() {
+        let (_server, reviewer) = mock_reviewer_with(
+            wiremock::ResponseTemplate::new(500).set_body_string("internal error"),
+        )
+        .await;
+        let reviewer = reviewer.with_max_retries(0);
+        match reviewer.analyze_topic("segment", &[]).await {
+            Err(ReviewerError::Api { status, body }) => {
+                assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+                assert_eq!(body, "internal error");
+            }
+            other => panic!("Expected an API error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transient_errors_are_retried() {
+        use wiremock::matchers::method;
+
+        let body = serde_json::json!({
+            "choices": [{ "message": { "content": "1. Processes" } }]
+        });
+        let (server, reviewer) =
+            mock_reviewer_with(wiremock::ResponseTemplate::new(200).set_body_json(body)).await;
+        // Higher priority than the success response, until each is used up.
+        wiremock::Mock::given(method("POST"))
+            .respond_with(wiremock::ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(method("POST"))
+            .respond_with(wiremock::ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .with_priority(2)
+            .mount(&server)
+            .await;
+
+        let subtopics = reviewer.generate_subtopics("OS", 10).await.unwrap();
+        assert_eq!(subtopics, vec!["Processes"]);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retries_give_up_after_max_retries() {
+        let (server, reviewer) = mock_reviewer_with(wiremock::ResponseTemplate::new(500)).await;
+        let reviewer = reviewer.with_max_retries(2);
+        assert!(reviewer.generate_subtopics("OS", 10).await.is_err());
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_long_retry_after_gives_up() {
+        let (server, reviewer) = mock_reviewer_with(
+            wiremock::ResponseTemplate::new(429).insert_header("Retry-After", "3600"),
+        )
+        .await;
+        let err = reviewer.generate_subtopics("OS", 10).await.unwrap_err();
+        assert!(matches!(err, ReviewerError::Api { status, .. } if status == 429));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert_eq!(
+            parse_retry_after(" 2.5 ", now),
+            Some(Duration::from_millis(2500))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        // A date in the past means now.
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_unbuildable_requests_are_not_retried() {
+        let client = Client::new();
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let request = || {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            client.post("not a url")
+        };
+        match send_with_retry(request, 3, Duration::from_millis(1)).await {
+            Err(e @ ReviewerError::Network(_)) => assert!(!e.is_transient()),
+            other => panic!("Expected a network error, got {other:?}"),
+        }
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_network_error_cause_is_reported_once() {
+        let cause = Client::new().post("not a url").send().await.unwrap_err();
+        let cause_message = cause.to_string();
+        let err = anyhow::Error::new(ReviewerError::from(cause));
+        let chain = format!("{err:#}");
+        assert!(chain.starts_with("Network error talking to the LLM: "));
+        assert_eq!(chain.matches(&cause_message).count(), 1, "{chain}");
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (server, reviewer) = mock_reviewer_with(wiremock::ResponseTemplate::new(400)).await;
+        let err = reviewer.generate_subtopics("OS", 10).await.unwrap_err();
+        assert!(matches!(err, ReviewerError::Api { status, .. } if status == 400));
+        assert!(!err.is_transient());
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_caching_reviewer_reuses_generated_subtopics() {
+        let mut inner = MockReviewer::new();
+        inner
+            .expect_generate_subtopics()
+            .times(1)
+            .return_once(|_, _| {
+                Box::pin(async { Ok(vec!["Processes".to_string(), "Threads".to_string()]) })
+            });
+        let reviewer = CachingReviewer::new(Arc::new(inner));
+
+        let first = reviewer.generate_subtopics("OS", 2).await.unwrap();
+        let second = reviewer.generate_subtopics("OS", 2).await.unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_caching_reviewer_expires_entries_and_skips_errors() {
+        let mut inner = MockReviewer::new();
+        let mut calls = 0;
+        inner
+            .expect_analyze_answer()
+            .times(3)
+            .returning(move |_, _| {
+                calls += 1;
+                if calls == 1 {
+                    Box::pin(async { Err(ReviewerError::EmptyResponse) })
+                } else {
+                    Box::pin(async { Ok(true) })
+                }
+            });
+        let reviewer = CachingReviewer::new(Arc::new(inner)).with_ttl(Duration::ZERO);
+
+        assert!(reviewer.analyze_answer("q", "a").await.is_err());
+        assert!(reviewer.analyze_answer("q", "a").await.unwrap());
+        assert!(reviewer.analyze_answer("q", "a").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_caching_reviewer_keeps_distinct_calls_apart_and_is_bounded() {
+        let mut inner = MockReviewer::new();
+        inner
+            .expect_analyze_answer()
+            .times(3)
+            .returning(|question, _| {
+                let verdict = question == "q1";
+                Box::pin(async move { Ok(verdict) })
+            });
+        let reviewer = CachingReviewer::new(Arc::new(inner)).with_capacity(1);
+
+        assert!(reviewer.analyze_answer("q1", "a").await.unwrap());
+        assert!(reviewer.analyze_answer("q1", "a").await.unwrap());
+        // A different call evicts the only entry, so q1 has to be asked again.
+        assert!(!reviewer.analyze_answer("q2", "a").await.unwrap());
+        assert!(reviewer.analyze_answer("q1", "a").await.unwrap());
+    }
+
+    #[test]
+    fn test_backoff_is_jittered_and_capped() {
+        let base = Duration::from_millis(100);
+        for _ in 0..20 {
+            let first = backoff(base, 1);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+            let third = backoff(base, 3);
+            assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+            assert!(backoff(base, 30) <= MAX_RETRY_DELAY);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_analyze_last_explained_context_streaming_forwards_deltas() {
+        let sse = [
+            r#"{"choices":[{"delta":{"role":"assistant"}}]}"#,
+            r#"{"choices":[{"delta":{"content":"You covered "}}]}"#,
+            r#"{"choices":[{"delta":{"content":"processes."}}]}"#,
+        ]
+        .iter()
+        .map(|chunk| format!("data: {chunk}\n\n"))
+        .collect::<String>()
+            + "data: [DONE]\n\n";
+        let (server, reviewer) = mock_reviewer_with(
+            wiremock::ResponseTemplate::new(200).set_body_raw(sse, "text/event-stream"),
+        )
+        .await;
+        let reviewer = ReviewerClient {
+            prompts: HashMap::from([(
+                "analyze_last_explained_context".to_string(),
+                "{main_topic}: {segment}".to_string(),
+            )])
+            .into(),
+            ..reviewer
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        reviewer
+            .analyze_last_explained_context_streaming("processes", "OS", &[], tx)
+            .await
+            .unwrap();
+
+        let mut deltas = Vec::new();
+        while let Some(delta) = rx.recv().await {
+            deltas.push(delta);
+        }
+        assert_eq!(deltas, vec!["You covered ", "processes."]);
+        let body: serde_json::Value = server.received_requests().await.unwrap()[0]
+            .body_json()
+            .unwrap();
+        assert_eq!(body["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn test_missing_prompt_template_is_an_error() {
+        let (_server, reviewer) = mock_reviewer("{}").await;
+        assert!(matches!(
+            reviewer.analyze_answer("question", "answer").await,
+            Err(ReviewerError::MissingPrompt(name)) if name == "analyze_answer"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_outlasts_request_timeout() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        const REQUEST_TIMEOUT: Duration = Duration::from_millis(1500);
+
+        // Streams four deltas 500ms apart: longer than the 1.5s timeout in total, but with a
+        // wide margin between reads, so a slow machine doesn't trip it.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await.unwrap();
+            socket
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\
+                      transfer-encoding: chunked\r\n\r\n",
+                )
+                .await
+                .unwrap();
+            for text in ["one ", "two ", "three ", "four"] {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                let event = format!(
+                    "data: {}\n\n",
+                    serde_json::json!({ "choices": [{ "delta": { "content": text } }] })
+                );
+                let chunk = format!("{:x}\r\n{event}\r\n", event.len());
+                socket.write_all(chunk.as_bytes()).await.unwrap();
+            }
+            socket.write_all(b"0\r\n\r\n").await.unwrap();
+        });
+
+        let prompts = HashMap::from([(
+            "analyze_last_explained_context".to_string(),
+            "{main_topic}: {segment}".to_string(),
+        )]);
+        let reviewer = ReviewerClient::new("key".to_string(), "gpt-4o".to_string(), prompts)
+            .unwrap()
+            .with_max_retries(0)
+            .with_timeouts(Duration::from_secs(1), REQUEST_TIMEOUT)
+            .unwrap()
+            .with_base_url(&base_url)
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        reviewer
+            .analyze_last_explained_context_streaming("processes", "OS", &[], tx)
+            .await
+            .unwrap();
+        let mut deltas = Vec::new();
+        while let Some(delta) = rx.recv().await {
+            deltas.push(delta);
+        }
+        assert_eq!(deltas, vec!["one ", "two ", "three ", "four"]);
+        assert!(started.elapsed() > REQUEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_slow_response_times_out_as_network_error() {
+        let (_server, reviewer) = mock_reviewer_with(
+            wiremock::ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({
+                    "choices": [{ "message": { "content": "1. Processes" } }]
+                }))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .await;
+        let reviewer = reviewer
+            .with_max_retries(0)
+            .with_timeouts(Duration::from_secs(1), Duration::from_millis(50))
+            .unwrap();
+
+        let err = reviewer.generate_subtopics("OS", 10).await.unwrap_err();
+        assert!(matches!(err, ReviewerError::Network(ref e) if e.is_timeout()));
+        assert!(err.is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_is_a_transient_network_error() {
+        // Grab a free port, then close it so nothing is listening there.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        drop(listener);
+
+        let prompts = HashMap::from([("generate_subtopics".to_string(), "{topic}".to_string())]);
+        let reviewer = ReviewerClient::new("key".to_string(), "gpt-4o".to_string(), prompts)
+            .unwrap()
+            .with_max_retries(0)
+            .with_base_url(&base_url)
+            .unwrap();
+        let err = reviewer.generate_subtopics("OS", 10).await.unwrap_err();
+        assert!(matches!(err, ReviewerError::Network(_)));
+        assert!(err.is_transient());
+    }
+
     // This is an integration test that makes a live call to the OpenAI API.
     // It is ignored by default to allow `cargo test` to run without requiring a live
     // API key. To run this test, use `cargo test -- --ignored`.
@@ -406,11 +1478,13 @@ mod tests {
         // For testing, we create a dummy prompt map. In a real scenario, this would be loaded.
         let mut prompts = HashMap::new();
         prompts.insert("generate_subtopics".to_string(), "List all the key subtopics and concepts someone should cover to thoroughly teach the topic \"{topic}\" to a beginner. Respond ONLY as a numbered list of subtopic names (no explanations).".to_string());
-        let reviewer = ReviewerClient::new(api_key, model, prompts);
+        let reviewer = ReviewerClient::new(api_key, model, prompts).unwrap();
 
         // Try generating subtopics for "Operating Systems"
         let topic = "Operating Systems";
-        let result = reviewer.generate_subtopics(topic).await;
+        let result = reviewer
+            .generate_subtopics(topic, DEFAULT_SUBTOPIC_COUNT)
+            .await;
 
         match result {
             Ok(subtopics) => {
@@ -459,7 +1533,7 @@ mod tests {
         {segment}
         ---
     "#.to_string());
-        let reviewer = ReviewerClient::new(api_key, model, prompts);
+        let reviewer = ReviewerClient::new(api_key, model, prompts).unwrap();
 
         // Prepare a simple segment and subtopics
         let segment =
@@ -499,24 +1573,24 @@ mod tests {
 
                 // Check that questions are structured as objects with 'field' and 'question'
                 for obj in arr.iter() {
-                    if let Some(questions) = obj.get("questions") {
-                        if let Some(qarr) = questions.as_array() {
-                            for q in qarr {
-                                // Accept old format (just text) OR new format (object with field/question)
-                                if q.is_object() {
-                                    assert!(
-                                        q.get("field").is_some(),
-                                        "Question object should have 'field'"
-                                    );
-                                    assert!(
-                                        q.get("question").is_some(),
-                                        "Question object should have 'question'"
-                                    );
-                                } else if q.is_string() {
-                                    // Accept legacy string questions for compatibility
-                                } else {
-                                    panic!("Question should be object or string");
-                                }
+                    if let Some(questions) = obj.get("questions")
+                        && let Some(qarr) = questions.as_array()
+                    {
+                        for q in qarr {
+                            // Accept old format (just text) OR new format (object with field/question)
+                            if q.is_object() {
+                                assert!(
+                                    q.get("field").is_some(),
+                                    "Question object should have 'field'"
+                                );
+                                assert!(
+                                    q.get("question").is_some(),
+                                    "Question object should have 'question'"
+                                );
+                            } else if q.is_string() {
+                                // Accept legacy string questions for compatibility
+                            } else {
+                                panic!("Question should be object or string");
                             }
                         }
                     }
@@ -555,7 +1629,7 @@ Respond STRICTLY as JSON:
 Do NOT add any explanation, just the JSON."#
                 .to_string(),
         );
-        let reviewer = ReviewerClient::new(api_key, model, prompts);
+        let reviewer = ReviewerClient::new(api_key, model, prompts).unwrap();
 
         // Test case 1: Correct and complete answer
         let question = "What is TCP/IP?";
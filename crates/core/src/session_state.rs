@@ -1,30 +1,164 @@
 use crate::{
     Command,
-    reviewer::Reviewer,
+    clock::{Clock, SystemClock},
+    reviewer::{Reviewer, ReviewerError},
     topic::{SubTopic, SubTopicList},
 };
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Notify;
 
-#[derive(Debug, Clone)]
+/// How long to wait for an answer before nudging the user, unless configured otherwise.
+pub const DEFAULT_ANSWER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long to wait for the runtime to confirm a question was spoken before assuming it was,
+/// unless configured otherwise.
+pub const DEFAULT_DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Spoken when an answer hasn't arrived within the answer timeout.
+const ANSWER_NUDGE: &str = "Take your time. Would you like me to repeat the question?";
+
+/// Spoken when the learner skips the last question of a batch, so the skip is acknowledged
+/// even though no next question follows.
+const SKIP_ACKNOWLEDGEMENT: &str =
+    "No problem, let's leave that one for now. Carry on whenever you're ready.";
+
+/// Spoken when the session ends because there was still no answer after a nudge.
+const ANSWER_TIMEOUT_GOODBYE: &str = "Let's pick this up another time.";
+
+/// Answers that mean the learner wants to skip the question, after lowercasing and dropping
+/// punctuation.
+const SKIP_PHRASES: &[&str] = &[
+    "skip",
+    "skip it",
+    "skip this",
+    "skip this one",
+    "skip this question",
+    "next question",
+    "pass",
+    "i dont know",
+    "i do not know",
+    "no idea",
+];
+
+// Whether an answer is just a request to skip the question.
+fn is_skip_phrase(answer: &str) -> bool {
+    let normalized: String = answer
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+    let normalized = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+    SKIP_PHRASES.contains(&normalized.as_str())
+}
+
+/// Who said a line of the session transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Speaker {
+    /// The user, explaining the topic.
+    Learner,
+    /// The AI student, asking questions.
+    Student,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QuestionForSubtopic {
     pub subtopic: String,
     pub field: String, // "has_definition" | "has_mechanism" | "has_example"
     pub question: String,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+impl QuestionForSubtopic {
+    fn to_command(&self, question_id: u64) -> Command {
+        Command::QuestionAsked {
+            question_id,
+            subtopic: self.subtopic.clone(),
+            field: self.field.clone(),
+            question: self.question.clone(),
+        }
+    }
+}
+
+/// Per-subtopic analytics: how long it took to cover and how many questions it needed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtopicStats {
+    /// When the reviewer first analyzed an explanation of this subtopic.
+    pub first_mentioned: Instant,
+    /// Time from first mention until the subtopic was fully covered, if it has been.
+    pub time_to_cover: Option<Duration>,
+    /// The number of questions asked about this subtopic.
+    pub questions_asked: u32,
+}
+
+/// A human-readable summary of per-subtopic stats, one line per subtopic, sorted by name.
+pub fn format_stats(stats: &HashMap<String, SubtopicStats>) -> String {
+    let mut names: Vec<_> = stats.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let stats = &stats[name];
+            let time = match stats.time_to_cover {
+                Some(duration) => format!("covered in {}s", duration.as_secs()),
+                None => "not covered".to_string(),
+            };
+            format!(
+                "- {}: {}, {} question(s) asked",
+                name, time, stats.questions_asked
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum FeynmanState {
     Listening,
     Analyzing,
+    /// A question has been handed to the runtime, but the AI has not finished speaking it yet.
+    DeliveringQuestion,
     AnalyzingAnswers,
 }
 
+/// The persistable part of a `FeynmanSession`, so a lesson can survive a restart.
+///
+/// The subtopic list is passed back in on restore, and subtopic stats are left out because
+/// they are built on `Instant`s, which mean nothing to another process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub state: FeynmanState,
+    pub in_between_buffer: Vec<String>,
+    pub answer_buffer: Vec<String>,
+    pub temp_context_buffer: Vec<String>,
+    pub question_queue: Vec<QuestionForSubtopic>,
+    pub current_question_idx: usize,
+    pub pending_segments: Vec<String>,
+    pub pending_no_subtopic_segment: bool,
+    pub covered_subtopics: HashMap<String, SubTopic>,
+    pub question_subtopics: Vec<String>,
+    pub incomplete_subtopics: HashMap<String, SubTopic>,
+    pub confirm_question_delivery: bool,
+    #[serde(default)]
+    pub history: Vec<(Speaker, String)>,
+}
+
+/// How far through the lesson the user is, e.g. for a progress display.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionProgress {
+    /// Fully explained subtopics, in lesson order.
+    pub covered: Vec<String>,
+    /// Subtopics still to explain, in lesson order.
+    pub incomplete: Vec<String>,
+    /// The share of subtopics covered, from 0 to 100.
+    pub percent_complete: f32,
+}
+
 pub struct FeynmanSession {
     pub state: FeynmanState,
     pub in_between_buffer: Vec<String>, // Segments that come in during analyzing/question delivery
@@ -39,6 +173,26 @@ pub struct FeynmanSession {
     pub question_subtopics: Vec<String>,              // Subtopics currently being questioned
     pub incomplete_subtopics: HashMap<String, SubTopic>,
     pub answer_notify: Arc<Notify>,
+    /// When true, answers are only analyzed after the runtime confirms the question was spoken.
+    pub confirm_question_delivery: bool,
+    /// Timing and question counts per subtopic, keyed by subtopic name.
+    pub subtopic_stats: HashMap<String, SubtopicStats>,
+    /// How long to wait for an answer before nudging, and then again before giving up.
+    pub answer_timeout: Duration,
+    /// How long to wait for `question_delivered` before listening for the answer anyway.
+    pub delivery_timeout: Duration,
+    /// Everything said in the session so far, oldest first.
+    pub history: Vec<(Speaker, String)>,
+    /// When true, each detected subtopic gets its own `analyze_topic` call, all run at once,
+    /// instead of one call covering them all. More requests, but less waiting.
+    pub concurrent_subtopic_analysis: bool,
+    // When the current answer wait expires, and whether the user has already been nudged.
+    answer_deadline: Option<tokio::time::Instant>,
+    answer_nudged: bool,
+    // The ID given to the last question asked, and when waiting for it to be spoken runs out.
+    question_id: u64,
+    delivery_deadline: Option<tokio::time::Instant>,
+    clock: Arc<dyn Clock>,
 }
 
 impl FeynmanSession {
@@ -57,18 +211,375 @@ impl FeynmanSession {
             question_subtopics: vec![],
             incomplete_subtopics: HashMap::new(),
             answer_notify: Arc::new(Notify::new()),
+            confirm_question_delivery: true,
+            subtopic_stats: HashMap::new(),
+            answer_timeout: DEFAULT_ANSWER_TIMEOUT,
+            delivery_timeout: DEFAULT_DELIVERY_TIMEOUT,
+            history: vec![],
+            concurrent_subtopic_analysis: false,
+            answer_deadline: None,
+            answer_nudged: false,
+            question_id: 0,
+            delivery_deadline: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Rebuilds a session from a snapshot taken by `to_snapshot`.
+    ///
+    /// A session snapshotted mid-question comes back waiting on a question this process never
+    /// asked. Call `resume` to ask it again.
+    pub fn from_snapshot(snapshot: SessionSnapshot, subtopic_list: SubTopicList) -> Self {
+        let mut session = Self {
+            state: snapshot.state,
+            in_between_buffer: snapshot.in_between_buffer,
+            answer_buffer: snapshot.answer_buffer,
+            temp_context_buffer: snapshot.temp_context_buffer,
+            question_queue: snapshot.question_queue,
+            current_question_idx: snapshot.current_question_idx,
+            pending_segments: snapshot.pending_segments,
+            pending_no_subtopic_segment: snapshot.pending_no_subtopic_segment,
+            covered_subtopics: snapshot.covered_subtopics,
+            question_subtopics: snapshot.question_subtopics,
+            incomplete_subtopics: snapshot.incomplete_subtopics,
+            confirm_question_delivery: snapshot.confirm_question_delivery,
+            history: snapshot.history,
+            ..Self::new(subtopic_list)
+        };
+        // The old answer deadline died with the old process, so start a fresh one.
+        if session.state == FeynmanState::AnalyzingAnswers {
+            session.start_waiting_for_answer();
+        }
+        session
+    }
+
+    /// Asks the pending question again after `from_snapshot`.
+    ///
+    /// Without this, a session restored while delivering a question would wait forever for it
+    /// to be spoken, and new speech would pile up unanalyzed. Does nothing unless the session
+    /// was restored mid-question.
+    pub async fn resume(&mut self, command_tx: tokio::sync::mpsc::Sender<Command>) -> Result<()> {
+        if !matches!(
+            self.state,
+            FeynmanState::DeliveringQuestion | FeynmanState::AnalyzingAnswers
+        ) {
+            return Ok(());
+        }
+        let Some(question) = self.question_queue.get(self.current_question_idx).cloned() else {
+            // No question is pending, so there is nothing to wait for.
+            self.state = FeynmanState::Listening;
+            return Ok(());
+        };
+        let question_id = self.next_question_id();
+        command_tx
+            .send(question.to_command(question_id))
+            .await
+            .context("Failed to resend QuestionAsked command")?;
+        self.question_sent();
+        Ok(())
+    }
+
+    /// Which subtopics have been covered so far.
+    pub fn progress(&self) -> SessionProgress {
+        let (covered, incomplete): (Vec<_>, Vec<_>) = self
+            .subtopic_list
+            .subtopics
+            .iter()
+            .map(|subtopic| subtopic.name.clone())
+            .partition(|name| self.covered_subtopics.contains_key(name));
+        let total = covered.len() + incomplete.len();
+        let percent_complete = if total == 0 {
+            0.0
+        } else {
+            covered.len() as f32 * 100.0 / total as f32
+        };
+        SessionProgress {
+            covered,
+            incomplete,
+            percent_complete,
+        }
+    }
+
+    /// Starts the lesson over on the same subtopics, e.g. so the user can practice again.
+    ///
+    /// All progress, pending questions, and buffered speech are dropped; settings such as the
+    /// answer timeout are kept. Returns the subtopics left to cover, which is all of them.
+    pub fn reset(&mut self) -> Vec<String> {
+        self.state = FeynmanState::Listening;
+        self.in_between_buffer.clear();
+        self.answer_buffer.clear();
+        self.temp_context_buffer.clear();
+        self.question_queue.clear();
+        self.current_question_idx = 0;
+        self.pending_segments.clear();
+        self.pending_no_subtopic_segment = false;
+        self.covered_subtopics.clear();
+        self.question_subtopics.clear();
+        self.incomplete_subtopics.clear();
+        self.subtopic_stats.clear();
+        self.history.clear();
+        self.answer_deadline = None;
+        self.answer_nudged = false;
+        self.delivery_deadline = None;
+        self.progress().incomplete
+    }
+
+    /// Moves the session on to a new set of subtopics, as `reset` does for the same ones.
+    ///
+    /// Returns how far the previous topic got, so callers can keep a history of topics
+    /// covered in one sitting.
+    pub fn switch_topic(&mut self, subtopic_list: SubTopicList) -> SessionProgress {
+        let previous = self.progress();
+        self.subtopic_list = subtopic_list;
+        self.reset();
+        previous
+    }
+
+    /// Adds a line to the session history.
+    pub fn push_turn(&mut self, speaker: Speaker, text: String) {
+        self.history.push((speaker, text));
+    }
+
+    /// The session history as text, one `Learner: ...` or `Student: ...` line per turn,
+    /// e.g. for summarizing what was taught.
+    pub fn transcript(&self) -> String {
+        self.history
+            .iter()
+            .map(|(speaker, text)| format!("{speaker:?}: {text}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Asks the reviewer for a recap of the lesson so far, from the transcript and progress.
+    pub async fn summarize<R: Reviewer + Send + Sync + ?Sized>(
+        &self,
+        reviewer: &R,
+        topic: &str,
+    ) -> Result<String> {
+        let progress = self.progress();
+        let summary = reviewer
+            .summarize_session(
+                topic,
+                &self.transcript(),
+                &progress.covered,
+                &progress.incomplete,
+            )
+            .await
+            .context("Failed to summarize the session")?;
+        Ok(summary)
+    }
+
+    /// Captures the session's progress so it can be saved and restored later.
+    pub fn to_snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot {
+            state: self.state.clone(),
+            in_between_buffer: self.in_between_buffer.clone(),
+            answer_buffer: self.answer_buffer.clone(),
+            temp_context_buffer: self.temp_context_buffer.clone(),
+            question_queue: self.question_queue.clone(),
+            current_question_idx: self.current_question_idx,
+            pending_segments: self.pending_segments.clone(),
+            pending_no_subtopic_segment: self.pending_no_subtopic_segment,
+            covered_subtopics: self.covered_subtopics.clone(),
+            question_subtopics: self.question_subtopics.clone(),
+            incomplete_subtopics: self.incomplete_subtopics.clone(),
+            confirm_question_delivery: self.confirm_question_delivery,
+            history: self.history.clone(),
+        }
+    }
+
+    /// Uses the given clock for subtopic timing instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Disables (or re-enables) waiting for `question_delivered` before analyzing answers.
+    pub fn with_confirm_question_delivery(mut self, confirm: bool) -> Self {
+        self.confirm_question_delivery = confirm;
+        self
+    }
+
+    /// Analyzes each detected subtopic in its own concurrent `analyze_topic` call.
+    pub fn with_concurrent_subtopic_analysis(mut self, concurrent: bool) -> Self {
+        self.concurrent_subtopic_analysis = concurrent;
+        self
+    }
+
+    /// Sets how long to wait for an answer before nudging the user.
+    pub fn with_answer_timeout(mut self, timeout: Duration) -> Self {
+        self.answer_timeout = timeout;
+        self
+    }
+
+    /// Sets how long to wait for a question to be spoken before listening for the answer anyway.
+    pub fn with_delivery_timeout(mut self, timeout: Duration) -> Self {
+        self.delivery_timeout = timeout;
+        self
+    }
+
+    /// Called by the runtime once the AI has finished speaking the question with this ID.
+    /// Moves the session on to waiting for the user's answer.
+    ///
+    /// Acks for any other question, e.g. one asked before a restart, are ignored.
+    pub fn question_delivered(&mut self, question_id: u64) {
+        if self.state == FeynmanState::DeliveringQuestion && question_id == self.question_id {
+            tracing::debug!(
+                "Question {} delivered. Waiting for the answer.",
+                question_id
+            );
+            self.start_waiting_for_answer();
         }
     }
 
+    // Hands out the ID for the next question sent to the runtime.
+    fn next_question_id(&mut self) -> u64 {
+        self.question_id += 1;
+        self.question_id
+    }
+
+    // Moves on after a question has been sent to the runtime.
+    fn question_sent(&mut self) {
+        if self.confirm_question_delivery {
+            self.state = FeynmanState::DeliveringQuestion;
+            self.delivery_deadline = Some(tokio::time::Instant::now() + self.delivery_timeout);
+        } else {
+            self.start_waiting_for_answer();
+        }
+    }
+
+    fn start_waiting_for_answer(&mut self) {
+        self.state = FeynmanState::AnalyzingAnswers;
+        self.delivery_deadline = None;
+        self.answer_deadline = Some(tokio::time::Instant::now() + self.answer_timeout);
+        self.answer_nudged = false;
+    }
+
+    /// When waiting for the current question to be spoken runs out, if the session is waiting.
+    ///
+    /// The runtime should call `check_delivery_timeout` once this deadline passes.
+    pub fn delivery_deadline(&self) -> Option<tokio::time::Instant> {
+        if self.state == FeynmanState::DeliveringQuestion {
+            self.delivery_deadline
+        } else {
+            None
+        }
+    }
+
+    /// Handles a question whose delivery was never confirmed, e.g. because its audio failed
+    /// to play, by listening for the answer anyway. Returns whether the deadline had passed.
+    pub fn check_delivery_timeout(&mut self) -> bool {
+        match self.delivery_deadline() {
+            Some(deadline) if tokio::time::Instant::now() >= deadline => {}
+            _ => return false,
+        }
+        tracing::warn!(
+            "Question {} not confirmed as spoken within {:?}. Listening for the answer anyway.",
+            self.question_id,
+            self.delivery_timeout
+        );
+        self.start_waiting_for_answer();
+        true
+    }
+
+    /// When the current wait for an answer runs out, if the session is waiting for one.
+    ///
+    /// The runtime should call `check_answer_timeout` once this deadline passes.
+    pub fn answer_deadline(&self) -> Option<tokio::time::Instant> {
+        if self.state == FeynmanState::AnalyzingAnswers {
+            self.answer_deadline
+        } else {
+            None
+        }
+    }
+
+    /// Handles an answer that is overdue. The first timeout nudges the user; a second one in a
+    /// row ends the session. Returns whether the deadline had passed.
+    pub async fn check_answer_timeout(
+        &mut self,
+        command_tx: tokio::sync::mpsc::Sender<Command>,
+    ) -> Result<bool> {
+        match self.answer_deadline() {
+            Some(deadline) if tokio::time::Instant::now() >= deadline => {}
+            _ => return Ok(false),
+        }
+
+        if !self.answer_nudged {
+            tracing::info!(
+                "No answer within {:?}. Nudging the user.",
+                self.answer_timeout
+            );
+            command_tx
+                .send(Command::SpeakText(ANSWER_NUDGE.to_string()))
+                .await
+                .context("Failed to send nudge SpeakText command")?;
+            self.answer_deadline = Some(tokio::time::Instant::now() + self.answer_timeout);
+            self.answer_nudged = true;
+        } else {
+            tracing::info!("Still no answer after a nudge. Ending the session.");
+            command_tx
+                .send(Command::SessionComplete {
+                    message: ANSWER_TIMEOUT_GOODBYE.to_string(),
+                    stats: self.subtopic_stats.clone(),
+                })
+                .await
+                .context("Failed to send SessionComplete command")?;
+            // Drop the abandoned question, so nothing later still sees it pending.
+            self.question_queue.clear();
+            self.current_question_idx = 0;
+            self.answer_buffer.clear();
+            self.answer_deadline = None;
+            self.answer_nudged = false;
+            self.state = FeynmanState::Listening;
+        }
+        Ok(true)
+    }
+
+    // Starts the timer for a subtopic the first time it is analyzed.
+    fn record_mention(&mut self, subtopic: &str) {
+        let now = self.clock.now();
+        self.subtopic_stats
+            .entry(subtopic.to_string())
+            .or_insert(SubtopicStats {
+                first_mentioned: now,
+                time_to_cover: None,
+                questions_asked: 0,
+            });
+    }
+
+    // Stamps how long a subtopic took to cover, the first time it becomes covered.
+    fn record_covered(&mut self, subtopic: &str) {
+        let now = self.clock.now();
+        if let Some(stats) = self.subtopic_stats.get_mut(subtopic)
+            && stats.time_to_cover.is_none()
+        {
+            stats.time_to_cover = Some(now.duration_since(stats.first_mentioned));
+        }
+    }
+
+    // Counts a question asked about a subtopic.
+    fn record_question(&mut self, subtopic: &str) {
+        self.record_mention(subtopic);
+        if let Some(stats) = self.subtopic_stats.get_mut(subtopic) {
+            stats.questions_asked += 1;
+        }
+    }
+
+    /// A human-readable summary of the per-subtopic stats, sorted by subtopic name.
+    pub fn stats_summary(&self) -> String {
+        format_stats(&self.subtopic_stats)
+    }
+
     // This function is now generic over any type `R` that implements the `Reviewer` trait.
     // The `Send + Sync` bounds are required because the `reviewer` is used in an `async`
     // context (`process_analyzing`) which may be run on a different thread.
-    pub async fn process_segment<R: Reviewer + Send + Sync>(
+    pub async fn process_segment<R: Reviewer + Send + Sync + ?Sized>(
         session: &mut FeynmanSession,
         reviewer: &R,
         segment: String,
         command_tx: tokio::sync::mpsc::Sender<Command>,
     ) {
+        session.push_turn(Speaker::Learner, segment.clone());
         let state_before = session.state.clone();
         match state_before {
             // In the listening state, we check if we have temp context from a previous leftover segment and add it to the new segment.
@@ -97,14 +608,16 @@ impl FeynmanSession {
                     session.state = FeynmanState::Listening;
                 }
             }
-            FeynmanState::Analyzing => {
-                // If we are in an analyzing state and a new segment comes in, just buffer it for later.
+            FeynmanState::Analyzing | FeynmanState::DeliveringQuestion => {
+                // If we are analyzing, or the question hasn't been fully spoken yet,
+                // just buffer the new segment for later.
                 session.in_between_buffer.push(segment);
             }
             FeynmanState::AnalyzingAnswers => {
                 // A new segment has arrived while we are waiting for an answer.
                 // We treat this as the answer (or part of it).
                 session.answer_buffer.push(segment);
+                session.answer_nudged = false;
 
                 // Now, we trigger the analysis of the answer.
                 // The `analyze_answer` function will handle the rest of the logic.
@@ -119,27 +632,56 @@ impl FeynmanSession {
         }
     }
 
+    /// Runs `analyze_topic` over `subtopics` and returns the results as one JSON array.
+    ///
+    /// With `concurrent`, each subtopic is analyzed in its own call and the calls run at once;
+    /// if any of them fails, so does the whole analysis.
+    async fn analyze_detected_subtopics<R>(
+        reviewer: &R,
+        segment: &str,
+        subtopics: &[SubTopic],
+        concurrent: bool,
+    ) -> Result<String, ReviewerError>
+    where
+        R: Reviewer + Send + Sync + ?Sized,
+    {
+        if !concurrent || subtopics.len() < 2 {
+            return reviewer.analyze_topic(segment, subtopics).await;
+        }
+        let calls = subtopics
+            .iter()
+            .map(|subtopic| reviewer.analyze_topic(segment, std::slice::from_ref(subtopic)));
+        let mut merged = Vec::new();
+        for analysis_json in futures::future::join_all(calls).await {
+            match serde_json::from_str(&analysis_json?)? {
+                Value::Array(results) => merged.extend(results),
+                result => merged.push(result),
+            }
+        }
+        Ok(Value::Array(merged).to_string())
+    }
+
     // This function returns a Pinned Future to allow for async recursion.
     // It's generic over `R: Reviewer` and also requires `Send + Sync` because the
     // returned Future might be sent across threads (e.g., in a `tokio::spawn`).
     // The `'a` lifetime ensures that the reference to the reviewer lives as long as the Future.
-    fn process_analyzing<'a, R: Reviewer + Send + Sync>(
+    fn process_analyzing<'a, R>(
         session: &'a mut FeynmanSession,
         reviewer: &'a R,
         segment: String,
         command_tx: tokio::sync::mpsc::Sender<Command>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>
     where
-        R: 'a,
+        R: Reviewer + Send + Sync + ?Sized + 'a,
     {
         Box::pin(async move {
-            let detected_subtopics = session.subtopic_list.find_mentions(&segment, 70);
+            let detected_subtopics = session.subtopic_list.find_mentions(&segment);
 
             // If the current segment contains no topics, put it into pending segments to process later.
             if detected_subtopics.is_empty() {
                 session.pending_segments.push(segment);
                 session.pending_no_subtopic_segment = true;
-                if let Some(next_segment) = session.in_between_buffer.pop() {
+                if let Some(next_segment) = session.next_in_between_segment() {
                     // Recursive call is safe here because of the Box::pin indirection.
                     Self::process_analyzing(session, reviewer, next_segment, command_tx).await?;
                 } else {
@@ -158,10 +700,26 @@ impl FeynmanSession {
                 let detected_subtopics: Vec<SubTopic> =
                     detected_subtopics.into_iter().cloned().collect();
                 // Analyze the topic for correctness using the reviewer.
-                let analysis_json = reviewer
-                    .analyze_topic(&combined, &detected_subtopics)
-                    .await
-                    .context("Reviewer failed to analyze the topic segment")?;
+                let analysis_json = match Self::analyze_detected_subtopics(
+                    reviewer,
+                    &combined,
+                    &detected_subtopics,
+                    session.concurrent_subtopic_analysis,
+                )
+                .await
+                {
+                    Ok(analysis_json) => analysis_json,
+                    Err(e) => {
+                        // A network blip shouldn't lose the explanation, so keep it to be
+                        // analyzed again with the next segment. Other failures won't get
+                        // better by retrying, so the segment is dropped.
+                        if e.is_transient() {
+                            session.pending_segments.push(combined);
+                        }
+                        return Err(anyhow::Error::new(e)
+                            .context("Reviewer failed to analyze the topic segment"));
+                    }
+                };
 
                 // Parse the LLM's JSON output.
                 let analysis: Value = serde_json::from_str(&analysis_json)
@@ -169,6 +727,8 @@ impl FeynmanSession {
 
                 let mut question_queue: Vec<QuestionForSubtopic> = vec![];
                 let incomplete_subtopics = Vec::new();
+                // So the session is only congratulated once, by whichever analysis finishes it.
+                let was_complete = session.is_session_complete();
 
                 if let Some(array) = analysis.as_array() {
                     for subtopic_result in array {
@@ -176,29 +736,30 @@ impl FeynmanSession {
                             .as_str()
                             .context("Subtopic result in JSON is missing a 'subtopic' field")?
                             .to_string();
-                        let has_def = subtopic_result["has_definition"].as_bool().unwrap_or(false);
-                        let has_mech = subtopic_result["has_mechanism"].as_bool().unwrap_or(false);
-                        let has_ex = subtopic_result["has_example"].as_bool().unwrap_or(false);
+                        let subtopic = SubTopic {
+                            name: name.clone(),
+                            has_definition: subtopic_result["has_definition"]
+                                .as_bool()
+                                .unwrap_or(false),
+                            has_mechanism: subtopic_result["has_mechanism"]
+                                .as_bool()
+                                .unwrap_or(false),
+                            has_example: subtopic_result["has_example"].as_bool().unwrap_or(false),
+                        };
+                        session.record_mention(&name);
 
                         // If a topic was completely covered, add it to the covered subtopics.
-                        if has_def && has_mech && has_ex {
-                            session.covered_subtopics.insert(
-                                name.clone(),
-                                SubTopic {
-                                    name: name.clone(),
-                                    has_definition: has_def,
-                                    has_mechanism: has_mech,
-                                    has_example: has_ex,
-                                },
-                            );
+                        if subtopic.is_complete() {
+                            session.record_covered(&name);
+                            session.covered_subtopics.insert(name.clone(), subtopic);
                         } else {
-                            // If there are incomplete subtopics, add them to the list.
-                            session.add_to_incomplete_subtopics(
-                                name.clone(),
-                                has_def,
-                                has_mech,
-                                has_ex,
+                            tracing::debug!(
+                                "Subtopic '{}' is missing {:?}",
+                                name,
+                                subtopic.missing_fields()
                             );
+                            // If there are incomplete subtopics, add them to the list.
+                            session.incomplete_subtopics.insert(name.clone(), subtopic);
                             // Parse the questions generated by the LLM.
                             if let Some(questions_arr) = subtopic_result["questions"].as_array() {
                                 for q in questions_arr {
@@ -224,8 +785,13 @@ impl FeynmanSession {
                 }
                 // If no questions were generated, we either continue to the next segment or go back to listening.
                 if question_queue.is_empty() {
-                    // All subtopics are complete.
-                    if let Some(next_segment) = session.in_between_buffer.pop() {
+                    session.send_progress(&command_tx, None).await?;
+                    // This segment may have covered the last subtopic, e.g. one the learner
+                    // explained while questions were being asked.
+                    if !was_complete && session.is_session_complete() {
+                        session.send_session_complete(&command_tx).await?;
+                    }
+                    if let Some(next_segment) = session.next_in_between_segment() {
                         Self::process_analyzing(session, reviewer, next_segment, command_tx)
                             .await?;
                     } else {
@@ -238,15 +804,22 @@ impl FeynmanSession {
                     session.current_question_idx = 0; // Start with the first question.
 
                     // Get the first question from the now-populated queue.
-                    if let Some(first_question) = session.question_queue.get(0) {
+                    if let Some(first_question) = session.question_queue.first().cloned() {
                         // Send a command to the runtime to ask the question.
+                        let question_id = session.next_question_id();
                         command_tx
-                            .send(Command::SpeakText(first_question.question.clone()))
+                            .send(first_question.to_command(question_id))
                             .await
-                            .context("Failed to send SpeakText command")?;
+                            .context("Failed to send QuestionAsked command")?;
+                        session.record_question(&first_question.subtopic);
+                        session.push_turn(Speaker::Student, first_question.question.clone());
 
-                        // After commanding the runtime to ask, we wait for the answer.
-                        session.state = FeynmanState::AnalyzingAnswers;
+                        // After commanding the runtime to ask, we wait for the question to be
+                        // spoken and then for the answer.
+                        session.question_sent();
+                        session
+                            .send_progress(&command_tx, Some(first_question.subtopic))
+                            .await?;
                     } else {
                         // This case should not be reached if the queue is not empty, but as a safeguard:
                         session.state = FeynmanState::Listening;
@@ -257,25 +830,7 @@ impl FeynmanSession {
         })
     }
 
-    fn add_to_incomplete_subtopics(
-        &mut self,
-        name: String,
-        has_def: bool,
-        has_mech: bool,
-        has_ex: bool,
-    ) {
-        self.incomplete_subtopics.insert(
-            name.clone(),
-            SubTopic {
-                name,
-                has_definition: has_def,
-                has_mechanism: has_mech,
-                has_example: has_ex,
-            },
-        );
-    }
-
-    pub async fn analyze_answer<R: Reviewer + Send + Sync>(
+    pub async fn analyze_answer<R: Reviewer + Send + Sync + ?Sized>(
         &mut self,
         reviewer: &R,
         command_tx: tokio::sync::mpsc::Sender<Command>,
@@ -296,6 +851,12 @@ impl FeynmanSession {
         // Combine answer segments into a single string.
         let combined_answer = self.answer_buffer.join(" ");
 
+        // The learner can't answer, so move on without asking the reviewer.
+        if is_skip_phrase(&combined_answer) {
+            self.skip_current_question(command_tx.clone()).await?;
+            return self.analyze_in_between(reviewer, command_tx).await;
+        }
+
         // Analyze the answer with the reviewer.
         let is_correct = reviewer
             .analyze_answer(&current_question.question, &combined_answer)
@@ -306,13 +867,13 @@ impl FeynmanSession {
             self.update_subtopic_field(&current_question.subtopic, &current_question.field, true);
 
             // Check if the subtopic is now complete. If so, move it from incomplete to covered.
-            if self.is_subtopic_complete(&current_question.subtopic) {
-                if let Some(complete_subtopic) =
+            if self.is_subtopic_complete(&current_question.subtopic)
+                && let Some(complete_subtopic) =
                     self.incomplete_subtopics.remove(&current_question.subtopic)
-                {
-                    self.covered_subtopics
-                        .insert(current_question.subtopic.clone(), complete_subtopic);
-                }
+            {
+                self.record_covered(&current_question.subtopic);
+                self.covered_subtopics
+                    .insert(current_question.subtopic.clone(), complete_subtopic);
             }
         }
 
@@ -320,11 +881,86 @@ impl FeynmanSession {
         self.answer_buffer.clear();
 
         // Ask the next question or conclude the batch.
-        self.ask_next_question(command_tx).await?;
+        self.ask_next_question(command_tx.clone()).await?;
+        let current_subtopic = self
+            .question_queue
+            .get(self.current_question_idx)
+            .map(|question| question.subtopic.clone());
+        self.send_progress(&command_tx, current_subtopic).await?;
+
+        self.analyze_in_between(reviewer, command_tx).await
+    }
+
+    // Tells the runtime how far through the subtopic list the session is.
+    async fn send_progress(
+        &self,
+        command_tx: &tokio::sync::mpsc::Sender<Command>,
+        current_subtopic: Option<String>,
+    ) -> Result<()> {
+        command_tx
+            .send(Command::Progress {
+                covered: self.covered_subtopics.len(),
+                total: self.subtopic_list.subtopics.len(),
+                current_subtopic,
+            })
+            .await
+            .context("Failed to send Progress command")
+    }
 
+    /// Gives up on the current question, leaving its subtopic incomplete, and moves on to the
+    /// next question or ends the batch. Ending the batch is acknowledged out loud, since the
+    /// learner would otherwise hear nothing.
+    pub async fn skip_current_question(
+        &mut self,
+        command_tx: tokio::sync::mpsc::Sender<Command>,
+    ) -> Result<()> {
+        let Some(skipped) = self.question_queue.get(self.current_question_idx) else {
+            return Err(anyhow::anyhow!("No current question to skip"));
+        };
+        tracing::info!("Skipping question: {}", skipped.question);
+
+        self.answer_buffer.clear();
+        command_tx
+            .send(Command::SkipQuestion)
+            .await
+            .context("Failed to send SkipQuestion command")?;
+        self.ask_next_question(command_tx.clone()).await?;
+        if self.state == FeynmanState::Listening {
+            command_tx
+                .send(Command::SpeakText(SKIP_ACKNOWLEDGEMENT.to_string()))
+                .await
+                .context("Failed to send skip acknowledgement SpeakText command")?;
+        }
+        Ok(())
+    }
+
+    // Once the batch is done, analyze whatever the user said while questions were being
+    // delivered instead of dropping it. `process_analyzing` carries on through the rest of
+    // the buffer until it either runs dry or raises new questions.
+    async fn analyze_in_between<R: Reviewer + Send + Sync + ?Sized>(
+        &mut self,
+        reviewer: &R,
+        command_tx: tokio::sync::mpsc::Sender<Command>,
+    ) -> Result<()> {
+        if self.state == FeynmanState::Listening
+            && let Some(segment) = self.next_in_between_segment()
+        {
+            self.state = FeynmanState::Analyzing;
+            Self::process_analyzing(self, reviewer, segment, command_tx).await?;
+        }
         Ok(())
     }
 
+    // Takes the oldest buffered in-between segment, so segments are analyzed in the order
+    // they were spoken.
+    fn next_in_between_segment(&mut self) -> Option<String> {
+        if self.in_between_buffer.is_empty() {
+            None
+        } else {
+            Some(self.in_between_buffer.remove(0))
+        }
+    }
+
     // Helper to manage the question-asking loop.
     async fn ask_next_question(
         &mut self,
@@ -334,13 +970,16 @@ impl FeynmanSession {
 
         if self.current_question_idx < self.question_queue.len() {
             // If there is a next question, command the runtime to ask it.
-            let next_question = &self.question_queue[self.current_question_idx];
+            let next_question = self.question_queue[self.current_question_idx].clone();
+            let question_id = self.next_question_id();
             command_tx
-                .send(Command::SpeakText(next_question.question.clone()))
+                .send(next_question.to_command(question_id))
                 .await
-                .context("Failed to send next SpeakText command")?;
-            // The state remains AnalyzingAnswers, as we are now waiting for the next answer.
-            self.state = FeynmanState::AnalyzingAnswers;
+                .context("Failed to send next QuestionAsked command")?;
+            self.record_question(&next_question.subtopic);
+            self.push_turn(Speaker::Student, next_question.question.clone());
+            // We are now waiting for the next question to be spoken, then for its answer.
+            self.question_sent();
         } else {
             // All questions for this batch have been asked.
             self.question_queue.clear();
@@ -348,13 +987,7 @@ impl FeynmanSession {
 
             // Check if the entire session is complete.
             if self.is_session_complete() {
-                let final_message =
-                    "Congratulations! You've explained all the key subtopics.".to_string();
-                command_tx
-                    .send(Command::SessionComplete(final_message))
-                    .await
-                    .context("Failed to send final SessionComplete command")?;
-                tracing::info!("Session complete! All subtopics covered.");
+                self.send_session_complete(&command_tx).await?;
             }
 
             // Reset state to listen for the next explanation.
@@ -364,18 +997,30 @@ impl FeynmanSession {
         Ok(())
     }
 
+    // Congratulates the learner once every subtopic is covered.
+    async fn send_session_complete(
+        &self,
+        command_tx: &tokio::sync::mpsc::Sender<Command>,
+    ) -> Result<()> {
+        let final_message = "Congratulations! You've explained all the key subtopics.".to_string();
+        command_tx
+            .send(Command::SessionComplete {
+                message: final_message,
+                stats: self.subtopic_stats.clone(),
+            })
+            .await
+            .context("Failed to send final SessionComplete command")?;
+        tracing::info!("Session complete! All subtopics covered.");
+        Ok(())
+    }
+
     // Helper function to update a field of a subtopic.
     fn update_subtopic_field(&mut self, subtopic_name: &str, field: &str, value: bool) {
         // Update in incomplete_subtopics if it exists, otherwise create a new entry.
         let subtopic = self
             .incomplete_subtopics
             .entry(subtopic_name.to_string())
-            .or_insert_with(|| SubTopic {
-                name: subtopic_name.to_string(),
-                has_definition: false,
-                has_mechanism: false,
-                has_example: false,
-            });
+            .or_insert_with(|| SubTopic::new(subtopic_name.to_string()));
 
         match field {
             "has_definition" => subtopic.has_definition = value,
@@ -388,7 +1033,7 @@ impl FeynmanSession {
     // Helper function to check if a subtopic is fully covered.
     fn is_subtopic_complete(&self, subtopic_name: &str) -> bool {
         if let Some(subtopic) = self.incomplete_subtopics.get(subtopic_name) {
-            subtopic.has_definition && subtopic.has_mechanism && subtopic.has_example
+            subtopic.is_complete()
         } else {
             // If it's not in incomplete, it might be in covered.
             // But the logic only checks incomplete, which is fine.
@@ -406,8 +1051,103 @@ impl FeynmanSession {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::reviewer::MockReviewer;
+    use crate::reviewer::{MockReviewer, ReviewerError};
     use crate::topic::SubTopic;
+    use tokio::sync::mpsc::{Receiver, error::TryRecvError};
+
+    // The next command the session sent, skipping progress updates.
+    fn next_command(command_rx: &mut Receiver<Command>) -> Result<Command, TryRecvError> {
+        loop {
+            match command_rx.try_recv()? {
+                Command::Progress { .. } => continue,
+                command => return Ok(command),
+            }
+        }
+    }
+
+    // A subtopic list with the given names, in order.
+    fn subtopic_list(names: &[&str]) -> SubTopicList {
+        SubTopicList::new(
+            names
+                .iter()
+                .map(|name| SubTopic::new(name.to_string()))
+                .collect(),
+        )
+    }
+
+    // A session over the named subtopics, and the channel it sends its commands on.
+    fn session_with(
+        subtopics: &[&str],
+    ) -> (
+        FeynmanSession,
+        tokio::sync::mpsc::Sender<Command>,
+        Receiver<Command>,
+    ) {
+        let (command_tx, command_rx) = tokio::sync::mpsc::channel(8);
+        (
+            FeynmanSession::new(subtopic_list(subtopics)),
+            command_tx,
+            command_rx,
+        )
+    }
+
+    // One subtopic's `analyze_topic` result, missing just the fields the questions ask about.
+    fn subtopic_result(subtopic: &str, questions: &[(&str, &str)]) -> Value {
+        let asks_about = |field: &str| questions.iter().any(|(asked, _)| *asked == field);
+        let questions: Vec<_> = questions
+            .iter()
+            .map(|(field, question)| serde_json::json!({ "field": field, "question": question }))
+            .collect();
+        serde_json::json!({
+            "subtopic": subtopic,
+            "has_definition": !asks_about("has_definition"),
+            "has_mechanism": !asks_about("has_mechanism"),
+            "has_example": !asks_about("has_example"),
+            "questions": questions,
+        })
+    }
+
+    // Expects one `analyze_topic` call, of `segment` if given, answered with `results`.
+    fn expect_analysis(
+        reviewer: &mut MockReviewer,
+        segment: Option<&'static str>,
+        results: Vec<Value>,
+    ) {
+        let analysis_json = Value::Array(results).to_string();
+        reviewer
+            .expect_analyze_topic()
+            .withf(move |analyzed, _| segment.is_none_or(|segment| analyzed == segment))
+            .times(1)
+            .return_once(move |_, _| Box::pin(async move { Ok(analysis_json) }));
+    }
+
+    // A reviewer whose one `analyze_topic` call finds `subtopic` missing the fields the
+    // questions ask about, and asks them.
+    fn reviewer_asking(subtopic: &str, questions: &[(&str, &str)]) -> MockReviewer {
+        let mut reviewer = MockReviewer::new();
+        expect_analysis(
+            &mut reviewer,
+            None,
+            vec![subtopic_result(subtopic, questions)],
+        );
+        reviewer
+    }
+
+    // Expects `times` answers, each judged correct.
+    fn expect_correct_answers(reviewer: &mut MockReviewer, times: usize) {
+        reviewer
+            .expect_analyze_answer()
+            .returning(|_, _| Box::pin(async { Ok(true) }))
+            .times(times);
+    }
+
+    // The ID of the question the session asked next, to confirm its delivery with.
+    fn asked_question_id(command_rx: &mut Receiver<Command>) -> u64 {
+        match next_command(command_rx) {
+            Ok(Command::QuestionAsked { question_id, .. }) => question_id,
+            other => panic!("Expected a QuestionAsked command, got {other:?}"),
+        }
+    }
 
     #[tokio::test]
     async fn test_process_segment_generates_questions() {
@@ -447,18 +1187,18 @@ mod tests {
         let segment = "Let's talk about TCP/IP.".to_string();
 
         // Create a dummy channel for the command. We'll check if a command was sent.
-        let (command_tx, mut command_rx) = tokio::sync::mpsc::channel(1);
+        let (command_tx, mut command_rx) = tokio::sync::mpsc::channel(8);
 
         // --- 2. Act ---
         // Process the segment. This will trigger the call to the (mock) reviewer.
         FeynmanSession::process_segment(&mut session, &mock_reviewer, segment, command_tx).await;
 
         // --- 3. Assert ---
-        // Check that the session state has transitioned correctly to wait for an answer.
+        // Check that the session state has transitioned correctly to wait for the question to be spoken.
         assert_eq!(
             session.state,
-            FeynmanState::AnalyzingAnswers,
-            "Session should be in AnalyzingAnswers state"
+            FeynmanState::DeliveringQuestion,
+            "Session should be in DeliveringQuestion state"
         );
 
         // Check that a command was actually sent.
@@ -466,10 +1206,13 @@ mod tests {
             .try_recv()
             .expect("A command should have been sent");
         match received_command {
-            Command::SpeakText(text) => {
-                assert_eq!(text, "What is TCP/IP?");
+            Command::QuestionAsked {
+                subtopic, question, ..
+            } => {
+                assert_eq!(subtopic, "TCP/IP");
+                assert_eq!(question, "What is TCP/IP?");
             }
-            _ => panic!("Expected a SpeakText command"),
+            _ => panic!("Expected a QuestionAsked command"),
         }
 
         // Check that the question queue has been populated with the expected question.
@@ -483,4 +1226,782 @@ mod tests {
         assert_eq!(question.field, "has_definition");
         assert_eq!(question.question, "What is TCP/IP?");
     }
+
+    #[tokio::test]
+    async fn test_one_message_moves_subtopics_and_asks_first_question() {
+        let mut mock_reviewer = MockReviewer::new();
+        expect_analysis(
+            &mut mock_reviewer,
+            None,
+            vec![
+                subtopic_result("TCP/IP", &[("has_example", "Where is TCP/IP used?")]),
+                subtopic_result("Paging", &[]),
+            ],
+        );
+        let (mut session, command_tx, mut command_rx) = session_with(&["TCP/IP", "Paging"]);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "TCP/IP carries packets, and paging splits memory.".to_string(),
+            command_tx,
+        )
+        .await;
+
+        assert!(session.covered_subtopics.contains_key("Paging"));
+        assert!(!session.incomplete_subtopics.contains_key("Paging"));
+        assert_eq!(
+            session.incomplete_subtopics["TCP/IP"].missing_fields(),
+            vec!["has_example"]
+        );
+        match next_command(&mut command_rx) {
+            Ok(Command::QuestionAsked { question, .. }) => {
+                assert_eq!(question, "Where is TCP/IP used?")
+            }
+            other => panic!("Expected the first question, got {other:?}"),
+        }
+        assert_eq!(session.state, FeynmanState::DeliveringQuestion);
+    }
+
+    #[tokio::test]
+    async fn test_one_message_covering_everything_completes_session() {
+        let mut mock_reviewer = MockReviewer::new();
+        expect_analysis(
+            &mut mock_reviewer,
+            None,
+            vec![
+                subtopic_result("TCP/IP", &[]),
+                subtopic_result("Paging", &[]),
+            ],
+        );
+        let (mut session, command_tx, mut command_rx) = session_with(&["TCP/IP", "Paging"]);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "TCP/IP carries packets, and paging splits memory.".to_string(),
+            command_tx,
+        )
+        .await;
+
+        assert_eq!(session.covered_subtopics.len(), 2);
+        assert!(session.incomplete_subtopics.is_empty());
+        assert!(matches!(
+            next_command(&mut command_rx),
+            Ok(Command::SessionComplete { .. })
+        ));
+        assert_eq!(session.state, FeynmanState::Listening);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_analysis_merges_per_subtopic_results() {
+        let mut mock_reviewer = MockReviewer::new();
+        mock_reviewer
+            .expect_analyze_topic()
+            .withf(|_segment, subtopics| subtopics.len() == 1)
+            .times(2)
+            .returning(|_segment, subtopics| {
+                let name = subtopics[0].name.clone();
+                let json_response = serde_json::json!([{
+                    "subtopic": name,
+                    "has_definition": false,
+                    "has_mechanism": true,
+                    "has_example": true,
+                    "questions": [
+                        { "field": "has_definition", "question": format!("What is {name}?") }
+                    ]
+                }]);
+                Box::pin(async move { Ok(json_response.to_string()) })
+            });
+
+        let subtopic_list = SubTopicList::new(vec![
+            SubTopic::new("TCP/IP".to_string()),
+            SubTopic::new("Paging".to_string()),
+        ]);
+        let mut session =
+            FeynmanSession::new(subtopic_list).with_concurrent_subtopic_analysis(true);
+        let (command_tx, _command_rx) = tokio::sync::mpsc::channel(8);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "TCP/IP sends packets, and paging maps memory.".to_string(),
+            command_tx,
+        )
+        .await;
+
+        let mut questions: Vec<_> = session
+            .question_queue
+            .iter()
+            .map(|q| (q.subtopic.as_str(), q.question.as_str()))
+            .collect();
+        questions.sort();
+        assert_eq!(
+            questions,
+            [("Paging", "What is Paging?"), ("TCP/IP", "What is TCP/IP?")]
+        );
+        assert_eq!(session.incomplete_subtopics.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_answers_only_analyzed_after_question_delivered() {
+        let mut mock_reviewer = reviewer_asking("TCP/IP", &[("has_definition", "What is TCP/IP?")]);
+        mock_reviewer
+            .expect_analyze_answer()
+            .withf(|question, answer| {
+                question == "What is TCP/IP?" && answer == "It is a suite of network protocols."
+            })
+            .returning(|_, _| Box::pin(async { Ok(true) }))
+            .once();
+
+        let (mut session, command_tx, mut command_rx) = session_with(&["TCP/IP"]);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Let's talk about TCP/IP.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        let question_id = asked_question_id(&mut command_rx);
+        assert_eq!(session.state, FeynmanState::DeliveringQuestion);
+
+        // A segment arriving while the question is still being spoken is not treated as an answer.
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "um".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        assert_eq!(session.state, FeynmanState::DeliveringQuestion);
+        assert!(session.answer_buffer.is_empty());
+        assert_eq!(session.in_between_buffer, vec!["um".to_string()]);
+
+        // The end of some other speech, e.g. an earlier reply, doesn't count as delivery.
+        session.question_delivered(question_id - 1);
+        assert_eq!(session.state, FeynmanState::DeliveringQuestion);
+
+        session.question_delivered(question_id);
+        assert_eq!(session.state, FeynmanState::AnalyzingAnswers);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "It is a suite of network protocols.".to_string(),
+            command_tx,
+        )
+        .await;
+        assert!(session.covered_subtopics.contains_key("TCP/IP"));
+        assert!(matches!(
+            next_command(&mut command_rx),
+            Ok(Command::SessionComplete { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_subtopic_stats_track_timeline_and_questions() {
+        let mut mock_reviewer = reviewer_asking(
+            "TCP/IP",
+            &[
+                ("has_definition", "What is TCP/IP?"),
+                ("has_example", "Can you give an example?"),
+            ],
+        );
+        expect_correct_answers(&mut mock_reviewer, 2);
+
+        let clock = Arc::new(crate::clock::ManualClock::new());
+        let (session, command_tx, mut command_rx) = session_with(&["TCP/IP"]);
+        let mut session = session
+            .with_confirm_question_delivery(false)
+            .with_clock(clock.clone());
+
+        // t = 0s: the subtopic is first explained and the first question is asked.
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Let's talk about TCP/IP.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        let stats = &session.subtopic_stats["TCP/IP"];
+        assert_eq!(stats.questions_asked, 1);
+        assert_eq!(stats.time_to_cover, None);
+
+        // t = 30s: the first answer arrives and the second question is asked.
+        clock.advance(Duration::from_secs(30));
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "A suite of protocols.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        assert_eq!(session.subtopic_stats["TCP/IP"].questions_asked, 2);
+
+        // t = 45s: the second answer completes the subtopic.
+        clock.advance(Duration::from_secs(15));
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Loading a web page.".to_string(),
+            command_tx,
+        )
+        .await;
+
+        let stats = &session.subtopic_stats["TCP/IP"];
+        assert_eq!(stats.questions_asked, 2);
+        assert_eq!(stats.time_to_cover, Some(Duration::from_secs(45)));
+
+        let mut completion = None;
+        while let Ok(command) = next_command(&mut command_rx) {
+            if let Command::SessionComplete { message, stats } = command {
+                completion = Some((message, stats));
+            }
+        }
+        let (message, stats) = completion.expect("session should be complete");
+        // The stats are for the teacher, so they aren't part of what gets spoken.
+        assert!(!message.contains("TCP/IP"));
+        assert_eq!(stats, session.subtopic_stats);
+        assert_eq!(
+            format_stats(&stats),
+            "- TCP/IP: covered in 45s, 2 question(s) asked"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let mut mock_reviewer = MockReviewer::new();
+        expect_analysis(
+            &mut mock_reviewer,
+            None,
+            vec![
+                subtopic_result("Paging", &[]),
+                subtopic_result("TCP/IP", &[("has_mechanism", "How does TCP/IP work?")]),
+            ],
+        );
+
+        let (mut session, command_tx, _command_rx) = session_with(&["Paging", "TCP/IP"]);
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Paging and TCP/IP.".to_string(),
+            command_tx,
+        )
+        .await;
+        assert!(session.covered_subtopics.contains_key("Paging"));
+
+        // The snapshot survives serialization and restores the same progress.
+        let snapshot = session.to_snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored_snapshot: SessionSnapshot = serde_json::from_str(&json).unwrap();
+        let mut restored =
+            FeynmanSession::from_snapshot(restored_snapshot, subtopic_list(&["Paging", "TCP/IP"]));
+
+        assert_eq!(restored.to_snapshot(), snapshot);
+        assert_eq!(restored.state, FeynmanState::DeliveringQuestion);
+        assert_eq!(restored.covered_subtopics, session.covered_subtopics);
+        assert_eq!(restored.question_queue, session.question_queue);
+        assert_eq!(restored.subtopic_list.subtopics.len(), 2);
+
+        // Resuming asks the pending question again, and its delivery moves the session on.
+        let (command_tx, mut command_rx) = tokio::sync::mpsc::channel(8);
+        restored.resume(command_tx).await.unwrap();
+        let question_id = match command_rx.try_recv() {
+            Ok(Command::QuestionAsked {
+                question_id,
+                question,
+                ..
+            }) if question == "How does TCP/IP work?" => question_id,
+            other => panic!("Expected the pending question, got {other:?}"),
+        };
+        restored.question_delivered(question_id);
+        assert_eq!(restored.state, FeynmanState::AnalyzingAnswers);
+        assert!(restored.answer_deadline().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_in_between_segments_analyzed_after_questions() {
+        let mut mock_reviewer = MockReviewer::new();
+        expect_analysis(
+            &mut mock_reviewer,
+            Some("Let's talk about TCP/IP."),
+            vec![subtopic_result(
+                "TCP/IP",
+                &[("has_definition", "What is TCP/IP?")],
+            )],
+        );
+        // The held-back segments are combined and analyzed once the question is answered.
+        expect_analysis(
+            &mut mock_reviewer,
+            Some("Also, paging splits memory into pages."),
+            vec![subtopic_result("Paging", &[])],
+        );
+        expect_correct_answers(&mut mock_reviewer, 1);
+
+        let (mut session, command_tx, mut command_rx) = session_with(&["TCP/IP", "Paging"]);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Let's talk about TCP/IP.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        let question_id = asked_question_id(&mut command_rx);
+        assert_eq!(session.state, FeynmanState::DeliveringQuestion);
+
+        // The user keeps talking while the question is being spoken.
+        for segment in ["Also,", "paging splits memory into pages."] {
+            FeynmanSession::process_segment(
+                &mut session,
+                &mock_reviewer,
+                segment.to_string(),
+                command_tx.clone(),
+            )
+            .await;
+        }
+        assert_eq!(session.in_between_buffer.len(), 2);
+
+        session.question_delivered(question_id);
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "A suite of network protocols.".to_string(),
+            command_tx,
+        )
+        .await;
+
+        assert!(session.in_between_buffer.is_empty());
+        assert!(session.covered_subtopics.contains_key("TCP/IP"));
+        assert!(session.covered_subtopics.contains_key("Paging"));
+        assert_eq!(session.state, FeynmanState::Listening);
+        // The in-between segment covered the last subtopic, which ends the session.
+        assert!(matches!(
+            next_command(&mut command_rx),
+            Ok(Command::SessionComplete { .. })
+        ));
+        assert!(next_command(&mut command_rx).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_answer_timeout_nudges_then_ends_session() {
+        let mock_reviewer = reviewer_asking("TCP/IP", &[("has_definition", "What is TCP/IP?")]);
+
+        let (session, command_tx, mut command_rx) = session_with(&["TCP/IP"]);
+        let mut session = session.with_answer_timeout(Duration::from_secs(30));
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Let's talk about TCP/IP.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        let question_id = asked_question_id(&mut command_rx);
+        // No timer runs until the question has actually been spoken.
+        assert_eq!(session.answer_deadline(), None);
+        session.question_delivered(question_id);
+
+        tokio::time::sleep(Duration::from_secs(29)).await;
+        assert!(
+            !session
+                .check_answer_timeout(command_tx.clone())
+                .await
+                .unwrap()
+        );
+        assert!(next_command(&mut command_rx).is_err());
+
+        // The first timeout nudges the user and keeps waiting.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(
+            session
+                .check_answer_timeout(command_tx.clone())
+                .await
+                .unwrap()
+        );
+        match next_command(&mut command_rx) {
+            Ok(Command::SpeakText(text)) => assert_eq!(text, ANSWER_NUDGE),
+            other => panic!("Expected a nudge, got {other:?}"),
+        }
+        assert_eq!(session.state, FeynmanState::AnalyzingAnswers);
+
+        // A second timeout in a row gives up gracefully.
+        tokio::time::sleep_until(session.answer_deadline().unwrap()).await;
+        assert!(session.check_answer_timeout(command_tx).await.unwrap());
+        match next_command(&mut command_rx) {
+            Ok(Command::SessionComplete { message, stats }) => {
+                // Only the goodbye is spoken; the stats travel alongside it.
+                assert_eq!(message, ANSWER_TIMEOUT_GOODBYE);
+                assert_eq!(stats["TCP/IP"].questions_asked, 1);
+            }
+            other => panic!("Expected the session to end, got {other:?}"),
+        }
+        assert_eq!(session.state, FeynmanState::Listening);
+        assert_eq!(session.answer_deadline(), None);
+        // The abandoned question is no longer pending.
+        let snapshot = session.to_snapshot();
+        assert!(snapshot.question_queue.is_empty());
+        assert_eq!(snapshot.current_question_idx, 0);
+        assert!(snapshot.answer_buffer.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unconfirmed_question_delivery_times_out() {
+        let mock_reviewer = reviewer_asking("TCP/IP", &[("has_definition", "What is TCP/IP?")]);
+
+        let (session, command_tx, mut command_rx) = session_with(&["TCP/IP"]);
+        let mut session = session.with_delivery_timeout(Duration::from_secs(10));
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Let's talk about TCP/IP.".to_string(),
+            command_tx,
+        )
+        .await;
+        asked_question_id(&mut command_rx);
+        assert_eq!(session.state, FeynmanState::DeliveringQuestion);
+
+        tokio::time::sleep(Duration::from_secs(9)).await;
+        assert!(!session.check_delivery_timeout());
+        assert_eq!(session.state, FeynmanState::DeliveringQuestion);
+
+        // The question audio never played, so the session stops waiting for it.
+        tokio::time::sleep_until(session.delivery_deadline().unwrap()).await;
+        assert!(session.check_delivery_timeout());
+        assert_eq!(session.state, FeynmanState::AnalyzingAnswers);
+        assert_eq!(session.delivery_deadline(), None);
+        assert!(session.answer_deadline().is_some());
+    }
+
+    #[test]
+    fn test_is_skip_phrase() {
+        assert!(is_skip_phrase("Skip."));
+        assert!(is_skip_phrase("I don't know"));
+        assert!(is_skip_phrase("  no   idea! "));
+        assert!(!is_skip_phrase(
+            "I don't know exactly, but it routes packets."
+        ));
+        assert!(!is_skip_phrase("Packets skip hops."));
+    }
+
+    #[tokio::test]
+    async fn test_skip_phrase_advances_past_question() {
+        let mut mock_reviewer = reviewer_asking(
+            "TCP/IP",
+            &[
+                ("has_definition", "What is TCP/IP?"),
+                ("has_mechanism", "How does TCP/IP work?"),
+            ],
+        );
+        // The skipped question never reaches the reviewer.
+        mock_reviewer.expect_analyze_answer().never();
+
+        let (session, command_tx, mut command_rx) = session_with(&["TCP/IP"]);
+        let mut session = session.with_confirm_question_delivery(false);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Let's talk about TCP/IP.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        assert!(matches!(
+            next_command(&mut command_rx),
+            Ok(Command::QuestionAsked { .. })
+        ));
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "I don't know.".to_string(),
+            command_tx,
+        )
+        .await;
+
+        assert!(matches!(
+            next_command(&mut command_rx),
+            Ok(Command::SkipQuestion)
+        ));
+        match next_command(&mut command_rx) {
+            Ok(Command::QuestionAsked { question, .. }) => {
+                assert_eq!(question, "How does TCP/IP work?")
+            }
+            other => panic!("Expected the next question, got {other:?}"),
+        }
+        // The next question moves things on, so nothing else is said.
+        assert!(next_command(&mut command_rx).is_err());
+        assert_eq!(session.current_question_idx, 1);
+        assert!(session.answer_buffer.is_empty());
+        assert!(!session.incomplete_subtopics["TCP/IP"].has_definition);
+        assert!(!session.covered_subtopics.contains_key("TCP/IP"));
+    }
+
+    #[tokio::test]
+    async fn test_skipping_last_question_is_acknowledged() {
+        let mut mock_reviewer = reviewer_asking("TCP/IP", &[("has_definition", "What is TCP/IP?")]);
+        mock_reviewer.expect_analyze_answer().never();
+
+        let (session, command_tx, mut command_rx) = session_with(&["TCP/IP"]);
+        let mut session = session.with_confirm_question_delivery(false);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Let's talk about TCP/IP.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        asked_question_id(&mut command_rx);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Skip.".to_string(),
+            command_tx,
+        )
+        .await;
+
+        assert!(matches!(
+            next_command(&mut command_rx),
+            Ok(Command::SkipQuestion)
+        ));
+        // With no next question, the skip is still acknowledged out loud.
+        match next_command(&mut command_rx) {
+            Ok(Command::SpeakText(text)) => assert_eq!(text, SKIP_ACKNOWLEDGEMENT),
+            other => panic!("Expected an acknowledgement, got {other:?}"),
+        }
+        assert_eq!(session.state, FeynmanState::Listening);
+        assert!(!session.covered_subtopics.contains_key("TCP/IP"));
+    }
+
+    #[tokio::test]
+    async fn test_progress_reports_covered_counts() {
+        let mut mock_reviewer = MockReviewer::new();
+        expect_analysis(
+            &mut mock_reviewer,
+            None,
+            vec![
+                subtopic_result("Paging", &[]),
+                subtopic_result("TCP/IP", &[("has_example", "Example of TCP/IP?")]),
+            ],
+        );
+        expect_correct_answers(&mut mock_reviewer, 1);
+
+        let (session, command_tx, mut command_rx) = session_with(&["Paging", "TCP/IP", "Caching"]);
+        let mut session = session.with_confirm_question_delivery(false);
+        let progress = |command_rx: &mut Receiver<Command>| {
+            std::iter::from_fn(|| command_rx.try_recv().ok())
+                .filter_map(|command| match command {
+                    Command::Progress {
+                        covered,
+                        total,
+                        current_subtopic,
+                    } => Some((covered, total, current_subtopic)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Paging and TCP/IP.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        assert_eq!(
+            progress(&mut command_rx),
+            vec![(1, 3, Some("TCP/IP".to_string()))]
+        );
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "Loading a web page.".to_string(),
+            command_tx,
+        )
+        .await;
+        assert_eq!(progress(&mut command_rx), vec![(2, 3, None)]);
+    }
+
+    #[tokio::test]
+    async fn test_transient_reviewer_error_keeps_segment_for_retry() {
+        // Get a real network error by calling a port nothing listens on.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let network_error = reqwest::get(url).await.unwrap_err();
+
+        let mut mock_reviewer = MockReviewer::new();
+        mock_reviewer
+            .expect_analyze_topic()
+            .times(1)
+            .return_once(move |_, _| Box::pin(async move { Err(network_error.into()) }));
+        mock_reviewer
+            .expect_analyze_topic()
+            .returning(|_, _| Box::pin(async { Err(ReviewerError::EmptyResponse) }));
+
+        let (mut session, command_tx, _command_rx) = session_with(&["TCP/IP"]);
+
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "TCP/IP is a suite of protocols.".to_string(),
+            command_tx.clone(),
+        )
+        .await;
+        assert_eq!(session.state, FeynmanState::Listening);
+        assert_eq!(
+            session.pending_segments,
+            vec!["TCP/IP is a suite of protocols."]
+        );
+
+        // A non-transient failure drops the segment, including what was kept from before.
+        FeynmanSession::process_segment(
+            &mut session,
+            &mock_reviewer,
+            "TCP/IP carries web traffic.".to_string(),
+            command_tx,
+        )
+        .await;
+        assert_eq!(session.state, FeynmanState::Listening);
+        assert!(session.pending_segments.is_empty());
+    }
+
+    #[test]
+    fn test_progress_of_partly_covered_session() {
+        let mut session = FeynmanSession::new(SubTopicList::new(vec![
+            SubTopic::new("Paging".to_string()),
+            SubTopic::new("TCP/IP".to_string()),
+            SubTopic::new("Caching".to_string()),
+            SubTopic::new("Scheduling".to_string()),
+        ]));
+        assert_eq!(session.progress().percent_complete, 0.0);
+
+        session
+            .covered_subtopics
+            .insert("TCP/IP".to_string(), SubTopic::new("TCP/IP".to_string()));
+        let progress = session.progress();
+        assert_eq!(progress.covered, vec!["TCP/IP"]);
+        assert_eq!(progress.incomplete, vec!["Paging", "Caching", "Scheduling"]);
+        assert_eq!(progress.percent_complete, 25.0);
+    }
+
+    #[tokio::test]
+    async fn test_history_accumulates_in_order() {
+        let mut session = FeynmanSession::new(SubTopicList::new(vec![]));
+        // While analyzing, segments are only buffered, so the reviewer isn't consulted.
+        session.state = FeynmanState::Analyzing;
+        let reviewer = MockReviewer::new();
+        let (command_tx, _command_rx) = tokio::sync::mpsc::channel(8);
+
+        for segment in ["TCP is reliable.", "It retransmits lost packets."] {
+            FeynmanSession::process_segment(
+                &mut session,
+                &reviewer,
+                segment.to_string(),
+                command_tx.clone(),
+            )
+            .await;
+        }
+        session.push_turn(Speaker::Student, "What is a packet?".to_string());
+
+        assert_eq!(
+            session.history,
+            vec![
+                (Speaker::Learner, "TCP is reliable.".to_string()),
+                (Speaker::Learner, "It retransmits lost packets.".to_string()),
+                (Speaker::Student, "What is a packet?".to_string()),
+            ]
+        );
+        assert_eq!(
+            session.transcript(),
+            "Learner: TCP is reliable.\nLearner: It retransmits lost packets.\n\
+             Student: What is a packet?"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_summarize_sends_transcript_and_progress() {
+        let mut session = FeynmanSession::new(SubTopicList::new(vec![
+            SubTopic::new("Paging".to_string()),
+            SubTopic::new("TCP/IP".to_string()),
+        ]));
+        session
+            .covered_subtopics
+            .insert("Paging".to_string(), SubTopic::new("Paging".to_string()));
+        session.push_turn(Speaker::Learner, "Pages are fixed-size blocks.".to_string());
+
+        let mut mock_reviewer = MockReviewer::new();
+        mock_reviewer
+            .expect_summarize_session()
+            .withf(|topic, transcript, covered, incomplete| {
+                topic == "Operating systems"
+                    && transcript == "Learner: Pages are fixed-size blocks."
+                    && covered == ["Paging"]
+                    && incomplete == ["TCP/IP"]
+            })
+            .times(1)
+            .return_once(|_, _, _, _| {
+                Box::pin(async { Ok("You explained paging well.".to_string()) })
+            });
+
+        let summary = session
+            .summarize(&mock_reviewer, "Operating systems")
+            .await
+            .unwrap();
+        assert_eq!(summary, "You explained paging well.");
+    }
+
+    #[test]
+    fn test_reset_after_partial_completion() {
+        let mut session = FeynmanSession::new(SubTopicList::new(vec![
+            SubTopic::new("Paging".to_string()),
+            SubTopic::new("TCP/IP".to_string()),
+        ]))
+        .with_answer_timeout(Duration::from_secs(5));
+        session
+            .covered_subtopics
+            .insert("Paging".to_string(), SubTopic::new("Paging".to_string()));
+        session
+            .incomplete_subtopics
+            .insert("TCP/IP".to_string(), SubTopic::new("TCP/IP".to_string()));
+        session.question_queue.push(QuestionForSubtopic {
+            subtopic: "TCP/IP".to_string(),
+            field: "has_example".to_string(),
+            question: "Example of TCP/IP?".to_string(),
+        });
+        session.state = FeynmanState::AnalyzingAnswers;
+
+        assert_eq!(session.reset(), vec!["Paging", "TCP/IP"]);
+        assert_eq!(session.state, FeynmanState::Listening);
+        assert!(session.covered_subtopics.is_empty());
+        assert!(session.incomplete_subtopics.is_empty());
+        assert!(session.question_queue.is_empty());
+        assert_eq!(session.progress().percent_complete, 0.0);
+        assert_eq!(session.answer_timeout, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_switch_topic_returns_previous_progress() {
+        let mut session = FeynmanSession::new(SubTopicList::new(vec![
+            SubTopic::new("Paging".to_string()),
+            SubTopic::new("TCP/IP".to_string()),
+        ]));
+        session
+            .covered_subtopics
+            .insert("Paging".to_string(), SubTopic::new("Paging".to_string()));
+
+        let previous = session.switch_topic(SubTopicList::new(vec![SubTopic::new(
+            "Enzymes".to_string(),
+        )]));
+
+        assert_eq!(previous.covered, vec!["Paging"]);
+        assert_eq!(previous.percent_complete, 50.0);
+        assert!(session.covered_subtopics.is_empty());
+        assert_eq!(session.progress().incomplete, vec!["Enzymes"]);
+    }
 }
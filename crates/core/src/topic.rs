@@ -1,6 +1,8 @@
+use crate::reviewer::extract_json;
+use anyhow::{Context, Result};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug)]
 pub struct Topic {
@@ -14,6 +16,14 @@ pub struct TopicChange {
     pub new_topic: Option<String>,
 }
 
+impl TopicChange {
+    /// Parses the reply to the `looks_like_topic_change` prompt, with or without a code fence.
+    pub fn parse(raw: &str) -> Result<Self> {
+        serde_json::from_str(extract_json(raw))
+            .with_context(|| format!("Invalid topic change reply: '{}'", raw.trim()))
+    }
+}
+
 // object to hold current topic and topic segments
 #[derive(Debug)]
 pub struct TopicBuffer {
@@ -29,17 +39,32 @@ impl TopicBuffer {
         }
     }
 
-    pub fn add_segment(&mut self, segment: String) {
+    pub fn push_segment(&mut self, segment: String) {
         self.segments.push(segment);
     }
 
+    #[deprecated(note = "use `push_segment`")]
+    pub fn add_segment(&mut self, segment: String) {
+        self.push_segment(segment);
+    }
+
+    /// All segments so far, joined with spaces, as context for the reviewer.
+    pub fn joined_context(&self) -> String {
+        self.segments.join(" ")
+    }
+
+    /// Whether no segments have been added since the buffer was created or cleared.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
     pub fn clear(&mut self) {
         self.topic.clear();
         self.segments.clear();
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubTopic {
     pub name: String,
     pub has_definition: bool,
@@ -57,17 +82,46 @@ impl SubTopic {
         }
     }
 
+    /// Whether the subtopic has a definition, a mechanism, and an example, i.e. is covered.
     pub fn is_complete(&self) -> bool {
-        self.has_definition && self.has_mechanism && self.has_example
+        self.missing_fields().is_empty()
+    }
+
+    /// The fields still to be explained, named as the reviewer names them
+    /// (`has_definition`, `has_mechanism`, `has_example`), in that order.
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        [
+            ("has_definition", self.has_definition),
+            ("has_mechanism", self.has_mechanism),
+            ("has_example", self.has_example),
+        ]
+        .into_iter()
+        .filter(|(_, present)| !present)
+        .map(|(field, _)| field)
+        .collect()
     }
+
     pub fn score(&self) -> u8 {
         self.has_definition as u8 + self.has_mechanism as u8 + self.has_example as u8
     }
 }
 
+/// The score a subtopic must exceed to count as mentioned, unless configured otherwise.
+///
+/// Three letters matched in a row score 71, so the default accepts a short name like "TCP"
+/// said verbatim but rejects it spelled out loosely ("tee cee pee" scores 67).
+pub const DEFAULT_MENTION_THRESHOLD: u8 = 70;
+
+/// The subtopics of a session, with fuzzy detection of which ones a segment mentions.
+///
+/// Matching uses the skim (fzf-style) algorithm: every character of the subtopic name must
+/// appear in the segment, in order and ignoring case. The score grows with each matched
+/// character, with bonuses for consecutive runs and word starts and penalties for gaps, so
+/// it is not a percentage. Longer names score higher; scores are capped at 255.
 pub struct SubTopicList {
     pub subtopics: Vec<SubTopic>,
     matcher: SkimMatcherV2,
+    threshold: u8,
 }
 
 impl SubTopicList {
@@ -75,19 +129,228 @@ impl SubTopicList {
         Self {
             subtopics,
             matcher: SkimMatcherV2::default(),
+            threshold: DEFAULT_MENTION_THRESHOLD,
         }
     }
 
-    // Returns subtopics whose name matches the segment fuzzily above a threshold
-    pub fn find_mentions(&self, segment: &str, threshold: i64) -> Vec<&SubTopic> {
+    /// Sets the score a subtopic must exceed to count as mentioned.
+    /// Lower values catch looser phrasings at the cost of more false positives.
+    pub fn with_threshold(mut self, threshold: u8) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    // Returns subtopics whose name matches the segment fuzzily above the threshold
+    pub fn find_mentions(&self, segment: &str) -> Vec<&SubTopic> {
         let segment_lower = segment.to_lowercase();
         self.subtopics
             .iter()
-            .filter(|subtopic| {
-                let name = subtopic.name.to_lowercase();
-                // Fuzzy match (score above threshold, e.g. 70)
-                self.matcher.fuzzy_match(&segment_lower, &name).unwrap_or(0) > threshold
-            })
+            .filter(|subtopic| self.score(&segment_lower, subtopic) > self.threshold)
+            .collect()
+    }
+
+    /// Scores every subtopic against the segment, highest first, whether or not it clears
+    /// the threshold. Useful for logging near-misses when calibrating the threshold.
+    pub fn find_mentions_scored(&self, segment: &str) -> Vec<(&SubTopic, u8)> {
+        let segment_lower = segment.to_lowercase();
+        let mut scored: Vec<_> = self
+            .subtopics
+            .iter()
+            .map(|subtopic| (subtopic, self.score(&segment_lower, subtopic)))
+            .collect();
+        // A stable sort keeps ties in list order.
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        scored
+    }
+
+    // Scores how strongly `segment_lower` mentions the subtopic; 0 means no match at all.
+    fn score(&self, segment_lower: &str, subtopic: &SubTopic) -> u8 {
+        let name = subtopic.name.to_lowercase();
+        let score = self.matcher.fuzzy_match(segment_lower, &name).unwrap_or(0);
+        score.clamp(0, u8::MAX as i64) as u8
+    }
+}
+
+/// Extracts the items of a list from an LLM reply, ignoring any other lines.
+///
+/// Items may be numbered (`1.` or `1)`) or bulleted (`-` or `*`). Items left empty once
+/// the marker is removed are dropped.
+pub fn parse_numbered_list(raw: &str) -> Vec<String> {
+    raw.lines()
+        .filter_map(|line| strip_list_marker(line.trim()))
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+// Returns the text after a list marker, or `None` if the line isn't a list item.
+fn strip_list_marker(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix(['-', '*']) {
+        // Require whitespace so `**Bold**` headings aren't taken as bullets.
+        return rest.starts_with(char::is_whitespace).then_some(rest);
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..].strip_prefix(['.', ')'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(names: &[&str]) -> SubTopicList {
+        SubTopicList::new(names.iter().map(|n| SubTopic::new(n.to_string())).collect())
+    }
+
+    fn mentioned<'a>(list: &'a SubTopicList, segment: &str) -> Vec<&'a str> {
+        list.find_mentions(segment)
+            .into_iter()
+            .map(|s| s.name.as_str())
             .collect()
     }
+
+    #[test]
+    fn test_topic_change_parse_plain() {
+        let change = TopicChange::parse(r#"{"topic_change": true, "new_topic": "UDP"}"#).unwrap();
+        assert!(change.topic_change);
+        assert_eq!(change.new_topic.as_deref(), Some("UDP"));
+    }
+
+    #[test]
+    fn test_topic_change_parse_fenced() {
+        let raw = "```json\n{\"topic_change\": true, \"new_topic\": \"Routing\"}\n```";
+        let change = TopicChange::parse(raw).unwrap();
+        assert!(change.topic_change);
+        assert_eq!(change.new_topic.as_deref(), Some("Routing"));
+    }
+
+    #[test]
+    fn test_topic_change_parse_null_new_topic() {
+        let change = TopicChange::parse(r#"{"topic_change": false, "new_topic": null}"#).unwrap();
+        assert!(!change.topic_change);
+        assert!(change.new_topic.is_none());
+
+        let change = TopicChange::parse(r#"{"topic_change": false}"#).unwrap();
+        assert!(change.new_topic.is_none());
+
+        assert!(TopicChange::parse("no idea").is_err());
+    }
+
+    #[test]
+    fn test_topic_buffer_joins_segments() {
+        let mut buffer = TopicBuffer::new("TCP".to_string());
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.joined_context(), "");
+
+        buffer.push_segment("TCP is reliable.".to_string());
+        buffer.push_segment("It retransmits lost packets.".to_string());
+        assert!(!buffer.is_empty());
+        assert_eq!(
+            buffer.joined_context(),
+            "TCP is reliable. It retransmits lost packets."
+        );
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert!(buffer.topic.is_empty());
+    }
+
+    #[test]
+    fn test_completeness_for_each_field_combination() {
+        let fields = ["has_definition", "has_mechanism", "has_example"];
+        for mask in 0..8u8 {
+            let subtopic = SubTopic {
+                name: "TCP".to_string(),
+                has_definition: mask & 1 != 0,
+                has_mechanism: mask & 2 != 0,
+                has_example: mask & 4 != 0,
+            };
+            let expected: Vec<&str> = fields
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| mask & (1 << bit) == 0)
+                .map(|(_, field)| *field)
+                .collect();
+            assert_eq!(subtopic.missing_fields(), expected, "mask {mask:03b}");
+            assert_eq!(subtopic.is_complete(), mask == 0b111, "mask {mask:03b}");
+        }
+    }
+
+    #[test]
+    fn test_find_mentions_borderline_matches() {
+        let list = list(&["TCP", "TCP/IP"]);
+        // "TCP/IP" can't be found inside a bare "TCP", but "TCP" is inside "TCP/IP".
+        assert_eq!(mentioned(&list, "TCP is reliable"), vec!["TCP"]);
+        assert_eq!(mentioned(&list, "TCP/IP is a suite"), vec!["TCP", "TCP/IP"]);
+        // Spelled out loosely, a three-letter name falls just short of the default.
+        assert!(mentioned(&list, "tee cee pee").is_empty());
+    }
+
+    #[test]
+    fn test_find_mentions_uses_configured_threshold() {
+        let list = list(&["TCP"]).with_threshold(60);
+        assert_eq!(list.threshold(), 60);
+        assert_eq!(mentioned(&list, "tee cee pee"), vec!["TCP"]);
+
+        let strict = self::list(&["TCP", "Virtual Memory"]).with_threshold(200);
+        assert_eq!(
+            mentioned(&strict, "TCP and virtual memory"),
+            vec!["Virtual Memory"]
+        );
+    }
+
+    #[test]
+    fn test_find_mentions_scored_includes_misses_sorted() {
+        let list = list(&["Paging", "TCP", "Virtual Memory"]);
+        let scored: Vec<(&str, u8)> = list
+            .find_mentions_scored("tee cee pee and virtual memory")
+            .into_iter()
+            .map(|(s, score)| (s.name.as_str(), score))
+            .collect();
+        assert_eq!(
+            scored,
+            vec![("Virtual Memory", 255), ("TCP", 67), ("Paging", 0)]
+        );
+        assert_eq!(
+            mentioned(&list, "tee cee pee and virtual memory"),
+            vec!["Virtual Memory"]
+        );
+    }
+
+    #[test]
+    fn test_parse_numbered_list_markers() {
+        let raw = "1. Processes\n2) Threads\n- Paging\n* Scheduling\n10. Memory";
+        assert_eq!(
+            parse_numbered_list(raw),
+            vec!["Processes", "Threads", "Paging", "Scheduling", "Memory"]
+        );
+    }
+
+    #[test]
+    fn test_parse_numbered_list_ignores_prose_and_empty_items() {
+        let raw = "Sure. Here are the subtopics:\n\n  1.   Processes  \n2.\n- \n\
+                   **Advanced**\nv2.0 notes\nThat's all.";
+        assert_eq!(parse_numbered_list(raw), vec!["Processes"]);
+    }
+
+    #[test]
+    fn test_parse_numbered_list_keeps_dots_inside_items() {
+        let raw = "1. Node.js event loop\n2) I/O (e.g. disks)";
+        assert_eq!(
+            parse_numbered_list(raw),
+            vec!["Node.js event loop", "I/O (e.g. disks)"]
+        );
+    }
+
+    #[test]
+    fn test_parse_numbered_list_empty_input() {
+        assert!(parse_numbered_list("").is_empty());
+        assert!(parse_numbered_list("No list here").is_empty());
+    }
 }
@@ -1,6 +1,13 @@
+use anyhow::Context;
 use base64::Engine;
 use ringbuf::HeapRb;
-use rubato::{FastFixedIn, PolynomialDegree};
+use ringbuf::traits::Producer;
+use rubato::{FastFixedIn, PolynomialDegree, Resampler};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
 
 pub const REALTIME_API_PCM16_SAMPLE_RATE: f64 = 24000.0;
 
@@ -20,6 +27,44 @@ pub fn create_resampler(
     Ok(resampler)
 }
 
+/// Resamples the samples left over in `pending` after the last full chunk.
+///
+/// Fixed-input resamplers only accept whole chunks, so callers drain `input_frames_next()`
+/// samples at a time and keep the remainder buffered. At the end of a turn that remainder
+/// is zero-padded to a full chunk, resampled, and the output trimmed to the length the real
+/// samples map to, so the trailing padding isn't sent as silence. `pending` is left empty.
+pub fn flush_resampler<R: Resampler<f32>>(
+    resampler: &mut R,
+    pending: &mut VecDeque<f32>,
+) -> anyhow::Result<Vec<f32>> {
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+    let remaining: Vec<f32> = pending.drain(..).collect();
+    let expected =
+        (remaining.len() * resampler.output_frames_next()).div_ceil(resampler.input_frames_next());
+    let mut output = resampler
+        .process_partial(Some(&[remaining.as_slice()]), None)
+        .context("Failed to flush resampler")?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    output.truncate(expected);
+    Ok(output)
+}
+
+/// Averages interleaved multi-channel samples down to a single mono channel.
+/// Mono input, and the degenerate `channels == 0`, are returned unchanged.
+pub fn downmix_to_mono(interleaved: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return interleaved.to_vec();
+    }
+    interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
 /// Splits a slice of audio samples into a vector of vectors, where each inner vector has a fixed chunk size.
 /// If a chunk is smaller than the `chunk_size`, it is padded with zeros.
 pub fn split_for_chunks(samples: &[f32], chunk_size: usize) -> Vec<Vec<f32>> {
@@ -38,6 +83,98 @@ pub fn shared_buffer(size: usize) -> HeapRb<f32> {
     HeapRb::new(size)
 }
 
+/// Pushes as many samples as fit into the ring buffer in one call, returning how many were pushed.
+/// Samples past the buffer's free space are not pushed.
+pub fn push_samples<P: Producer<Item = f32>>(producer: &mut P, samples: &[f32]) -> usize {
+    producer.push_slice(samples)
+}
+
+/// Counts samples dropped because the playback ring buffer was full.
+///
+/// Clones share the same count, so the task pushing audio can record drops while another
+/// part of the app reads the total.
+#[derive(Debug, Clone, Default)]
+pub struct OverflowCounter {
+    dropped: Arc<AtomicU64>,
+}
+
+impl OverflowCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `samples` to the count and returns the new total.
+    pub fn record(&self, samples: usize) -> u64 {
+        self.dropped.fetch_add(samples as u64, Ordering::Relaxed) + samples as u64
+    }
+
+    /// The total number of samples dropped so far.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Coordinates draining the playback buffer on shutdown.
+///
+/// After `start`, the task feeding the ring buffer stops queueing new audio and the output
+/// callback calls `mark_drained` once the buffer has run dry. Clones share the same flags.
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackDrain {
+    draining: Arc<AtomicBool>,
+    drained: Arc<AtomicBool>,
+}
+
+impl PlaybackDrain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops accepting new audio; what is already buffered keeps playing.
+    pub fn start(&self) {
+        self.draining.store(true, Ordering::Release);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    /// Records that the buffer is empty. Ignored unless a drain has started.
+    pub fn mark_drained(&self) {
+        if self.is_draining() {
+            self.drained.store(true, Ordering::Release);
+        }
+    }
+
+    pub fn is_drained(&self) -> bool {
+        self.drained.load(Ordering::Acquire)
+    }
+}
+
+/// Lets a barge-in throw away the audio queued for playback.
+///
+/// `request` can be called from any task; the output callback calls `take_request` and
+/// empties its ring buffer when it returns `true`. Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackFlush {
+    requested: Arc<AtomicBool>,
+}
+
+impl PlaybackFlush {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the output callback to drop whatever it has buffered.
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::Release);
+    }
+
+    /// Returns whether a flush was requested since the last call, clearing the request.
+    pub fn take_request(&self) -> bool {
+        self.requested.swap(false, Ordering::AcqRel)
+    }
+}
+
 /// Decodes a vector of base64-encoded audio fragments into a single vector of f32 samples.
 pub fn decode_all(fragments: Vec<String>) -> Vec<f32> {
     fragments
@@ -50,6 +187,13 @@ pub fn decode_all(fragments: Vec<String>) -> Vec<f32> {
         .collect()
 }
 
+/// An audio fragment that couldn't be decoded.
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error("invalid base64 audio: {0}")]
+    InvalidBase64(#[from] base64::DecodeError),
+}
+
 /// Decodes a single base64-encoded string into a vector of f32 PCM samples.
 pub fn decode(base64_fragment: &str) -> Vec<f32> {
     decode_f32(base64_fragment)
@@ -58,32 +202,58 @@ pub fn decode(base64_fragment: &str) -> Vec<f32> {
 /// Decodes a base64 string representing PCM16 audio into a vector of f32 samples.
 /// The function converts the string to a binary vector of u8, interprets chunks as i16 values,
 /// and then normalizes them to f32 values between -1.0 and 1.0.
+/// Logs an error and returns an empty vector if the fragment isn't valid base64;
+/// use `try_decode_f32` to tell a corrupt fragment from silence.
 pub fn decode_f32(base64_fragment: &str) -> Vec<f32> {
-    if let Ok(pcm16) = base64::engine::general_purpose::STANDARD.decode(base64_fragment) {
-        pcm16
-            .chunks_exact(2)
-            .map(|chunk| {
-                let v = i16::from_le_bytes([chunk[0], chunk[1]]);
-                (v as f32 / 32768.0).clamp(-1.0, 1.0)
-            })
-            .collect()
-    } else {
-        tracing::error!("Failed to decode base64 fragment");
+    try_decode_f32(base64_fragment).unwrap_or_else(|e| {
+        tracing::error!("Failed to decode base64 fragment: {}", e);
         Vec::new()
+    })
+}
+
+/// Decodes a base64 string representing PCM16 audio into a vector of f32 samples.
+pub fn try_decode_f32(base64_fragment: &str) -> Result<Vec<f32>, DecodeError> {
+    let mut samples = Vec::new();
+    try_decode_into(base64_fragment, &mut samples)?;
+    Ok(samples)
+}
+
+/// Decodes a base64 string of PCM16 audio into `out`, replacing its contents.
+/// Reusing the same `out` across fragments avoids allocating a new vector for each one.
+/// On a decode error `out` is left empty.
+pub fn decode_into(base64_fragment: &str, out: &mut Vec<f32>) {
+    if let Err(e) = try_decode_into(base64_fragment, out) {
+        tracing::error!("Failed to decode base64 fragment: {}", e);
     }
 }
 
+/// Like `decode_into`, but returns the error instead of logging it.
+pub fn try_decode_into(base64_fragment: &str, out: &mut Vec<f32>) -> Result<(), DecodeError> {
+    out.clear();
+    let pcm16 = base64::engine::general_purpose::STANDARD.decode(base64_fragment)?;
+    out.extend(pcm16.chunks_exact(2).map(|chunk| {
+        let v = i16::from_le_bytes([chunk[0], chunk[1]]);
+        (v as f32 / 32768.0).clamp(-1.0, 1.0)
+    }));
+    Ok(())
+}
+
 /// Decodes a base64 string into a vector of i16 PCM values.
+/// Logs an error and returns an empty vector if the fragment isn't valid base64.
 pub fn decode_i16(base64_fragment: &str) -> Vec<i16> {
-    if let Ok(pcm16) = base64::engine::general_purpose::STANDARD.decode(base64_fragment) {
-        pcm16
-            .chunks_exact(2)
-            .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
-            .collect()
-    } else {
-        tracing::error!("Failed to decode base64 fragment");
+    try_decode_i16(base64_fragment).unwrap_or_else(|e| {
+        tracing::error!("Failed to decode base64 fragment: {}", e);
         Vec::new()
-    }
+    })
+}
+
+/// Decodes a base64 string into a vector of i16 PCM values.
+pub fn try_decode_i16(base64_fragment: &str) -> Result<Vec<i16>, DecodeError> {
+    let pcm16 = base64::engine::general_purpose::STANDARD.decode(base64_fragment)?;
+    Ok(pcm16
+        .chunks_exact(2)
+        .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect())
 }
 
 /// Encodes a slice of f32 samples into a base64 string.
@@ -121,6 +291,311 @@ pub fn convert_i16_to_f32(pcm16: &[i16]) -> Vec<f32> {
         .collect()
 }
 
+/// Compresses PCM16 samples to G.711 μ-law, one byte per sample, as used by `g711_ulaw`.
+pub fn encode_mulaw(pcm16: &[i16]) -> Vec<u8> {
+    pcm16
+        .iter()
+        .map(|&sample| linear_to_mulaw(sample))
+        .collect()
+}
+
+/// Expands G.711 μ-law bytes to PCM16 samples.
+pub fn decode_mulaw(mulaw: &[u8]) -> Vec<i16> {
+    mulaw.iter().map(|&byte| mulaw_to_linear(byte)).collect()
+}
+
+/// Compresses PCM16 samples to G.711 A-law, one byte per sample, as used by `g711_alaw`.
+pub fn encode_alaw(pcm16: &[i16]) -> Vec<u8> {
+    pcm16.iter().map(|&sample| linear_to_alaw(sample)).collect()
+}
+
+/// Expands G.711 A-law bytes to PCM16 samples.
+pub fn decode_alaw(alaw: &[u8]) -> Vec<i16> {
+    alaw.iter().map(|&byte| alaw_to_linear(byte)).collect()
+}
+
+const MULAW_BIAS: i32 = 0x84;
+const MULAW_CLIP: i32 = 32635;
+
+fn linear_to_mulaw(sample: i16) -> u8 {
+    let sign = if sample < 0 { 0x80 } else { 0x00 };
+    let magnitude = (sample as i32).abs().min(MULAW_CLIP) + MULAW_BIAS;
+    // The segment is the position of the highest set bit above the 7 low bits.
+    let exponent = (magnitude >> 7).ilog2() as u8;
+    let mantissa = ((magnitude >> (exponent + 3)) & 0x0F) as u8;
+    !(sign | (exponent << 4) | mantissa)
+}
+
+fn mulaw_to_linear(byte: u8) -> i16 {
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = (byte & 0x0F) as i32;
+    let magnitude = (((mantissa << 3) + MULAW_BIAS) << exponent) - MULAW_BIAS;
+    if byte & 0x80 != 0 {
+        -magnitude as i16
+    } else {
+        magnitude as i16
+    }
+}
+
+fn linear_to_alaw(sample: i16) -> u8 {
+    // A-law works on 13-bit samples; even bits are inverted on the wire.
+    let pcm = (sample >> 3) as i32;
+    let (mask, magnitude) = if pcm >= 0 {
+        (0xD5, pcm)
+    } else {
+        (0x55, -pcm - 1)
+    };
+    let segment = match magnitude {
+        0..=0x1F => 0,
+        _ => (magnitude >> 4).ilog2(),
+    };
+    let shift = if segment < 2 { 1 } else { segment };
+    let code = ((segment << 4) as i32 | ((magnitude >> shift) & 0x0F)) as u8;
+    code ^ mask
+}
+
+fn alaw_to_linear(byte: u8) -> i16 {
+    let byte = byte ^ 0x55;
+    let segment = (byte & 0x70) >> 4;
+    let mut magnitude = ((byte & 0x0F) as i32) << 4;
+    magnitude = match segment {
+        0 => magnitude + 8,
+        1 => magnitude + 0x108,
+        _ => (magnitude + 0x108) << (segment - 1),
+    };
+    if byte & 0x80 != 0 {
+        magnitude as i16
+    } else {
+        -magnitude as i16
+    }
+}
+
+/// An RMS level below which a buffer is treated as silence.
+pub const DEFAULT_SILENCE_THRESHOLD: f32 = 0.001;
+
+/// The root-mean-square level of the samples, or 0.0 for an empty slice.
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum_of_squares: f32 = samples.iter().map(|&sample| sample * sample).sum();
+    (sum_of_squares / samples.len() as f32).sqrt()
+}
+
+/// The largest absolute sample value, or 0.0 for an empty slice.
+pub fn peak(samples: &[f32]) -> f32 {
+    samples
+        .iter()
+        .fold(0.0, |peak, &sample| f32::max(peak, sample.abs()))
+}
+
+/// Whether the samples' RMS level is below `threshold`.
+/// Unlike checking for exact zeros, this tolerates dither and resampling noise.
+pub fn is_silent(samples: &[f32], threshold: f32) -> bool {
+    rms(samples) < threshold
+}
+
+/// Multiplies every sample by `gain`, clamping to [-1.0, 1.0] so a loud signal clips rather
+/// than wrapping around when converted to PCM16.
+pub fn apply_gain(samples: &mut [f32], gain: f32) {
+    for sample in samples {
+        *sample = (*sample * gain).clamp(-1.0, 1.0);
+    }
+}
+
+/// The most `normalize_peak` and `PeakNormalizer` will amplify, so near-silence isn't boosted
+/// into loud hiss.
+pub const MAX_NORMALIZE_GAIN: f32 = 10.0;
+
+/// Scales the samples so their peak reaches `target_peak`, up to `MAX_NORMALIZE_GAIN`.
+/// Silence is left untouched.
+pub fn normalize_peak(samples: &mut [f32], target_peak: f32) {
+    let current_peak = peak(samples);
+    if current_peak == 0.0 {
+        return;
+    }
+    let gain = (target_peak.clamp(0.0, 1.0) / current_peak).min(MAX_NORMALIZE_GAIN);
+    apply_gain(samples, gain);
+}
+
+/// How quickly `PeakNormalizer`'s running peak rises to meet a louder signal.
+pub const DEFAULT_PEAK_ATTACK: Duration = Duration::from_millis(5);
+/// How quickly the running peak falls back once the signal gets quieter.
+pub const DEFAULT_PEAK_RELEASE: Duration = Duration::from_millis(800);
+
+/// Boosts a quiet mono stream so its peaks reach a target level, across many buffers.
+///
+/// The gain follows a smoothed running peak, so it doesn't pump from one buffer to the
+/// next. The peak rises quickly (attack) so a sudden loud sound is caught before it clips,
+/// and falls slowly (release) so the gain creeps back up between words rather than jumping.
+/// The gain is updated every sample, ramping instead of stepping at buffer boundaries. It
+/// never drops below 1.0: loud input is left as is.
+#[derive(Debug, Clone)]
+pub struct PeakNormalizer {
+    target_peak: f32,
+    attack: f32,
+    release: f32,
+    running_peak: f32,
+}
+
+impl PeakNormalizer {
+    /// Creates a normalizer for a mono stream at `sample_rate`, starting at unity gain.
+    pub fn new(target_peak: f32, sample_rate: u32, attack: Duration, release: Duration) -> Self {
+        let target_peak = target_peak.clamp(0.0, 1.0);
+        Self {
+            target_peak,
+            attack: smoothing_coefficient(sample_rate, attack),
+            release: smoothing_coefficient(sample_rate, release),
+            running_peak: target_peak,
+        }
+    }
+
+    /// The gain currently being applied.
+    pub fn gain(&self) -> f32 {
+        if self.running_peak <= 0.0 {
+            return MAX_NORMALIZE_GAIN;
+        }
+        (self.target_peak / self.running_peak).clamp(1.0, MAX_NORMALIZE_GAIN)
+    }
+
+    /// Applies the gain to the next buffer in place.
+    /// A silent buffer keeps the current gain, so pauses don't raise it to amplify the noise floor.
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if is_silent(samples, DEFAULT_SILENCE_THRESHOLD) {
+            apply_gain(samples, self.gain());
+            return;
+        }
+        for sample in samples {
+            let level = sample.abs();
+            let coefficient = if level > self.running_peak {
+                self.attack
+            } else {
+                self.release
+            };
+            self.running_peak = level + coefficient * (self.running_peak - level);
+            *sample = (*sample * self.gain()).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// The per-sample factor for a one-pole smoother that covers ~63% of a step in `time`.
+fn smoothing_coefficient(sample_rate: u32, time: Duration) -> f32 {
+    let samples = sample_rate as f32 * time.as_secs_f32();
+    if samples <= 0.0 {
+        return 0.0;
+    }
+    (-1.0 / samples).exp()
+}
+
+/// How long output must stay silent before speech is considered finished.
+pub const DEFAULT_SPEECH_HANGOVER: Duration = Duration::from_millis(300);
+
+/// Tracks whether an output stream is playing speech, based on buffer energy.
+///
+/// A single silent buffer doesn't end speech: the stream must stay below the threshold
+/// for the whole hangover period, so brief gaps between words don't flip the state.
+#[derive(Debug, Clone)]
+pub struct SpeakingDetector {
+    threshold: f32,
+    hangover_samples: usize,
+    silent_samples: usize,
+    speaking: bool,
+}
+
+impl SpeakingDetector {
+    /// Creates a detector that ends speech after `hangover_samples` consecutive silent samples.
+    pub fn new(threshold: f32, hangover_samples: usize) -> Self {
+        Self {
+            threshold,
+            hangover_samples,
+            silent_samples: 0,
+            speaking: false,
+        }
+    }
+
+    /// Creates a detector for an interleaved stream, with the hangover given as a duration.
+    pub fn for_stream(
+        sample_rate: u32,
+        channels: usize,
+        threshold: f32,
+        hangover: Duration,
+    ) -> Self {
+        let hangover_samples =
+            (sample_rate as f64 * channels as f64 * hangover.as_secs_f64()).round() as usize;
+        Self::new(threshold, hangover_samples)
+    }
+
+    /// Feeds the next output buffer and returns whether speech is in progress.
+    pub fn update(&mut self, samples: &[f32]) -> bool {
+        if is_silent(samples, self.threshold) {
+            self.silent_samples = self.silent_samples.saturating_add(samples.len());
+            if self.silent_samples >= self.hangover_samples {
+                self.speaking = false;
+            }
+        } else {
+            self.silent_samples = 0;
+            self.speaking = true;
+        }
+        self.speaking
+    }
+
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+}
+
+/// Reads a mono PCM16 WAV file, returning its samples and sample rate.
+/// Returns an error for multi-channel or non-PCM16 files.
+pub fn read_wav_pcm16(path: impl AsRef<Path>) -> anyhow::Result<(Vec<i16>, u32)> {
+    let path = path.as_ref();
+    let mut reader = hound::WavReader::open(path)
+        .with_context(|| format!("Failed to open WAV file: {}", path.display()))?;
+    let spec = reader.spec();
+    if spec.channels != 1 {
+        anyhow::bail!(
+            "Unsupported WAV file {}: expected mono, got {} channels",
+            path.display(),
+            spec.channels
+        );
+    }
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+        anyhow::bail!(
+            "Unsupported WAV file {}: expected 16-bit PCM, got {}-bit {:?}",
+            path.display(),
+            spec.bits_per_sample,
+            spec.sample_format
+        );
+    }
+    let samples = reader
+        .samples::<i16>()
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to read samples from {}", path.display()))?;
+    Ok((samples, spec.sample_rate))
+}
+
+/// Writes mono PCM16 samples to a WAV file at the given sample rate.
+pub fn write_wav_pcm16(
+    path: impl AsRef<Path>,
+    samples: &[i16],
+    sample_rate: u32,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .with_context(|| format!("Failed to create WAV file: {}", path.display()))?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
 /// A trait for converting audio sample types to a binary representation (Vec<u8>).
 pub trait ToBinary {
     fn to_binary(&self) -> Vec<u8>;
@@ -144,3 +619,372 @@ impl ToBinary for [f32] {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 24000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_downmix_to_mono() {
+        assert_eq!(downmix_to_mono(&[0.1, 0.2, 0.3], 1), vec![0.1, 0.2, 0.3]);
+        assert_eq!(downmix_to_mono(&[0.2, 0.4, -0.5, 0.5], 2), vec![0.3, 0.0]);
+        let surround = [0.6, 0.6, 0.0, 0.0, 0.0, 0.0, 1.2, 1.2, 1.2, 1.2, 1.2, 1.2];
+        let mono = downmix_to_mono(&surround, 6);
+        assert_eq!(mono.len(), 2);
+        assert!((mono[0] - 0.2).abs() < 1e-6);
+        assert!((mono[1] - 1.2).abs() < 1e-6);
+        assert_eq!(downmix_to_mono(&[0.1, 0.2], 0), vec![0.1, 0.2]);
+    }
+
+    #[test]
+    fn test_flush_resampler_emits_tail_samples() {
+        let mut resampler = create_resampler(48000.0, 24000.0, 1024).unwrap();
+        let mut pending: VecDeque<f32> = sine(0.5, 100).into();
+
+        let output = flush_resampler(&mut resampler, &mut pending).unwrap();
+
+        // 100 samples at 48 kHz map to about 50 at 24 kHz; the zero padding is trimmed off.
+        assert!(
+            (50..=51).contains(&output.len()),
+            "got {} samples",
+            output.len()
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_flush_resampler_with_nothing_pending() {
+        let mut resampler = create_resampler(48000.0, 24000.0, 1024).unwrap();
+        let mut pending = VecDeque::new();
+
+        assert!(
+            flush_resampler(&mut resampler, &mut pending)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_rms_and_peak_of_sine() {
+        // One second of a 440 Hz tone: RMS is amplitude / sqrt(2).
+        let samples = sine(0.5, 24000);
+        assert!((rms(&samples) - 0.5 / 2f32.sqrt()).abs() < 1e-3);
+        assert!((peak(&samples) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_apply_gain_clamps_instead_of_clipping_past_full_scale() {
+        let mut samples = vec![0.1, -0.25, 0.6, -0.9];
+        apply_gain(&mut samples, 2.0);
+        assert_eq!(samples, [0.2, -0.5, 1.0, -1.0]);
+        assert_eq!(convert_f32_to_i16(&samples)[2..], [i16::MAX, -i16::MAX]);
+    }
+
+    #[test]
+    fn test_normalize_peak_reaches_target() {
+        let mut samples = sine(0.1, 2400);
+        normalize_peak(&mut samples, 0.8);
+        assert!((peak(&samples) - 0.8).abs() < 1e-4);
+
+        // Louder input is turned down to the target too.
+        let mut samples = sine(0.95, 2400);
+        normalize_peak(&mut samples, 0.5);
+        assert!((peak(&samples) - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_normalize_peak_caps_gain_and_skips_silence() {
+        let mut samples = vec![0.001, -0.002];
+        normalize_peak(&mut samples, 0.9);
+        assert!((peak(&samples) - 0.002 * MAX_NORMALIZE_GAIN).abs() < 1e-6);
+
+        let mut silence = vec![0.0; 16];
+        normalize_peak(&mut silence, 0.9);
+        assert!(silence.iter().all(|&sample| sample == 0.0));
+    }
+
+    fn normalize_in_chunks(normalizer: &mut PeakNormalizer, samples: &mut [f32]) {
+        for chunk in samples.chunks_mut(480) {
+            normalizer.process(chunk);
+        }
+    }
+
+    #[test]
+    fn test_peak_normalizer_boosts_quiet_input_to_target() {
+        let mut normalizer =
+            PeakNormalizer::new(0.8, 24000, DEFAULT_PEAK_ATTACK, DEFAULT_PEAK_RELEASE);
+        let mut samples = sine(0.1, 24000 * 5);
+        normalize_in_chunks(&mut normalizer, &mut samples);
+        // Once settled, the peaks sit at the target, give or take the smoothing.
+        let settled = peak(&samples[24000 * 4..]);
+        assert!((settled - 0.8).abs() < 0.04, "settled peak {settled}");
+    }
+
+    #[test]
+    fn test_peak_normalizer_ramps_gain_without_steps() {
+        let mut normalizer =
+            PeakNormalizer::new(0.8, 24000, DEFAULT_PEAK_ATTACK, DEFAULT_PEAK_RELEASE);
+        let mut gains = Vec::new();
+        for chunk in sine(0.1, 24000).chunks_mut(480) {
+            normalizer.process(chunk);
+            gains.push(normalizer.gain());
+        }
+        // The gain rises gradually rather than jumping to 8x on the first buffer.
+        assert!(gains[0] < 1.5, "first gain {}", gains[0]);
+        for pair in gains.windows(2) {
+            assert!(pair[1] >= pair[0]);
+            assert!(pair[1] - pair[0] < 0.5, "gain jumped {pair:?}");
+        }
+    }
+
+    #[test]
+    fn test_peak_normalizer_leaves_loud_input_alone() {
+        let mut normalizer =
+            PeakNormalizer::new(0.5, 24000, DEFAULT_PEAK_ATTACK, DEFAULT_PEAK_RELEASE);
+        let original = sine(0.95, 4800);
+        let mut samples = original.clone();
+        normalize_in_chunks(&mut normalizer, &mut samples);
+        // Louder input isn't turned down to the target.
+        for (processed, original) in samples.iter().zip(&original) {
+            assert!((processed - original).abs() < 1e-3);
+        }
+        assert_eq!(normalizer.gain(), 1.0);
+    }
+
+    #[test]
+    fn test_peak_normalizer_caps_gain_and_holds_it_through_silence() {
+        let mut normalizer =
+            PeakNormalizer::new(0.9, 24000, DEFAULT_PEAK_ATTACK, DEFAULT_PEAK_RELEASE);
+        normalize_in_chunks(&mut normalizer, &mut sine(0.01, 24000 * 10));
+        assert_eq!(normalizer.gain(), MAX_NORMALIZE_GAIN);
+
+        let mut normalizer =
+            PeakNormalizer::new(0.9, 24000, DEFAULT_PEAK_ATTACK, DEFAULT_PEAK_RELEASE);
+        normalize_in_chunks(&mut normalizer, &mut sine(0.45, 24000));
+        let gain = normalizer.gain();
+        let mut silence = vec![0.0; 24000];
+        normalize_in_chunks(&mut normalizer, &mut silence);
+        assert!(silence.iter().all(|&sample| sample == 0.0));
+        assert_eq!(normalizer.gain(), gain);
+    }
+
+    #[test]
+    fn test_silence_detection() {
+        assert_eq!(rms(&[]), 0.0);
+        assert_eq!(peak(&[]), 0.0);
+        assert!(is_silent(&[0.0; 512], DEFAULT_SILENCE_THRESHOLD));
+
+        // Low-level dither is silent even though no sample is exactly zero.
+        let dither: Vec<f32> = (0..512)
+            .map(|i| if i % 2 == 0 { 1e-4 } else { -1e-4 })
+            .collect();
+        assert!(is_silent(&dither, DEFAULT_SILENCE_THRESHOLD));
+
+        assert!(!is_silent(&sine(0.1, 512), DEFAULT_SILENCE_THRESHOLD));
+    }
+
+    #[test]
+    fn test_speaking_detector_hangover() {
+        // 100 samples per buffer, hangover of 3 buffers.
+        let mut detector = SpeakingDetector::new(DEFAULT_SILENCE_THRESHOLD, 300);
+        let speech = sine(0.2, 100);
+        let silence = [0.0; 100];
+
+        assert!(!detector.update(&silence));
+        assert!(detector.update(&speech));
+
+        // A short gap between words keeps the speaking state.
+        assert!(detector.update(&silence));
+        assert!(detector.update(&silence));
+        assert!(detector.update(&speech));
+
+        // Silence lasting the full hangover ends it.
+        assert!(detector.update(&silence));
+        assert!(detector.update(&silence));
+        assert!(!detector.update(&silence));
+        assert!(!detector.is_speaking());
+    }
+
+    #[test]
+    fn test_speaking_detector_for_stream() {
+        let detector = SpeakingDetector::for_stream(48000, 2, 0.001, Duration::from_millis(250));
+        assert_eq!(detector.hangover_samples, 24000);
+    }
+
+    #[test]
+    fn test_decode_into_reuses_buffer() {
+        let mut out = vec![0.5; 10];
+
+        decode_into(&encode_i16(&[0, 16384, -16384]), &mut out);
+        assert_eq!(out, vec![0.0, 0.5, -0.5]);
+
+        decode_into("not base64!", &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_try_decode_reports_corrupt_fragments() {
+        let fragment = encode_i16(&[0, 16384]);
+        assert_eq!(try_decode_i16(&fragment).unwrap(), vec![0, 16384]);
+        assert_eq!(try_decode_f32(&fragment).unwrap(), vec![0.0, 0.5]);
+
+        // An empty fragment is silence, not an error.
+        assert!(try_decode_f32("").unwrap().is_empty());
+
+        assert!(matches!(
+            try_decode_f32("not base64!"),
+            Err(DecodeError::InvalidBase64(_))
+        ));
+        assert!(try_decode_i16("not base64!").is_err());
+        // The infallible versions still fall back to no samples.
+        assert!(decode_f32("not base64!").is_empty());
+    }
+
+    #[test]
+    fn test_push_samples_stops_when_full() {
+        let (mut producer, mut consumer) = ringbuf::traits::Split::split(shared_buffer(4));
+
+        assert_eq!(push_samples(&mut producer, &[0.1, 0.2, 0.3]), 3);
+        assert_eq!(push_samples(&mut producer, &[0.4, 0.5]), 1);
+
+        let played: Vec<f32> = ringbuf::traits::Consumer::pop_iter(&mut consumer).collect();
+        assert_eq!(played, vec![0.1, 0.2, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn test_overflow_counter_is_shared_between_clones() {
+        let counter = OverflowCounter::new();
+        let reporter = counter.clone();
+
+        assert_eq!(counter.record(3), 3);
+        assert_eq!(counter.record(2), 5);
+        assert_eq!(reporter.dropped(), 5);
+    }
+
+    #[test]
+    fn test_playback_drain() {
+        let drain = PlaybackDrain::new();
+        let callback = drain.clone();
+
+        // An empty buffer before shutdown doesn't count as drained.
+        callback.mark_drained();
+        assert!(!drain.is_drained());
+
+        drain.start();
+        assert!(callback.is_draining());
+        assert!(!drain.is_drained());
+        callback.mark_drained();
+        assert!(drain.is_drained());
+    }
+
+    #[test]
+    fn test_playback_flush_is_taken_once() {
+        let flush = PlaybackFlush::new();
+        let callback = flush.clone();
+        assert!(!callback.take_request());
+
+        flush.request();
+        flush.request();
+        assert!(callback.take_request());
+        assert!(!callback.take_request());
+    }
+
+    #[test]
+    fn test_wav_round_trip_through_base64() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("speech.wav");
+        let samples = vec![0, 1000, -1000, i16::MAX, i16::MIN];
+
+        // Audio received from the API is written to disk, then read back and re-encoded.
+        let received = encode_i16(&samples);
+        write_wav_pcm16(&path, &decode_i16(&received), 24000).unwrap();
+        let (read, sample_rate) = read_wav_pcm16(&path).unwrap();
+
+        assert_eq!(sample_rate, 24000);
+        assert_eq!(read, samples);
+        assert_eq!(encode_i16(&read), received);
+    }
+
+    #[test]
+    fn test_read_wav_rejects_stereo_and_float() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let stereo = dir.path().join("stereo.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 24000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        hound::WavWriter::create(&stereo, spec)
+            .unwrap()
+            .finalize()
+            .unwrap();
+        assert!(read_wav_pcm16(&stereo).is_err());
+
+        let float = dir.path().join("float.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 24000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        hound::WavWriter::create(&float, spec)
+            .unwrap()
+            .finalize()
+            .unwrap();
+        assert!(read_wav_pcm16(&float).is_err());
+
+        assert!(read_wav_pcm16(dir.path().join("missing.wav")).is_err());
+    }
+
+    #[test]
+    fn test_mulaw_known_values() {
+        // Reference pairs from the G.711 μ-law tables.
+        assert_eq!(
+            encode_mulaw(&[0, -1, 32767, -32768, 1000, -1000]),
+            [0xFF, 0x7F, 0x80, 0x00, 0xCE, 0x4E]
+        );
+        assert_eq!(
+            decode_mulaw(&[0xFF, 0x7F, 0x80, 0x00, 0xCE, 0x4E, 0xF0]),
+            [0, 0, 32124, -32124, 988, -988, 120]
+        );
+    }
+
+    #[test]
+    fn test_alaw_known_values() {
+        // Reference pairs from the G.711 A-law tables.
+        assert_eq!(
+            encode_alaw(&[0, -1, 32767, -32768, 1000, -1000]),
+            [0xD5, 0x55, 0xAA, 0x2A, 0xFA, 0x7A]
+        );
+        assert_eq!(
+            decode_alaw(&[0xD5, 0x55, 0xAA, 0x2A, 0xFA, 0x7A]),
+            [8, -8, 32256, -32256, 1008, -1008]
+        );
+    }
+
+    #[test]
+    fn test_companding_stays_within_quantization_error() {
+        for sample in (i16::MIN..=i16::MAX).step_by(97) {
+            let mulaw = decode_mulaw(&encode_mulaw(&[sample]))[0];
+            let alaw = decode_alaw(&encode_alaw(&[sample]))[0];
+            // The step size grows with the magnitude, up to 1/16 of it in the top segment.
+            let tolerance = (sample as i32).abs() / 16 + 16;
+            assert!(
+                (mulaw as i32 - sample as i32).abs() <= tolerance,
+                "μ-law {sample}"
+            );
+            assert!(
+                (alaw as i32 - sample as i32).abs() <= tolerance,
+                "A-law {sample}"
+            );
+        }
+    }
+}
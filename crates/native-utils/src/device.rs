@@ -1,3 +1,4 @@
+use anyhow::Context;
 use cpal::Device;
 use cpal::traits::{DeviceTrait, HostTrait};
 
@@ -17,9 +18,13 @@ pub fn get_or_default_input(device_name: Option<String>) -> anyhow::Result<Devic
     // Track the ID of the host for debugging.
     tracing::debug!("Host: {:?}", host.id());
     // Set the target device name to the provided parameter or the default input device.
-    let target = device_name
-        .clone()
-        .unwrap_or_else(|| host.default_input_device().unwrap().name().unwrap());
+    let target = match device_name {
+        Some(name) => name,
+        None => host
+            .default_input_device()
+            .context("No default input device is available; is any audio hardware connected?")?
+            .name()?,
+    };
 
     // Search for the target device among available input devices.
     let mut device: Option<Device> = None;
@@ -43,9 +48,13 @@ pub fn get_or_default_input(device_name: Option<String>) -> anyhow::Result<Devic
 /// Gets a specific output device by name, or falls back to the host's default output device.
 pub fn get_or_default_output(device_name: Option<String>) -> anyhow::Result<Device> {
     let host = get_host();
-    let target = device_name
-        .clone()
-        .unwrap_or_else(|| host.default_output_device().unwrap().name().unwrap());
+    let target = match device_name {
+        Some(name) => name,
+        None => host
+            .default_output_device()
+            .context("No default output device is available; is any audio hardware connected?")?
+            .name()?,
+    };
 
     let mut device: Option<Device> = None;
     let output_devices = host.output_devices()?;
@@ -65,70 +74,220 @@ pub fn get_or_default_output(device_name: Option<String>) -> anyhow::Result<Devi
     Ok(device)
 }
 
-/// Returns a formatted string listing all available audio input devices.
-pub fn get_available_inputs() -> String {
+/// Gets the input device whose name contains `query`, ignoring case.
+pub fn get_input_by_substring(query: &str) -> anyhow::Result<Device> {
+    let host = get_host();
+    select_by_substring(host.input_devices()?, query, "input")
+}
+
+/// Gets the output device whose name contains `query`, ignoring case.
+pub fn get_output_by_substring(query: &str) -> anyhow::Result<Device> {
+    let host = get_host();
+    select_by_substring(host.output_devices()?, query, "output")
+}
+
+/// Picks the single device whose name contains `query`, ignoring case.
+/// An exact (case-insensitive) name match wins when several devices contain the query,
+/// otherwise an ambiguous query is an error that lists every candidate.
+fn select_by_substring(
+    devices: impl Iterator<Item = Device>,
+    query: &str,
+    kind: &str,
+) -> anyhow::Result<Device> {
+    let devices: Vec<(String, Device)> = devices
+        .filter_map(|device| device.name().ok().map(|name| (name, device)))
+        .collect();
+    let names: Vec<&str> = devices.iter().map(|(name, _)| name.as_str()).collect();
+    let index = match_substring(&names, query).map_err(|candidates| {
+        if candidates.is_empty() {
+            anyhow::anyhow!("No {} device matches '{}'", kind, query)
+        } else {
+            anyhow::anyhow!(
+                "'{}' matches several {} devices: {}",
+                query,
+                kind,
+                candidates.join(", ")
+            )
+        }
+    })?;
+    Ok(devices.into_iter().nth(index).map(|(_, device)| device).unwrap())
+}
+
+/// Returns the index of the one name matching `query`, or every matching name on failure.
+fn match_substring(names: &[&str], query: &str) -> Result<usize, Vec<String>> {
+    let query = query.to_lowercase();
+    let matches: Vec<usize> = names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| name.to_lowercase().contains(&query))
+        .map(|(i, _)| i)
+        .collect();
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        _ => matches
+            .iter()
+            .copied()
+            .find(|&i| names[i].to_lowercase() == query)
+            .ok_or_else(|| matches.iter().map(|&i| names[i].to_string()).collect()),
+    }
+}
+
+/// Describes an audio device and its default stream configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub name: String,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub is_default: bool,
+}
+
+/// Lists all available audio input devices with their default input configuration.
+/// Devices whose name or configuration can't be read are skipped with a warning.
+pub fn list_input_devices() -> anyhow::Result<Vec<DeviceInfo>> {
     for host in cpal::available_hosts() {
         tracing::debug!("Available host: {:?}", host);
     }
 
     let host = get_host();
-
-    let mut device_names: Vec<String> = Vec::new();
-    // Get the default device name for comparison.
-    let default_device = host
-        .default_input_device()
-        .expect("No default input device")
-        .name()
-        .expect("Default input device has no name...");
-    // Iterate through all found input devices.
-    let input_devices = host.input_devices().expect("No input devices found");
-    for in_device in input_devices {
-        let d_name = in_device.name().expect("Device has no name...");
+    // Get the default device name for comparison. Having no default device is not an error here.
+    let default_device = host.default_input_device().and_then(|d| d.name().ok());
+    let mut devices = Vec::new();
+    // Iterate through all found input devices. One broken device shouldn't hide the rest.
+    for in_device in host.input_devices()? {
+        let name = match in_device.name() {
+            Ok(name) => name,
+            Err(e) => {
+                tracing::warn!("Skipping input device with no name: {}", e);
+                continue;
+            }
+        };
         // Check the device's default configuration.
-        let d_cfg = in_device
-            .default_input_config()
-            .expect("Device has no default input config...");
-        let d_sampling_rate = d_cfg.sample_rate().0;
-        let d_ch = d_cfg.channels();
-
-        // Format the output string.
-        let mut d = format!(" * {}({}ch, {}hz)", d_name, d_ch, d_sampling_rate);
-        if d_name == default_device {
-            d.push_str(" [default]");
-        }
-        device_names.push(d);
+        let cfg = match in_device.default_input_config() {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                tracing::warn!("Skipping input device '{}' with no default config: {}", name, e);
+                continue;
+            }
+        };
+        devices.push(DeviceInfo {
+            is_default: default_device.as_deref() == Some(name.as_str()),
+            name,
+            channels: cfg.channels(),
+            sample_rate: cfg.sample_rate().0,
+        });
     }
-    // Return a single string with each device on a new line.
-    device_names.join("\n")
+    Ok(devices)
 }
 
-/// Returns a formatted string listing all available audio output devices.
-pub fn get_available_outputs() -> String {
+/// Lists all available audio output devices with their default output configuration.
+/// Devices whose name or configuration can't be read are skipped with a warning.
+pub fn list_output_devices() -> anyhow::Result<Vec<DeviceInfo>> {
     for host in cpal::available_hosts() {
         tracing::debug!("Available host: {:?}", host);
     }
 
     let host = get_host();
-    let mut device_names: Vec<String> = Vec::new();
-    let default_device = host
-        .default_output_device()
-        .expect("No default output device")
-        .name()
-        .expect("Default output device has no name...");
-    let output_devices = host.output_devices().expect("No output devices found");
-    for out_device in output_devices {
-        let d_name = out_device.name().expect("Device has no name...");
-        let d_cfg = out_device
-            .default_output_config()
-            .expect("Device has no default output config...");
-        let d_sampling_rate = d_cfg.sample_rate().0;
-        let d_ch = d_cfg.channels();
-
-        let mut d = format!(" * {}({}ch, {}hz)", d_name, d_ch, d_sampling_rate);
-        if d_name == default_device {
-            d.push_str(" [default]");
-        }
-        device_names.push(d);
+    let default_device = host.default_output_device().and_then(|d| d.name().ok());
+    let mut devices = Vec::new();
+    for out_device in host.output_devices()? {
+        let name = match out_device.name() {
+            Ok(name) => name,
+            Err(e) => {
+                tracing::warn!("Skipping output device with no name: {}", e);
+                continue;
+            }
+        };
+        let cfg = match out_device.default_output_config() {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                tracing::warn!("Skipping output device '{}' with no default config: {}", name, e);
+                continue;
+            }
+        };
+        devices.push(DeviceInfo {
+            is_default: default_device.as_deref() == Some(name.as_str()),
+            name,
+            channels: cfg.channels(),
+            sample_rate: cfg.sample_rate().0,
+        });
     }
-    device_names.join("\n")
-}
\ No newline at end of file
+    Ok(devices)
+}
+
+/// Formats devices one per line, marking the default device.
+fn format_devices(devices: &[DeviceInfo]) -> String {
+    devices
+        .iter()
+        .map(|d| {
+            let mut line = format!(" * {}({}ch, {}hz)", d.name, d.channels, d.sample_rate);
+            if d.is_default {
+                line.push_str(" [default]");
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns a formatted string listing all available audio input devices.
+pub fn get_available_inputs() -> anyhow::Result<String> {
+    Ok(format_devices(&list_input_devices()?))
+}
+
+/// Returns a formatted string listing all available audio output devices.
+pub fn get_available_outputs() -> anyhow::Result<String> {
+    Ok(format_devices(&list_output_devices()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 3] = [
+        "MacBook Pro Microphone (Built-in)",
+        "USB Audio Device",
+        "USB Audio Device 2",
+    ];
+
+    #[test]
+    fn test_format_devices_marks_default() {
+        let devices = [
+            DeviceInfo {
+                name: "Built-in Microphone".to_string(),
+                channels: 1,
+                sample_rate: 48000,
+                is_default: true,
+            },
+            DeviceInfo {
+                name: "USB Audio Device".to_string(),
+                channels: 2,
+                sample_rate: 44100,
+                is_default: false,
+            },
+        ];
+        assert_eq!(
+            format_devices(&devices),
+            " * Built-in Microphone(1ch, 48000hz) [default]\n * USB Audio Device(2ch, 44100hz)"
+        );
+    }
+
+    #[test]
+    fn test_match_substring_ignores_case() {
+        assert_eq!(match_substring(&NAMES, "mic"), Ok(0));
+        assert_eq!(match_substring(&NAMES, "MACBOOK"), Ok(0));
+    }
+
+    #[test]
+    fn test_match_substring_prefers_exact_name() {
+        assert_eq!(match_substring(&NAMES, "usb audio device"), Ok(1));
+    }
+
+    #[test]
+    fn test_match_substring_reports_candidates() {
+        assert_eq!(
+            match_substring(&NAMES, "usb"),
+            Err(vec!["USB Audio Device".to_string(), "USB Audio Device 2".to_string()])
+        );
+        assert_eq!(match_substring(&NAMES, "headset"), Err(vec![]));
+    }
+}
@@ -2,8 +2,98 @@ mod turn_detection;
 mod transcription;
 mod consts;
 
-pub use turn_detection::{TurnDetection, ServerVadTurnDetection};
+pub use turn_detection::{
+    Eagerness, SemanticVadTurnDetection, ServerVadTurnDetection, TurnDetection,
+};
 pub use transcription::InputAudioTranscription;
 pub use consts::*;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
 /// Audio data encoded as base64
-pub type Base64EncodedAudioBytes = String;
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Base64EncodedAudioBytes(String);
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AudioBytesError {
+    #[error("audio is not valid base64: {0}")]
+    InvalidBase64(String),
+    #[error("PCM16 audio must have an even number of bytes, got {0}")]
+    OddByteCount(usize),
+}
+
+impl Base64EncodedAudioBytes {
+    /// Checks that `s` is base64 holding whole PCM16 samples, unlike `From`, which takes
+    /// any string.
+    pub fn try_new(s: &str) -> Result<Self, AudioBytesError> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(s)
+            .map_err(|e| AudioBytesError::InvalidBase64(e.to_string()))?;
+        if bytes.len() % 2 != 0 {
+            return Err(AudioBytesError::OddByteCount(bytes.len()));
+        }
+        Ok(Self(s.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for Base64EncodedAudioBytes {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&str> for Base64EncodedAudioBytes {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+impl std::ops::Deref for Base64EncodedAudioBytes {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Base64EncodedAudioBytes {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_new_accepts_pcm16() {
+        // Two samples: 0x0001 and 0xffff.
+        let audio = Base64EncodedAudioBytes::try_new("AQD//w==").unwrap();
+        assert_eq!(audio.as_str(), "AQD//w==");
+        assert_eq!(serde_json::to_value(&audio).unwrap(), "AQD//w==");
+        assert!(Base64EncodedAudioBytes::try_new("").is_ok());
+    }
+
+    #[test]
+    fn test_try_new_rejects_bad_audio() {
+        assert!(matches!(
+            Base64EncodedAudioBytes::try_new("not base64!"),
+            Err(AudioBytesError::InvalidBase64(_))
+        ));
+        // Three bytes can't be whole 16-bit samples.
+        assert_eq!(
+            Base64EncodedAudioBytes::try_new("AQID"),
+            Err(AudioBytesError::OddByteCount(3))
+        );
+    }
+}
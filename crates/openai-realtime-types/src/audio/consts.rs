@@ -111,6 +111,7 @@ impl<'de> Deserialize<'de> for TranscriptionModel {
     }
 }
 
+#[cfg(test)]
 mod test {
 
     #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -35,7 +35,7 @@ impl InputAudioTranscription {
     }
 
     pub fn enabled(&self) -> bool {
-        self.enabled.map_or(true, |x| x)
+        self.enabled.unwrap_or(true)
     }
 
     pub fn model(&self) -> TranscriptionModel {
@@ -3,6 +3,10 @@
 pub enum TurnDetection {
     #[serde(rename = "server_vad")]
     ServerVad(ServerVadTurnDetection),
+    /// Ends the turn when a model judges the user has finished their thought, rather than on
+    /// silence alone.
+    #[serde(rename = "semantic_vad")]
+    SemanticVad(SemanticVadTurnDetection),
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
@@ -28,6 +32,63 @@ pub struct ServerVadTurnDetection {
     create_response: Option<bool>,
 }
 
+/// How quickly semantic VAD ends the user's turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Eagerness {
+    /// Waits longer, letting the user pause mid-thought.
+    Low,
+    Medium,
+    /// Responds as soon as possible.
+    High,
+    /// Lets the server choose; currently the same as `Medium`.
+    Auto,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+pub struct SemanticVadTurnDetection {
+    /// How quickly the model ends the user's turn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    eagerness: Option<Eagerness>,
+
+    /// Whether the model should interrupt its response when the user starts speaking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interrupt_response: Option<bool>,
+
+    /// Whether to automatically create a response when the user stops speaking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    create_response: Option<bool>,
+}
+
+impl SemanticVadTurnDetection {
+    pub fn with_eagerness(mut self, eagerness: Eagerness) -> Self {
+        self.eagerness = Some(eagerness);
+        self
+    }
+
+    pub fn with_interrupt_response(mut self, interrupt: bool) -> Self {
+        self.interrupt_response = Some(interrupt);
+        self
+    }
+
+    pub fn with_create_response(mut self, create: bool) -> Self {
+        self.create_response = Some(create);
+        self
+    }
+
+    pub fn eagerness(&self) -> Option<Eagerness> {
+        self.eagerness
+    }
+
+    pub fn interrupt_response(&self) -> Option<bool> {
+        self.interrupt_response
+    }
+
+    pub fn create_response(&self) -> Option<bool> {
+        self.create_response
+    }
+}
+
 impl Default for TurnDetection {
     fn default() -> Self {
         Self::ServerVad(ServerVadTurnDetection::default())
@@ -80,3 +141,74 @@ impl ServerVadTurnDetection {
         self.create_response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_vad_parameters_round_trip() {
+        let turn_detection = TurnDetection::ServerVad(
+            ServerVadTurnDetection::default()
+                .with_threshold(0.6)
+                .with_prefix_padding_ms(200)
+                .with_silence_duration_ms(800)
+                .with_interrupt_response(true)
+                .with_create_response(false),
+        );
+
+        let json = serde_json::to_value(&turn_detection).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "server_vad",
+                "threshold": 0.6f32,
+                "prefix_padding_ms": 200,
+                "silence_duration_ms": 800,
+                "interrupt_response": true,
+                "create_response": false,
+            })
+        );
+
+        let TurnDetection::ServerVad(vad) = serde_json::from_value(json).unwrap() else {
+            panic!("expected server VAD");
+        };
+        assert_eq!(vad.threshold(), Some(0.6));
+        assert_eq!(vad.prefix_padding_ms(), Some(200));
+        assert_eq!(vad.silence_duration_ms(), Some(800));
+        assert_eq!(vad.interrupt_response(), Some(true));
+        assert_eq!(vad.create_response(), Some(false));
+    }
+
+    #[test]
+    fn test_unset_server_vad_parameters_are_omitted() {
+        let json = serde_json::to_value(TurnDetection::default()).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "server_vad" }));
+    }
+
+    #[test]
+    fn test_semantic_vad_round_trip() {
+        let turn_detection = TurnDetection::SemanticVad(
+            SemanticVadTurnDetection::default()
+                .with_eagerness(Eagerness::Low)
+                .with_create_response(false),
+        );
+
+        let json = serde_json::to_value(&turn_detection).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "semantic_vad",
+                "eagerness": "low",
+                "create_response": false,
+            })
+        );
+
+        let TurnDetection::SemanticVad(vad) = serde_json::from_value(json).unwrap() else {
+            panic!("expected semantic VAD");
+        };
+        assert_eq!(vad.eagerness(), Some(Eagerness::Low));
+        assert_eq!(vad.interrupt_response(), None);
+        assert_eq!(vad.create_response(), Some(false));
+    }
+}
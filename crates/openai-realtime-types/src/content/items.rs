@@ -25,9 +25,11 @@ pub enum ItemStatus {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
 pub struct _Item {
     /// The unique ID of the item, Optional for client events
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 
     /// The status of the item: "completed", "in_progress", "incomplete"
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<ItemStatus>,
 }
 
@@ -56,10 +58,82 @@ pub struct FunctionCallItem {
     arguments: Option<String>,
 }
 
+impl FunctionCallItem {
+    pub fn id(&self) -> Option<String> {
+        self.item.id.clone()
+    }
+
+    pub fn call_id(&self) -> Option<&str> {
+        self.call_id.as_deref()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn arguments(&self) -> Option<&str> {
+        self.arguments.as_deref()
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FunctionCallOutputItem {
     #[serde(flatten)]
     item: _Item,
+    /// The ID of the function call this is the output for.
+    call_id: Option<String>,
     /// The output of the function call(for "function_call_output" items).
     output: Option<String>,
+}
+
+impl FunctionCallOutputItem {
+    pub fn new(call_id: &str, output: String) -> Self {
+        Self {
+            item: _Item::default(),
+            call_id: Some(call_id.to_string()),
+            output: Some(output),
+        }
+    }
+
+    pub fn id(&self) -> Option<String> {
+        self.item.id.clone()
+    }
+
+    pub fn call_id(&self) -> Option<&str> {
+        self.call_id.as_deref()
+    }
+
+    pub fn output(&self) -> Option<&str> {
+        self.output.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_call_output_round_trip() {
+        let item = Item::FunctionCallOutput(FunctionCallOutputItem::new(
+            "call_123",
+            r#"{"temperature": 21}"#.to_string(),
+        ));
+
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "function_call_output",
+                "call_id": "call_123",
+                "output": "{\"temperature\": 21}",
+            })
+        );
+
+        let Item::FunctionCallOutput(parsed) = serde_json::from_value(json).unwrap() else {
+            panic!("expected a function_call_output item");
+        };
+        assert_eq!(parsed.call_id(), Some("call_123"));
+        assert_eq!(parsed.output(), Some(r#"{"temperature": 21}"#));
+        assert_eq!(parsed.id(), None);
+    }
 }
\ No newline at end of file
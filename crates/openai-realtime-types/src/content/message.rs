@@ -1,5 +1,5 @@
 use crate::audio::Base64EncodedAudioBytes;
-use crate::content::items::{ItemStatus, _Item};
+use crate::content::items::_Item;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MessageItem {
@@ -23,11 +23,7 @@ impl MessageItem {
     }
     
     pub fn status(&self) -> Option<&str> {
-        self.item.status.as_ref().map(|status| match status {
-            ItemStatus::Completed => "completed",
-            ItemStatus::InProgress => "in_progress",
-            ItemStatus::Incomplete => "incomplete",
-        })
+        self.item.status_as_str()
     }
     
     pub fn role(&self) -> MessageRole {
@@ -74,6 +70,18 @@ impl MessageItemBuilder {
         self.item.content.push(Content::input_text(text));
         self
     }
+
+    /// Adds model output text, e.g. to seed the conversation with a prior assistant turn.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.item.content.push(Content::text(text));
+        self
+    }
+
+    /// Adds the transcript of model output audio, for a prior assistant turn that was spoken.
+    pub fn with_audio_transcript(mut self, transcript: &str) -> Self {
+        self.item.content.push(Content::audio_transcript(transcript));
+        self
+    }
     
     // pub fn with_input_audio(mut self, audio: Base64EncodedAudioBytes) -> Self {
     //     self.item.content.push(Content::input_audio(audio));
@@ -112,6 +120,14 @@ impl Content {
     pub fn input_text(text: &str) -> Self {
         Content::InputText(InputTextContent::new(text))
     }
+
+    pub fn text(text: &str) -> Self {
+        Content::Text(TextContent::new(text))
+    }
+
+    pub fn audio_transcript(transcript: &str) -> Self {
+        Content::Audio(AudioContent::new(transcript))
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
@@ -138,6 +154,12 @@ pub struct InputAudioContent {
     audio: Option<Base64EncodedAudioBytes>,
 }
 
+impl Default for InputAudioContent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InputAudioContent {
     pub fn new() -> Self {
         Self {
@@ -182,4 +204,28 @@ impl AudioContent {
     pub fn transcript(&self) -> String {
         self.transcript.clone()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assistant_message_with_transcript() {
+        let item = MessageItem::builder()
+            .with_role(MessageRole::Assistant)
+            .with_text("Hi there.")
+            .with_audio_transcript("Hi there, what are we learning today?")
+            .build();
+
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(json["role"], "assistant");
+        assert_eq!(
+            json["content"],
+            serde_json::json!([
+                { "type": "text", "text": "Hi there." },
+                { "type": "audio", "transcript": "Hi there, what are we learning today?" },
+            ])
+        );
+    }
+}
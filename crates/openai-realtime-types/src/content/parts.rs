@@ -1,5 +1,3 @@
-use crate::audio::Base64EncodedAudioBytes;
-
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "type")]
 pub enum ContentPart {
@@ -4,6 +4,8 @@ mod server;
 use client::*;
 use server::*;
 
+pub use server::{ErrorDetails, ErrorKind, RateLimitInformation, RateLimitKind};
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "type")]
 pub enum ClientEvent {
@@ -34,6 +36,21 @@ pub enum ServerEvent {
     Close {
         reason: Option<String>,
     },
+    /// Emitted by the client as soon as the WebSocket handshake succeeds, before any server event.
+    #[serde(rename = "connected")]
+    Connected,
+    /// Emitted by the client when the connection dropped and a reconnect attempt is starting.
+    #[serde(rename = "reconnecting")]
+    Reconnecting {
+        attempt: u32,
+    },
+    /// Emitted by the client once the connection has been re-established.
+    ///
+    /// The new connection starts a fresh server-side conversation: only the latest session
+    /// config is sent again. Earlier conversation items are gone and in-flight responses will
+    /// never finish, so callers that need them must recreate the items themselves.
+    #[serde(rename = "reconnected")]
+    Reconnected,
     #[serde(rename = "error")]
     Error(ErrorEvent),
     #[serde(rename = "session.created")]
@@ -59,9 +76,9 @@ pub enum ServerEvent {
     #[serde(rename = "conversation.item.input_audio_transcription.failed")]
     ConversationItemInputAudioTranscriptionFailed(ConversationItemInputAudioTranscriptionFailedEvent),
     #[serde(rename = "conversation.item.truncated")]
-    ConversationItemTruncated(ConversationItemTruncateEvent),
+    ConversationItemTruncated(ConversationItemTruncatedEvent),
     #[serde(rename = "conversation.item.deleted")]
-    ConversationItemDeleted(ConversationItemDeleteEvent),
+    ConversationItemDeleted(ConversationItemDeletedEvent),
     #[serde(rename = "response.created")]
     ResponseCreated(ResponseCreatedEvent),
     #[serde(rename = "response.done")]
@@ -92,4 +109,122 @@ pub enum ServerEvent {
     ResponseFunctionCallArgumentsDone(ResponseFunctionCallArgumentsDoneEvent),
     #[serde(rename = "rate_limits.updated")]
     RateLimitsUpdated(RateLimitsUpdatedEvent),
-}
\ No newline at end of file
+}
+
+/// Which kind of `ServerEvent` an event is, without its payload, e.g. to filter a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServerEventKind {
+    Close,
+    Connected,
+    Reconnecting,
+    Reconnected,
+    Error,
+    SessionCreated,
+    SessionUpdated,
+    ConversationCreated,
+    InputAudioBufferCommitted,
+    InputAudioBufferCleared,
+    InputAudioBufferSpeechStarted,
+    InputAudioBufferSpeechStopped,
+    ConversationItemCreated,
+    ConversationItemInputAudioTranscriptionCompleted,
+    ConversationItemInputAudioTranscriptionDelta,
+    ConversationItemInputAudioTranscriptionFailed,
+    ConversationItemTruncated,
+    ConversationItemDeleted,
+    ResponseCreated,
+    ResponseDone,
+    ResponseOutputItemAdded,
+    ResponseOutputItemDone,
+    ResponseContentPartAdded,
+    ResponseContentPartDone,
+    ResponseTextDelta,
+    ResponseTextDone,
+    ResponseAudioTranscriptDelta,
+    ResponseAudioTranscriptDone,
+    ResponseAudioDelta,
+    ResponseAudioDone,
+    ResponseFunctionCallArgumentsDelta,
+    ResponseFunctionCallArgumentsDone,
+    RateLimitsUpdated,
+}
+
+impl ServerEvent {
+    pub fn kind(&self) -> ServerEventKind {
+        match self {
+            ServerEvent::Close { .. } => ServerEventKind::Close,
+            ServerEvent::Connected => ServerEventKind::Connected,
+            ServerEvent::Reconnecting { .. } => ServerEventKind::Reconnecting,
+            ServerEvent::Reconnected => ServerEventKind::Reconnected,
+            ServerEvent::Error(_) => ServerEventKind::Error,
+            ServerEvent::SessionCreated(_) => ServerEventKind::SessionCreated,
+            ServerEvent::SessionUpdated(_) => ServerEventKind::SessionUpdated,
+            ServerEvent::ConversationCreated(_) => ServerEventKind::ConversationCreated,
+            ServerEvent::InputAudioBufferCommitted(_) => ServerEventKind::InputAudioBufferCommitted,
+            ServerEvent::InputAudioBufferCleared(_) => ServerEventKind::InputAudioBufferCleared,
+            ServerEvent::InputAudioBufferSpeechStarted(_) => {
+                ServerEventKind::InputAudioBufferSpeechStarted
+            }
+            ServerEvent::InputAudioBufferSpeechStopped(_) => {
+                ServerEventKind::InputAudioBufferSpeechStopped
+            }
+            ServerEvent::ConversationItemCreated(_) => ServerEventKind::ConversationItemCreated,
+            ServerEvent::ConversationItemInputAudioTranscriptionCompleted(_) => {
+                ServerEventKind::ConversationItemInputAudioTranscriptionCompleted
+            }
+            ServerEvent::ConversationItemInputAudioTranscriptionDelta(_) => {
+                ServerEventKind::ConversationItemInputAudioTranscriptionDelta
+            }
+            ServerEvent::ConversationItemInputAudioTranscriptionFailed(_) => {
+                ServerEventKind::ConversationItemInputAudioTranscriptionFailed
+            }
+            ServerEvent::ConversationItemTruncated(_) => ServerEventKind::ConversationItemTruncated,
+            ServerEvent::ConversationItemDeleted(_) => ServerEventKind::ConversationItemDeleted,
+            ServerEvent::ResponseCreated(_) => ServerEventKind::ResponseCreated,
+            ServerEvent::ResponseDone(_) => ServerEventKind::ResponseDone,
+            ServerEvent::ResponseOutputItemAdded(_) => ServerEventKind::ResponseOutputItemAdded,
+            ServerEvent::ResponseOutputItemDone(_) => ServerEventKind::ResponseOutputItemDone,
+            ServerEvent::ResponseContentPartAdded(_) => ServerEventKind::ResponseContentPartAdded,
+            ServerEvent::ResponseContentPartDone(_) => ServerEventKind::ResponseContentPartDone,
+            ServerEvent::ResponseTextDelta(_) => ServerEventKind::ResponseTextDelta,
+            ServerEvent::ResponseTextDone(_) => ServerEventKind::ResponseTextDone,
+            ServerEvent::ResponseAudioTranscriptDelta(_) => {
+                ServerEventKind::ResponseAudioTranscriptDelta
+            }
+            ServerEvent::ResponseAudioTranscriptDone(_) => {
+                ServerEventKind::ResponseAudioTranscriptDone
+            }
+            ServerEvent::ResponseAudioDelta(_) => ServerEventKind::ResponseAudioDelta,
+            ServerEvent::ResponseAudioDone(_) => ServerEventKind::ResponseAudioDone,
+            ServerEvent::ResponseFunctionCallArgumentsDelta(_) => {
+                ServerEventKind::ResponseFunctionCallArgumentsDelta
+            }
+            ServerEvent::ResponseFunctionCallArgumentsDone(_) => {
+                ServerEventKind::ResponseFunctionCallArgumentsDone
+            }
+            ServerEvent::RateLimitsUpdated(_) => ServerEventKind::RateLimitsUpdated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_matches_event() {
+        let event: ServerEvent = serde_json::from_value(serde_json::json!({
+            "type": "input_audio_buffer.speech_started",
+            "event_id": "event_1",
+            "audio_start_ms": 100,
+            "item_id": "item_1"
+        }))
+        .unwrap();
+        assert_eq!(event.kind(), ServerEventKind::InputAudioBufferSpeechStarted);
+        assert_eq!(ServerEvent::Connected.kind(), ServerEventKind::Connected);
+        assert_eq!(
+            ServerEvent::Close { reason: None }.kind(),
+            ServerEventKind::Close
+        );
+    }
+}
@@ -5,6 +5,7 @@ use crate::session::Session;
 /// `session.update` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SessionUpdateEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 
     /// The session configuration to update
@@ -31,6 +32,7 @@ impl SessionUpdateEvent {
 /// `input_audio_buffer.append` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InputAudioBufferAppendEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 
     /// The audio data to append to the buffer
@@ -58,9 +60,16 @@ impl InputAudioBufferAppendEvent {
 /// `input_audio_buffer.commit` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InputAudioBufferCommitEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 }
 
+impl Default for InputAudioBufferCommitEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InputAudioBufferCommitEvent {
     pub fn new() -> Self {
         Self { event_id: None }
@@ -74,9 +83,16 @@ impl InputAudioBufferCommitEvent {
 /// `input_audio_buffer.clear` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InputAudioBufferClearEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 }
 
+impl Default for InputAudioBufferClearEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InputAudioBufferClearEvent {
     pub fn new() -> Self {
         Self { event_id: None }
@@ -90,6 +106,7 @@ impl InputAudioBufferClearEvent {
 /// `conversation.item.create` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ConversationItemCreateEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 
     /// The ID of the preceding item after which the new item will be inserted
@@ -119,6 +136,7 @@ impl ConversationItemCreateEvent {
 /// `conversation.item.truncate` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ConversationItemTruncateEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 
     /// The ID of the assistant message item to truncate.
@@ -147,6 +165,7 @@ impl ConversationItemTruncateEvent {
 /// `conversation.item.delete` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ConversationItemDeleteEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 
     /// The ID of the item to delete
@@ -169,6 +188,7 @@ impl ConversationItemDeleteEvent {
 /// `response.create` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ResponseCreateEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 
     /// Configuration for the response
@@ -201,9 +221,16 @@ impl ResponseCreateEvent {
 /// `response.cancel` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ResponseCancelEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     event_id: Option<String>,
 }
 
+impl Default for ResponseCancelEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ResponseCancelEvent {
     pub fn new() -> Self {
         Self { event_id: None }
@@ -213,3 +240,79 @@ impl ResponseCancelEvent {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClientEvent;
+
+    /// Every client event type, built once without and once with an event ID.
+    fn all_events(event_id: Option<&str>) -> Vec<ClientEvent> {
+        let item = || {
+            Item::Message(
+                crate::MessageItem::builder()
+                    .with_input_text("hello")
+                    .build(),
+            )
+        };
+        macro_rules! id {
+            ($event:expr) => {
+                match event_id {
+                    Some(id) => $event.with_event_id(id),
+                    None => $event,
+                }
+            };
+        }
+        vec![
+            ClientEvent::SessionUpdate(id!(SessionUpdateEvent::new(Session::new().build()))),
+            ClientEvent::InputAudioBufferAppend(id!(InputAudioBufferAppendEvent::new(
+                "AAAA".into()
+            ))),
+            ClientEvent::InputAudioBufferCommit(id!(InputAudioBufferCommitEvent::new())),
+            ClientEvent::InputAudioBufferClear(id!(InputAudioBufferClearEvent::new())),
+            ClientEvent::ConversationItemCreate(id!(ConversationItemCreateEvent::new(item()))),
+            ClientEvent::ConversationItemTruncate(id!(ConversationItemTruncateEvent::new(
+                "item_1", 0, 100
+            ))),
+            ClientEvent::ConversationItemDelete(id!(ConversationItemDeleteEvent::new("item_1"))),
+            ClientEvent::ResponseCreate(id!(ResponseCreateEvent::new())),
+            ClientEvent::ResponseCancel(id!(ResponseCancelEvent::new())),
+        ]
+    }
+
+    #[test]
+    fn test_event_id_omitted_by_default() {
+        for event in all_events(None) {
+            let json = serde_json::to_value(&event).unwrap();
+            assert!(json.get("event_id").is_none(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_event_id_serialized_when_set() {
+        for event in all_events(Some("event_42")) {
+            let json = serde_json::to_value(&event).unwrap();
+            assert_eq!(json["event_id"], "event_42", "{json}");
+
+            // Serialization is deterministic.
+            let text = serde_json::to_string(&event).unwrap();
+            assert_eq!(text, serde_json::to_string(&event).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_conversation_item_delete_serialization() {
+        let event = ClientEvent::ConversationItemDelete(
+            ConversationItemDeleteEvent::new("item_123").with_event_id("event_1"),
+        );
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "conversation.item.delete",
+                "event_id": "event_1",
+                "item_id": "item_123",
+            })
+        );
+    }
+}
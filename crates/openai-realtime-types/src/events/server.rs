@@ -2,8 +2,9 @@ mod resources;
 mod error;
 
 use resources::*;
+pub use error::{ErrorDetails, ErrorKind};
+pub use resources::{RateLimitInformation, RateLimitKind};
 use crate::ContentPart;
-use crate::events::server::error::ErrorDetails;
 
 /// `error` event
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -905,4 +906,42 @@ impl RateLimitsUpdatedEvent {
     pub fn rate_limits(&self) -> &[RateLimitInformation] {
         &self.rate_limits
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ServerEvent;
+
+    #[test]
+    fn test_deserialize_item_truncated_and_deleted() {
+        let json = r#"{
+            "event_id": "event_2526",
+            "type": "conversation.item.truncated",
+            "item_id": "msg_004",
+            "content_index": 0,
+            "audio_end_ms": 1500
+        }"#;
+        match serde_json::from_str::<ServerEvent>(json).unwrap() {
+            ServerEvent::ConversationItemTruncated(truncated) => {
+                assert_eq!(truncated.event_id(), "event_2526");
+                assert_eq!(truncated.item_id(), "msg_004");
+                assert_eq!(truncated.content_index(), 0);
+                assert_eq!(truncated.audio_end_ms(), 1500);
+            }
+            other => panic!("Expected conversation.item.truncated, got {other:?}"),
+        }
+
+        let json = r#"{
+            "event_id": "event_2728",
+            "type": "conversation.item.deleted",
+            "item_id": "msg_005"
+        }"#;
+        match serde_json::from_str::<ServerEvent>(json).unwrap() {
+            ServerEvent::ConversationItemDeleted(deleted) => {
+                assert_eq!(deleted.event_id(), "event_2728");
+                assert_eq!(deleted.item_id(), "msg_005");
+            }
+            other => panic!("Expected conversation.item.deleted, got {other:?}"),
+        }
+    }
+}
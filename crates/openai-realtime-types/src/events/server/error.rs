@@ -28,6 +28,43 @@ impl ErrorDetails {
     pub fn event_id(&self) -> Option<&str> {
         self.event_id.as_deref()
     }
+
+    /// Classifies the error from its `code`, falling back to its `type`.
+    pub fn kind(&self) -> ErrorKind {
+        self.code
+            .as_deref()
+            .map(ErrorKind::from_code)
+            .filter(|kind| *kind != ErrorKind::Unknown)
+            .unwrap_or_else(|| ErrorKind::from_code(&self.error_type))
+    }
+}
+
+/// A coarse classification of server errors, for deciding whether to retry, back off, or abort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Too many requests or tokens; retry after backing off.
+    RateLimited,
+    /// The session expired or is otherwise unusable; reconnect with a new session.
+    InvalidSession,
+    /// The request was malformed; retrying the same request will fail again.
+    InvalidRequest,
+    /// A transient failure on the server's side.
+    ServerInternal,
+    Unknown,
+}
+
+impl ErrorKind {
+    fn from_code(code: &str) -> Self {
+        match code {
+            "rate_limit_exceeded" | "rate_limit_error" => ErrorKind::RateLimited,
+            "session_expired" | "session_not_found" | "invalid_session" => {
+                ErrorKind::InvalidSession
+            }
+            "invalid_request_error" => ErrorKind::InvalidRequest,
+            "server_error" | "internal_error" => ErrorKind::ServerInternal,
+            _ => ErrorKind::Unknown,
+        }
+    }
 }
 
 impl ErrorDetails {
@@ -57,3 +94,36 @@ impl ErrorDetails {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_prefers_code_over_type() {
+        let error = ErrorDetails::new(
+            "invalid_request_error",
+            "Your session hit the maximum duration",
+        )
+        .with_code("session_expired");
+        assert_eq!(error.kind(), ErrorKind::InvalidSession);
+
+        let error = ErrorDetails::new("invalid_request_error", "Rate limit reached")
+            .with_code("rate_limit_exceeded");
+        assert_eq!(error.kind(), ErrorKind::RateLimited);
+    }
+
+    #[test]
+    fn test_kind_falls_back_to_type() {
+        let error = ErrorDetails::new("server_error", "Something went wrong");
+        assert_eq!(error.kind(), ErrorKind::ServerInternal);
+
+        let error = ErrorDetails::new("invalid_request_error", "Unknown parameter")
+            .with_code("unknown_parameter");
+        assert_eq!(error.kind(), ErrorKind::InvalidRequest);
+    }
+
+    #[test]
+    fn test_kind_unknown() {
+        assert_eq!(ErrorDetails::new("mystery", "?").kind(), ErrorKind::Unknown);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::content::items::Item;
 use crate::session::Session;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Duration;
+use std::{convert::Infallible, str::FromStr};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct _Resource {
@@ -142,16 +145,66 @@ impl TokenUsageDetails {
         self.text_tokens
     }
     pub fn cached_tokens(&self) -> Option<i32> {
-        self.cached_tokens.clone()
+        self.cached_tokens
     }
 }
 
 
+/// The kind of resource a rate limit applies to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateLimitKind {
+    Requests,
+    Tokens,
+    Custom(String),
+}
+
+impl RateLimitKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RateLimitKind::Requests => "requests",
+            RateLimitKind::Tokens => "tokens",
+            RateLimitKind::Custom(s) => s,
+        }
+    }
+}
+
+impl Serialize for RateLimitKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl FromStr for RateLimitKind {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "requests" => RateLimitKind::Requests,
+            "tokens" => RateLimitKind::Tokens,
+            _ => RateLimitKind::Custom(s.to_string()),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for RateLimitKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Ok(RateLimitKind::from_str(&s).unwrap())
+    }
+}
+
 /// Rate limit information
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct RateLimitInformation {
     /// The name of the rate limit ("requests", "tokens", "input_tokens", "output_tokens").
-    name: String,
+    #[serde(rename = "name")]
+    kind: RateLimitKind,
     /// The maximum allowed value for the rate limit.
     limit: i32,
     /// The remaining value before the limit is reached.
@@ -163,7 +216,11 @@ pub struct RateLimitInformation {
 impl RateLimitInformation {
     /// The name of the rate limit ("requests", "tokens", "input_tokens", "output_tokens").
     pub fn name(&self) -> &str {
-        &self.name
+        self.kind.as_str()
+    }
+    /// The kind of resource this rate limit applies to.
+    pub fn kind(&self) -> &RateLimitKind {
+        &self.kind
     }
     /// The maximum allowed value for the rate limit.
     pub fn limit(&self) -> i32 {
@@ -177,4 +234,62 @@ impl RateLimitInformation {
     pub fn reset_seconds(&self) -> f32 {
         self.reset_seconds
     }
-}
\ No newline at end of file
+    /// Time until the rate limit resets.
+    /// A negative or NaN `reset_seconds` reads as zero, and one too large for a `Duration` as
+    /// `Duration::MAX`.
+    pub fn reset(&self) -> Duration {
+        Duration::try_from_secs_f32(self.reset_seconds).unwrap_or(if self.reset_seconds > 0.0 {
+            Duration::MAX
+        } else {
+            Duration::ZERO
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_rate_limit_kind() {
+        let json = r#"{"name":"requests","limit":1000,"remaining":999,"reset_seconds":60}"#;
+        let info: RateLimitInformation = serde_json::from_str(json).unwrap();
+        assert_eq!(info.kind(), &RateLimitKind::Requests);
+        assert_eq!(info.name(), "requests");
+        assert_eq!(info.remaining(), 999);
+        assert_eq!(info.reset(), Duration::from_secs(60));
+
+        let json = r#"{"name":"tokens","limit":50000,"remaining":49950,"reset_seconds":0.06}"#;
+        let info: RateLimitInformation = serde_json::from_str(json).unwrap();
+        assert_eq!(info.kind(), &RateLimitKind::Tokens);
+        assert_eq!(info.reset(), Duration::from_secs_f32(0.06));
+
+        let json = r#"{"name":"input_tokens","limit":1,"remaining":1,"reset_seconds":1}"#;
+        let info: RateLimitInformation = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            info.kind(),
+            &RateLimitKind::Custom("input_tokens".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reset_tolerates_out_of_range_seconds() {
+        let info = |reset_seconds| RateLimitInformation {
+            kind: RateLimitKind::Requests,
+            limit: 1,
+            remaining: 0,
+            reset_seconds,
+        };
+        assert_eq!(info(-1.0).reset(), Duration::ZERO);
+        assert_eq!(info(f32::NAN).reset(), Duration::ZERO);
+        assert_eq!(info(f32::INFINITY).reset(), Duration::MAX);
+        assert_eq!(info(f32::MAX).reset(), Duration::MAX);
+    }
+
+    #[test]
+    fn test_serialize_rate_limit_kind() {
+        let json = r#"{"name":"tokens","limit":10,"remaining":5,"reset_seconds":1.5}"#;
+        let info: RateLimitInformation = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&info).unwrap(), json);
+    }
+}
@@ -7,7 +7,7 @@ mod content;
 
 //re-export types for easier access
 pub use session::Session;
-pub use content::items::Item;
+pub use content::items::{FunctionCallItem, FunctionCallOutputItem, Item};
 pub use content::message::*;
 pub use content::parts::ContentPart;
-pub use events::{ClientEvent, ServerEvent};
+pub use events::{ClientEvent, ServerEvent, ServerEventKind};
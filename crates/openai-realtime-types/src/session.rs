@@ -1,12 +1,16 @@
-use crate::audio::{AudioFormat, InputAudioTranscription, TranscriptionModel, TurnDetection, Voice};
+use crate::audio::{
+    AudioFormat, InputAudioTranscription, SemanticVadTurnDetection, TranscriptionModel,
+    TurnDetection, Voice,
+};
 use crate::tools::{Tool, ToolChoice};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     /// The set of modalities the model can respond with. To disable audio, set this to ["text"].
     /// To enable audio, set this to ["text", "audio"].
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    modalities: Vec<String>,
+    modalities: Vec<Modality>,
 
     /// The default system instructions prepended to model calls.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,9 +33,14 @@ pub struct Session {
     #[serde(skip_serializing_if = "Option::is_none")]
     input_audio_transcription: Option<InputAudioTranscription>,
 
-    /// Configuration for turn detection. Can be set to null to turn off
-    #[serde(skip_serializing_if = "Option::is_none")]
-    turn_detection: Option<TurnDetection>,
+    /// Configuration for turn detection. Can be set to null to turn off.
+    /// `None` leaves the server's setting alone; `Some(None)` sends `null`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_present"
+    )]
+    turn_detection: Option<Option<TurnDetection>>,
 
     /// Tools(Functions) available to the model.
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -54,30 +63,138 @@ pub struct Session {
     expires_at: Option<i32>,
 }
 
+/// The lowest temperature the Realtime API accepts.
+pub const MIN_TEMPERATURE: f32 = 0.6;
+/// The highest temperature the Realtime API accepts.
+pub const MAX_TEMPERATURE: f32 = 1.2;
+
+/// A kind of output the model can respond with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modality {
+    Text,
+    Audio,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SessionError {
+    #[error("temperature {0} is outside the allowed range [{MIN_TEMPERATURE}, {MAX_TEMPERATURE}]")]
+    TemperatureOutOfRange(f32),
+}
+
 impl Session {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> SessionConfigurator {
         SessionConfigurator::new()
     }
+
+    /// Starts a configurator from this session, e.g. to change one field and send it again.
+    pub fn into_configurator(self) -> SessionConfigurator {
+        SessionConfigurator { session: self }
+    }
+
+    pub fn instructions(&self) -> Option<&str> {
+        self.instructions.as_deref()
+    }
+
+    pub fn voice(&self) -> Option<&Voice> {
+        self.voice.as_ref()
+    }
+
+    pub fn input_audio_format(&self) -> Option<&AudioFormat> {
+        self.input_audio_format.as_ref()
+    }
+
+    pub fn output_audio_format(&self) -> Option<&AudioFormat> {
+        self.output_audio_format.as_ref()
+    }
+
+    pub fn turn_detection(&self) -> Option<&TurnDetection> {
+        self.turn_detection.as_ref().and_then(Option::as_ref)
+    }
+
+    /// Whether the session explicitly turns off turn detection, leaving turns to the client.
+    pub fn turn_detection_disabled(&self) -> bool {
+        matches!(self.turn_detection, Some(None))
+    }
+}
+
+/// Deserializes a field that is present, even as `null`, to `Some`. Paired with
+/// `#[serde(default)]`, a missing field stays `None`, so the two can be told apart.
+fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-#[serde(untagged)]
+/// The maximum number of output tokens for a response.
+/// `Infinity` always serializes as `"inf"`, whatever string it holds.
+#[derive(Debug, Clone, PartialEq)]
 pub enum MaxOutputTokens {
     Number(i32),
     Infinity(String),
 }
 
+impl MaxOutputTokens {
+    pub fn inf() -> Self {
+        MaxOutputTokens::Infinity("inf".to_string())
+    }
+}
+
+impl Serialize for MaxOutputTokens {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            MaxOutputTokens::Number(n) => serializer.serialize_i32(*n),
+            MaxOutputTokens::Infinity(_) => serializer.serialize_str("inf"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MaxOutputTokens {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(i32),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(n) => Ok(MaxOutputTokens::Number(n)),
+            Raw::Text(s) if s == "inf" => Ok(MaxOutputTokens::inf()),
+            Raw::Text(s) => Err(serde::de::Error::custom(format!(
+                "invalid max_output_tokens: expected a number or \"inf\", got \"{}\"",
+                s
+            ))),
+        }
+    }
+}
+
 
 pub struct SessionConfigurator {
     session: Session,
 }
 
+impl Default for SessionConfigurator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SessionConfigurator {
     pub fn new() -> Self {
         Self {
             session: Session {
-                modalities: vec!["text".to_string(), "audio".to_string()],
+                modalities: vec![Modality::Text, Modality::Audio],
                 instructions: None,
                 voice: None,
                 input_audio_format: None,
@@ -93,19 +210,17 @@ impl SessionConfigurator {
         }
     }
 
-    pub fn with_modalities(mut self, modalities: Vec<String>) -> Self {
+    pub fn with_modalities(mut self, modalities: Vec<Modality>) -> Self {
         self.session.modalities = modalities;
         self
     }
 
-    pub fn with_modalities_disable_audio(mut self) -> Self {
-        self.session.modalities = vec!["text".to_string()];
-        self
+    pub fn with_modalities_disable_audio(self) -> Self {
+        self.with_modalities(vec![Modality::Text])
     }
 
-    pub fn with_modalities_enable_audio(mut self) -> Self {
-        self.session.modalities = vec!["text".to_string(), "audio".to_string()];
-        self
+    pub fn with_modalities_enable_audio(self) -> Self {
+        self.with_modalities(vec![Modality::Text, Modality::Audio])
     }
 
     pub fn with_instructions(mut self, instructions: &str) -> Self {
@@ -145,12 +260,19 @@ impl SessionConfigurator {
     }
 
     pub fn with_turn_detection_enable(mut self, turn_detection: TurnDetection) -> Self {
-        self.session.turn_detection = Some(turn_detection);
+        self.session.turn_detection = Some(Some(turn_detection));
         self
     }
 
+    /// Enables semantic VAD, which waits for the user to finish their thought.
+    pub fn with_semantic_vad(mut self, semantic_vad: SemanticVadTurnDetection) -> Self {
+        self.session.turn_detection = Some(Some(TurnDetection::SemanticVad(semantic_vad)));
+        self
+    }
+
+    /// Turns off turn detection, so the client commits the input buffer and creates responses.
     pub fn with_turn_detection_disable(mut self) -> Self {
-        self.session.turn_detection = None;
+        self.session.turn_detection = Some(None);
         self
     }
 
@@ -164,17 +286,196 @@ impl SessionConfigurator {
         self
     }
 
+    /// Sets the sampling temperature, clamped to [`MIN_TEMPERATURE`, `MAX_TEMPERATURE`].
+    /// The server rejects sessions outside this range; use `try_with_temperature` to
+    /// surface out-of-range values instead of clamping them.
     pub fn with_temperature(mut self, temperature: f32) -> Self {
-        self.session.temperature = temperature;
+        self.session.temperature = if temperature.is_nan() {
+            MIN_TEMPERATURE
+        } else {
+            temperature.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE)
+        };
         self
     }
 
+    /// Sets the sampling temperature, or returns an error if it's outside
+    /// [`MIN_TEMPERATURE`, `MAX_TEMPERATURE`].
+    pub fn try_with_temperature(mut self, temperature: f32) -> Result<Self, SessionError> {
+        if !(MIN_TEMPERATURE..=MAX_TEMPERATURE).contains(&temperature) {
+            return Err(SessionError::TemperatureOutOfRange(temperature));
+        }
+        self.session.temperature = temperature;
+        Ok(self)
+    }
+
     pub fn with_max_output_tokens(mut self, max_output_tokens: MaxOutputTokens) -> Self {
         self.session.max_output_tokens = Some(max_output_tokens);
         self
     }
 
+    /// Lets responses use as many output tokens as the model allows.
+    pub fn with_max_output_tokens_inf(self) -> Self {
+        self.with_max_output_tokens(MaxOutputTokens::inf())
+    }
+
+    pub fn with_max_output_tokens_count(self, count: i32) -> Self {
+        self.with_max_output_tokens(MaxOutputTokens::Number(count))
+    }
+
     pub fn build(self) -> Session {
         self.session
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::Eagerness;
+
+    #[test]
+    fn test_max_output_tokens_round_trip() {
+        let session = Session::new().with_max_output_tokens_inf().build();
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["max_output_tokens"], "inf");
+        let parsed: MaxOutputTokens =
+            serde_json::from_value(json["max_output_tokens"].clone()).unwrap();
+        assert_eq!(parsed, MaxOutputTokens::inf());
+
+        let session = Session::new().with_max_output_tokens_count(4096).build();
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["max_output_tokens"], 4096);
+        let parsed: MaxOutputTokens =
+            serde_json::from_value(json["max_output_tokens"].clone()).unwrap();
+        assert_eq!(parsed, MaxOutputTokens::Number(4096));
+    }
+
+    #[test]
+    fn test_with_temperature_clamps() {
+        let temperature = |session: Session| {
+            serde_json::to_value(session).unwrap()["temperature"]
+                .as_f64()
+                .unwrap() as f32
+        };
+        assert_eq!(temperature(Session::new().build()), 0.8);
+        assert_eq!(temperature(Session::new().with_temperature(0.0).build()), 0.6);
+        assert_eq!(temperature(Session::new().with_temperature(2.0).build()), 1.2);
+        assert_eq!(temperature(Session::new().with_temperature(1.0).build()), 1.0);
+    }
+
+    #[test]
+    fn test_try_with_temperature_validates_range() {
+        assert!(Session::new().try_with_temperature(0.6).is_ok());
+        assert!(Session::new().try_with_temperature(1.2).is_ok());
+        assert!(matches!(
+            Session::new().try_with_temperature(0.0),
+            Err(SessionError::TemperatureOutOfRange(t)) if t == 0.0
+        ));
+        assert!(Session::new().try_with_temperature(1.5).is_err());
+        assert!(Session::new().try_with_temperature(f32::NAN).is_err());
+    }
+
+    #[test]
+    fn test_max_output_tokens_infinity_ignores_contents() {
+        let json =
+            serde_json::to_value(MaxOutputTokens::Infinity("unlimited".to_string())).unwrap();
+        assert_eq!(json, "inf");
+        assert!(serde_json::from_str::<MaxOutputTokens>(r#""unlimited""#).is_err());
+    }
+
+    #[test]
+    fn test_session_updated_event_keeps_semantic_vad() {
+        use crate::events::ServerEvent;
+
+        let json = r#"{
+            "type": "session.updated",
+            "event_id": "event_123",
+            "session": {
+                "id": "sess_123",
+                "object": "realtime.session",
+                "modalities": ["text", "audio"],
+                "tools": [],
+                "temperature": 0.8,
+                "turn_detection": {
+                    "type": "semantic_vad",
+                    "eagerness": "high",
+                    "create_response": true,
+                    "interrupt_response": true
+                }
+            }
+        }"#;
+        let ServerEvent::SessionUpdated(event) = serde_json::from_str(json).unwrap() else {
+            panic!("expected a session.updated event");
+        };
+        let session = event.session().session();
+        let Some(TurnDetection::SemanticVad(vad)) = session.turn_detection() else {
+            panic!("expected semantic VAD, got {:?}", session.turn_detection());
+        };
+        assert_eq!(vad.eagerness(), Some(Eagerness::High));
+        assert_eq!(vad.create_response(), Some(true));
+        assert_eq!(vad.interrupt_response(), Some(true));
+    }
+
+    #[test]
+    fn test_with_semantic_vad() {
+        let semantic_vad = SemanticVadTurnDetection::default().with_eagerness(Eagerness::Auto);
+        let session = Session::new().with_semantic_vad(semantic_vad).build();
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(
+            json["turn_detection"],
+            serde_json::json!({ "type": "semantic_vad", "eagerness": "auto" })
+        );
+    }
+
+    #[test]
+    fn test_mulaw_session() {
+        let session = Session::new()
+            .with_input_audio_format(AudioFormat::Mulaw)
+            .with_output_audio_format(AudioFormat::Mulaw)
+            .build();
+        let mut json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["input_audio_format"], "g711_ulaw");
+        assert_eq!(json["output_audio_format"], "g711_ulaw");
+
+        // The server always reports `tools`, which isn't sent when empty.
+        json["tools"] = serde_json::json!([]);
+        let parsed: Session = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.input_audio_format(), Some(&AudioFormat::Mulaw));
+        assert_eq!(parsed.output_audio_format(), Some(&AudioFormat::Mulaw));
+    }
+
+    #[test]
+    fn test_modalities_wire_format() {
+        let session = Session::new().with_modalities_enable_audio().build();
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["modalities"], serde_json::json!(["text", "audio"]));
+
+        let session = Session::new().with_modalities_disable_audio().build();
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["modalities"], serde_json::json!(["text"]));
+
+        assert_eq!(
+            serde_json::from_str::<Vec<Modality>>(r#"["audio", "text"]"#).unwrap(),
+            vec![Modality::Audio, Modality::Text]
+        );
+        assert!(serde_json::from_str::<Modality>(r#""Audio""#).is_err());
+    }
+
+    #[test]
+    fn test_turn_detection_disable_sends_null() {
+        let session = Session::new().with_turn_detection_disable().build();
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["turn_detection"], serde_json::Value::Null);
+        assert!(json.as_object().unwrap().contains_key("turn_detection"));
+
+        let unset = serde_json::to_value(Session::new().build()).unwrap();
+        assert!(!unset.as_object().unwrap().contains_key("turn_detection"));
+
+        let mut json = serde_json::json!({ "modalities": [], "tools": [], "temperature": 0.8 });
+        let parsed: Session = serde_json::from_value(json.clone()).unwrap();
+        assert!(!parsed.turn_detection_disabled());
+        json["turn_detection"] = serde_json::Value::Null;
+        let parsed: Session = serde_json::from_value(json).unwrap();
+        assert!(parsed.turn_detection_disabled());
+        assert!(parsed.turn_detection().is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use schemars::JsonSchema;
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
 pub enum ToolChoice {
@@ -17,6 +19,24 @@ pub enum Tool {
     Function(FunctionTool),
 }
 
+impl Tool {
+    /// Creates a function tool whose parameters are the JSON Schema generated for `T`.
+    pub fn from_schema<T: JsonSchema>(name: &str, description: &str) -> Self {
+        let mut parameters = serde_json::to_value(schemars::schema_for!(T))
+            .expect("JSON Schema should always serialize");
+        // The API only wants the schema body, not the document metadata.
+        if let Some(object) = parameters.as_object_mut() {
+            object.remove("$schema");
+            object.remove("title");
+        }
+        Tool::Function(FunctionTool::new(
+            name.to_string(),
+            description.to_string(),
+            parameters,
+        ))
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FunctionTool {
     /// The name of the function
@@ -49,4 +69,39 @@ impl FunctionTool {
     pub fn parameters(&self) -> &serde_json::Value {
         &self.parameters
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct GetWeather {
+        /// The city to look up
+        city: String,
+        days: Option<u32>,
+    }
+
+    #[test]
+    fn test_tool_from_schema() {
+        let tool = Tool::from_schema::<GetWeather>("get_weather", "Get the weather forecast");
+        let json = serde_json::to_value(&tool).unwrap();
+
+        assert_eq!(json["type"], "function");
+        assert_eq!(json["name"], "get_weather");
+        assert_eq!(json["description"], "Get the weather forecast");
+
+        let parameters = &json["parameters"];
+        assert_eq!(parameters["type"], "object");
+        assert_eq!(parameters["properties"]["city"]["type"], "string");
+        assert_eq!(
+            parameters["properties"]["city"]["description"],
+            "The city to look up"
+        );
+        assert!(parameters["properties"]["days"].is_object());
+        assert_eq!(parameters["required"], serde_json::json!(["city"]));
+        assert!(parameters.get("$schema").is_none());
+        assert!(parameters.get("title").is_none());
+    }
+}
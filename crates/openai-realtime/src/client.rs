@@ -1,40 +1,66 @@
-use crate::client::stats::Stats;
 use crate::types;
-use futures_util::{SinkExt, StreamExt};
-use openai_realtime_types::audio::Base64EncodedAudioBytes;
+use async_trait::async_trait;
+use openai_realtime_types::events::RateLimitInformation;
 use openai_realtime_types::session::Session;
-use std::sync::{Arc, Mutex};
-use tokio_tungstenite::tungstenite::Message;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 // Add this use statement
 use anyhow::Result;
 
+mod api;
 mod config;
+mod connection;
 mod consts;
+mod export;
+mod filter;
+mod reconnect;
+mod recorder;
+mod replay_client;
+
+pub use api::RealtimeClient;
+pub use config::{Config, ConfigBuilder};
+pub use export::ExportOptions;
+pub use filter::FilteredServerRx;
+pub use recorder::replay;
+pub use replay_client::ReplayClient;
+pub use reconnect::ReconnectPolicy;
+pub use state::ConnectionState;
+pub use stats::Stats;
+mod state;
 mod stats;
 mod utils;
 
 pub type ClientTx = tokio::sync::mpsc::Sender<types::ClientEvent>;
-type ServerTx = tokio::sync::broadcast::Sender<types::ServerEvent>;
+pub(crate) type ServerTx = tokio::sync::broadcast::Sender<types::ServerEvent>;
 pub type ServerRx = tokio::sync::broadcast::Receiver<types::ServerEvent>;
 
 // Contains the capacity for channels, client/server transmitters, configuration,
-// and stats guarded by a Mutex.
+// stats guarded by a Mutex, and the connection state.
 pub struct Client {
     capacity: usize,
-    config: config::Config,
+    config: Arc<config::Config>,
     c_tx: Option<ClientTx>,
     s_tx: Option<ServerTx>,
+    // The receiver created with the broadcast channel, handed to the first `server_events`
+    // caller so it sees `Connected` even though it subscribes after `connect` returns.
+    first_rx: Option<ServerRx>,
     stats: Arc<Mutex<Stats>>,
+    session: Arc<Mutex<Option<Session>>>,
+    rate_limits: Arc<Mutex<Option<Vec<RateLimitInformation>>>>,
+    state_tx: tokio::sync::watch::Sender<ConnectionState>,
 }
 
 impl Client {
     fn new(capacity: usize, config: config::Config) -> Self {
         Self {
             capacity,
-            config,
+            config: Arc::new(config),
             c_tx: None,
             s_tx: None,
+            first_rx: None,
             stats: Arc::new(Mutex::new(Stats::new())),
+            session: Arc::new(Mutex::new(None)),
+            rate_limits: Arc::new(Mutex::new(None)),
+            state_tx: tokio::sync::watch::Sender::new(ConnectionState::Disconnected),
         }
     }
 
@@ -47,231 +73,829 @@ impl Client {
 
         // Create a request using the build_request function.
         let request = utils::build_request(&self.config)?;
+        // Open the recording before connecting, so a bad path fails fast.
+        let recorder = match self.config.recording_path() {
+            Some(path) => Some(recorder::Recorder::open(path)?),
+            None => None,
+        };
 
         // Get a WebSocket stream object.
-        let (ws_stream, _) = tokio_tungstenite::connect_async(request).await?;
-
-        // Split the WebSocket into read and write halves.
-        let (mut write, mut read) = ws_stream.split();
+        self.state_tx.send_replace(ConnectionState::Connecting);
+        let ws_stream = match tokio_tungstenite::connect_async(request).await {
+            Ok((ws_stream, _)) => ws_stream,
+            Err(e) => {
+                self.state_tx.send_replace(ConnectionState::Disconnected);
+                return Err(e.into());
+            }
+        };
+        self.state_tx.send_replace(ConnectionState::Connected);
 
         // Create the channels to hold events to send and receive.
-        let (c_tx, mut c_rx) = tokio::sync::mpsc::channel(self.capacity);
+        let (c_tx, c_rx) = tokio::sync::mpsc::channel(self.capacity);
         // Create the server transmitter that will broadcast out to client receivers.
-        let (s_tx, _) = tokio::sync::broadcast::channel(self.capacity);
+        // It lives as long as the client, so subscribers keep receiving events across reconnects.
+        let (s_tx, s_rx) = tokio::sync::broadcast::channel(self.capacity);
 
         // Store the server and client transmitters in the struct.
-        self.c_tx = Some(c_tx.clone());
+        self.c_tx = Some(c_tx);
         self.s_tx = Some(s_tx.clone());
+        self.first_rx = Some(s_rx);
 
-        // This task listens for events on the client receiving channel.
-        tokio::spawn(async move {
-            while let Some(event) = c_rx.recv().await {
-                match serde_json::to_string(&event) {
-                    // Take the JSON event and attempt to send it using our WebSocket.
-                    Ok(text) => {
-                        // If we have an error sending the message, output it.
-                        if let Err(e) = write.send(Message::Text(text)).await {
-                            tracing::error!("failed to send message: {}", e);
-                        }
-                    }
-                    // If we get an error converting to JSON, log the serialization failure.
-                    Err(e) => {
-                        tracing::error!("failed to serialize event: {}", e);
-                    }
-                }
-            }
-        });
+        // Spawn a task that owns the WebSocket. It sends client events, broadcasts server events,
+        // and reconnects if the connection drops.
+        let connection = connection::Connection::new(
+            self.config.clone(),
+            c_rx,
+            s_tx,
+            self.stats.clone(),
+            self.session.clone(),
+            self.rate_limits.clone(),
+            self.state_tx.clone(),
+        )
+        .with_recorder(recorder);
+        tokio::spawn(connection.run(ws_stream));
+        Ok(())
+    }
+
+    // Get a server receiver that only yields the given kinds of event, for consumers that
+    // care about a few events and would otherwise match and discard the rest.
+    pub async fn subscribe_filtered(
+        &mut self,
+        kinds: &[types::ServerEventKind],
+    ) -> Result<FilteredServerRx> {
+        let rx = self.server_events().await?;
+        Ok(FilteredServerRx::new(rx, kinds))
+    }
 
-        let stats = self.stats.clone();
-        // Spawn a task to listen for server events and transmit them.
-        // We first need to ensure we can get a message, then verify its type (text, binary, close),
-        // and finally determine the event type.
-        // We use the `read` half of the WebSocket to receive messages and the `s_tx` to broadcast events.
+    // Export every server event received from now on to a JSON-lines file.
+    // The file is created (or truncated) before this returns; writing happens in a background
+    // task that finishes when the connection closes.
+    pub async fn export_events_jsonl(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        options: ExportOptions,
+    ) -> Result<()> {
+        let rx = self.server_events().await?;
+        let file = tokio::fs::File::create(path).await?;
         tokio::spawn(async move {
-            // Get the message from the WebSocket, which will be in JSON format.
-            while let Some(message) = read.next().await {
-                let message = match message {
-                    Err(e) => {
-                        tracing::error!("failed to read message: {}", e);
-                        break;
-                    }
-                    Ok(message) => message,
-                };
-                // At this point, we have the message.
-                // Match the message variant to handle text, binary, or close messages.
-                match message {
-                    Message::Text(text) => {
-                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
-                            // Get the event type and ID as strings for logging.
-                            let event_type = json.get("type").and_then(|v| v.as_str());
-                            let event_id = json.get("event_id").and_then(|v| v.as_str());
-                            // Track the received messages.
-                            tracing::debug!(
-                                "received message: {}, id={}",
-                                event_type.unwrap_or("unknown"),
-                                event_id.unwrap_or("unknown")
-                            );
-                        }
-                        // Match the server event enum variant or handle the error.
-                        match serde_json::from_str::<types::ServerEvent>(&text) {
-                            Ok(event) => {
-                                // Send the server event across the transmitting server channel.
-                                if let Err(e) = s_tx.send(event.clone()) {
-                                    tracing::error!("failed to send event: {}", e);
-                                }
-
-                                // If the server is done responding, record its usage stats.
-                                if let types::ServerEvent::ResponseDone(response) = event {
-                                    if let Some(usage) = response.response().usage() {
-                                        let total_tokens = usage.total_tokens();
-                                        let input_tokens = usage.input_tokens();
-                                        let output_tokens = usage.output_tokens();
-
-                                        if let Ok(mut stats_guard) = stats.lock() {
-                                            stats_guard.update_usage(
-                                                total_tokens,
-                                                input_tokens,
-                                                output_tokens,
-                                            );
-                                        } else {
-                                            tracing::error!("failed to update stats");
-                                        }
-
-                                        tracing::debug!(
-                                            "total_tokens: {}, input_tokens: {}, output_tokens: {}",
-                                            total_tokens,
-                                            input_tokens,
-                                            output_tokens
-                                        );
-                                    }
-                                }
-                            }
-                            // Log an error if we couldn't properly deserialize the server event.
-                            Err(e) => {
-                                let json = serde_json::from_str::<serde_json::Value>(&text);
-                                json.map(|json| {
-                                    tracing::error!(
-                                        "failed to deserialize event: {}, type=> {:?}",
-                                        e,
-                                        json
-                                    );
-                                })
-                                .unwrap_or_else(|_| {
-                                    tracing::error!(
-                                        "failed to deserialize event: {}, text=> {:?}",
-                                        e,
-                                        text
-                                    );
-                                });
-                            }
-                        }
-                    }
-                    // We received a binary message, not JSON.
-                    Message::Binary(bin) => {
-                        tracing::warn!("unexpected binary message: {:?}", bin);
-                    }
-                    // The WebSocket connection was closed.
-                    Message::Close(reason) => {
-                        tracing::info!("connection closed: {:?}", reason);
-                        let close_event = types::ServerEvent::Close {
-                            reason: reason.map(|v| format!("{:?}", v)),
-                        };
-                        if let Err(e) = s_tx.send(close_event) {
-                            tracing::error!("failed to send close event: {}", e);
-                        }
-                        break;
-                    }
-                    _ => {}
-                }
+            let writer = tokio::io::BufWriter::new(file);
+            if let Err(e) = export::write_events_jsonl(rx, writer, options).await {
+                tracing::error!("failed to export events: {}", e);
             }
-            drop(c_tx);
-            drop(s_tx);
         });
         Ok(())
     }
 
+    // Get a receiver that observes changes to the connection state.
+    pub fn connection_state(&self) -> tokio::sync::watch::Receiver<ConnectionState> {
+        self.state_tx.subscribe()
+    }
+
+    // Return a snapshot of the stats.
+    pub fn stats(&self) -> Stats {
+        lock(&self.stats).clone()
+    }
+
+    // Return the session the client believes is active: the latest one sent with
+    // `update_session` or reported by a `session.updated` event, whichever came last.
+    pub fn current_session(&self) -> Option<Session> {
+        lock(&self.session).clone()
+    }
+
+    // Return the rate limits from the most recent `rate_limits.updated` event, if any,
+    // e.g. to check the remaining tokens before asking for another response.
+    pub fn rate_limits(&self) -> Option<Vec<RateLimitInformation>> {
+        lock(&self.rate_limits).clone()
+    }
+}
+
+#[async_trait]
+impl RealtimeClient for Client {
+    async fn send_event(&mut self, event: types::ClientEvent) -> Result<()> {
+        let Some(ref tx) = self.c_tx else {
+            return Err(anyhow::anyhow!("not connected yet"));
+        };
+        let session = match &event {
+            types::ClientEvent::SessionUpdate(update) => Some(update.session().clone()),
+            _ => None,
+        };
+        tx.send(event).await?;
+        if let Some(session) = session {
+            *lock(&self.session) = Some(session);
+        }
+        Ok(())
+    }
+
     // Get a server receiver that we can use to receive server events.
-    pub async fn server_events(&mut self) -> Result<ServerRx> {
+    // The first receiver starts at `Connected`; later ones only see events sent after subscribing.
+    async fn server_events(&mut self) -> Result<ServerRx> {
+        if let Some(rx) = self.first_rx.take() {
+            return Ok(rx);
+        }
         match self.s_tx {
             Some(ref tx) => Ok(tx.subscribe()),
             None => Err(anyhow::anyhow!("not connected yet")),
         }
     }
+}
 
-    // Return a stats object that we can use to inspect the stats.
-    pub fn stats(&self) -> Result<Stats> {
-        if let Ok(stats_guard) = self.stats.lock() {
-            Ok(stats_guard.clone())
-        } else {
-            Err(anyhow::anyhow!("failed to get stats"))
-        }
+// Lock shared client state, recovering from poisoning.
+// The stats and session are plain values with no invariants a panicking holder could break,
+// so it's safe to keep using them rather than losing them for the rest of the session.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Public function to create a client with specific config and connect to OpenAI.
+// Unless the config's `ReconnectPolicy` is disabled, a dropped connection is re-established
+// automatically. The server keeps no conversation across connections: only the latest
+// `update_session` is re-sent, so after a `Reconnected` event the caller must recreate any
+// conversation items it still needs, and responses in flight when the connection dropped are
+// lost.
+pub async fn connect_with_config(capacity: usize, config: config::Config) -> Result<Client> {
+    let mut client = Client::new(capacity, config);
+    client.connect().await?;
+    Ok(client)
+}
+
+// Public function to connect with default settings but an explicit API key, rather than the
+// one in the `OPENAI_API_KEY` environment variable.
+pub async fn connect_with_key(api_key: &str) -> Result<Client> {
+    let config = config::Config::builder().with_api_key(api_key).build();
+    connect_with_config(1024, config).await
+}
+
+// Public function to connect to a specific realtime model with an explicit API key, e.g. to pin
+// a model snapshot for reproducible sessions. Use `Config::builder()` to change anything else.
+pub async fn connect_with(model: &str, api_key: &str) -> Result<Client> {
+    let config = config::Config::builder()
+        .with_model(model)
+        .with_api_key(api_key)
+        .build();
+    connect_with_config(1024, config).await
+}
+
+// Public function to connect with default settings.
+pub async fn connect() -> Result<Client> {
+    // Create the default config object.
+    let config = config::Config::new();
+    // Call connect_with_config using the default config.
+    connect_with_config(1024, config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Starts a WebSocket server that accepts one connection and closes it when `close_rx` fires.
+    async fn start_server(close_rx: tokio::sync::oneshot::Receiver<()>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _ = close_rx.await;
+            ws.close(None).await.unwrap();
+        });
+        format!("ws://{}", addr)
     }
 
-    /// Send a client event.
-    async fn send_client_event(&mut self, event: types::ClientEvent) -> Result<()> {
-        match self.c_tx {
-            Some(ref tx) => {
-                tx.send(event).await?;
-                Ok(())
+    #[tokio::test]
+    async fn test_connection_state_transitions() {
+        let (close_tx, close_rx) = tokio::sync::oneshot::channel();
+        let base_url = start_server(close_rx).await;
+        let config = config::Config::builder()
+            .with_base_url(&base_url)
+            .with_api_key("test")
+            .with_reconnect_policy(ReconnectPolicy::disabled())
+            .build();
+
+        let mut client = Client::new(16, config);
+        let mut state = client.connection_state();
+        assert_eq!(*state.borrow_and_update(), ConnectionState::Disconnected);
+
+        client.connect().await.unwrap();
+        assert_eq!(*state.borrow_and_update(), ConnectionState::Connected);
+
+        close_tx.send(()).unwrap();
+        state
+            .wait_for(|s| *s == ConnectionState::Disconnected)
+            .await
+            .unwrap();
+    }
+
+    fn fast_reconnect_config(base_url: &str, max_retries: u32) -> config::Config {
+        config::Config::builder()
+            .with_base_url(base_url)
+            .with_api_key("test")
+            .with_reconnect_policy(
+                ReconnectPolicy::default()
+                    .with_max_retries(max_retries)
+                    .with_base_delay(std::time::Duration::from_millis(1)),
+            )
+            .build()
+    }
+
+    /// Reads client messages until one of the given type arrives, returning it as JSON.
+    async fn next_of_type(
+        ws: &mut tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>,
+        event_type: &str,
+    ) -> Option<serde_json::Value> {
+        use futures_util::StreamExt;
+        while let Some(Ok(message)) = ws.next().await {
+            if let tokio_tungstenite::tungstenite::Message::Text(text) = message {
+                let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                if json["type"] == event_type {
+                    return Some(json);
+                }
             }
-            None => Err(anyhow::anyhow!("not connected yet")),
         }
+        None
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_and_restores_session() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        let (restored_tx, restored_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            // The first connection receives the session, then drops.
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            next_of_type(&mut ws, "session.update").await.unwrap();
+            ws.close(None).await.unwrap();
+
+            // The second connection should receive the same session again.
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let session = next_of_type(&mut ws, "session.update").await.unwrap();
+            restored_tx.send(session).unwrap();
+            // Keep the connection open until the client goes away.
+            next_of_type(&mut ws, "never").await;
+        });
+
+        let mut client = Client::new(16, fast_reconnect_config(&base_url, 3));
+        client.connect().await.unwrap();
+        let mut events = client.server_events().await.unwrap();
+        assert!(matches!(events.recv().await.unwrap(), types::ServerEvent::Connected));
+        client
+            .update_session(Session::new().with_instructions("be brief").build())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            types::ServerEvent::Reconnecting { attempt: 1 }
+        ));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            types::ServerEvent::Reconnected
+        ));
+        let session = restored_rx.await.unwrap();
+        assert_eq!(session["session"]["instructions"], "be brief");
+        assert_eq!(*client.connection_state().borrow(), ConnectionState::Connected);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let (close_tx, close_rx) = tokio::sync::oneshot::channel();
+        let base_url = start_server(close_rx).await;
+
+        let mut client = Client::new(16, fast_reconnect_config(&base_url, 2));
+        client.connect().await.unwrap();
+        let mut events = client.server_events().await.unwrap();
+        assert!(matches!(events.recv().await.unwrap(), types::ServerEvent::Connected));
+        let mut state = client.connection_state();
+
+        // The server closes and stops listening, so every reconnect attempt fails.
+        close_tx.send(()).unwrap();
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            types::ServerEvent::Reconnecting { attempt: 1 }
+        ));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            types::ServerEvent::Reconnecting { attempt: 2 }
+        ));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            types::ServerEvent::Close { .. }
+        ));
+        state
+            .wait_for(|s| *s == ConnectionState::Disconnected)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dropping_client_closes_without_reconnecting() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            // Wait for the client to close the socket.
+            next_of_type(&mut ws, "never").await;
+            // No reconnect attempt should follow.
+            tokio::time::timeout(std::time::Duration::from_millis(200), listener.accept())
+                .await
+                .is_err()
+        });
+
+        let mut client = Client::new(16, fast_reconnect_config(&base_url, 3));
+        client.connect().await.unwrap();
+        drop(client);
+
+        assert!(server.await.unwrap(), "client should not reconnect after being dropped");
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_pings_while_idle() {
+        use futures_util::StreamExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(message)) = ws.next().await {
+                if message.is_ping() {
+                    return true;
+                }
+            }
+            false
+        });
+
+        let config = config::Config::builder()
+            .with_base_url(&base_url)
+            .with_api_key("test")
+            .with_keepalive_interval(std::time::Duration::from_millis(20))
+            .build();
+        let _client = connect_with_config(16, config).await.unwrap();
+
+        let pinged = tokio::time::timeout(std::time::Duration::from_secs(5), server)
+            .await
+            .expect("no keepalive ping within 5 seconds")
+            .unwrap();
+        assert!(pinged);
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_server_is_dropped_after_keepalive_timeout() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            // Complete the handshake, then never read again, so pings go unanswered.
+            let (stream, _) = listener.accept().await.unwrap();
+            let _ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            std::future::pending::<()>().await;
+        });
+
+        let config = config::Config::builder()
+            .with_base_url(&base_url)
+            .with_api_key("test")
+            .with_reconnect_policy(ReconnectPolicy::disabled())
+            .with_keepalive_interval(std::time::Duration::from_millis(20))
+            .with_keepalive_timeout(std::time::Duration::from_millis(50))
+            .build();
+        let mut client = connect_with_config(16, config).await.unwrap();
+        let mut events = client.server_events().await.unwrap();
+        assert!(matches!(events.recv().await.unwrap(), types::ServerEvent::Connected));
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), events.recv())
+            .await
+            .expect("connection was not dropped within 5 seconds")
+            .unwrap();
+        assert!(matches!(
+            event,
+            types::ServerEvent::Close { reason: Some(ref reason) } if reason == "keepalive timeout"
+        ));
     }
 
-    // Function to send an update session event.
-    pub async fn update_session(&mut self, config: Session) -> Result<()> {
-        let event = types::ClientEvent::SessionUpdate(
-            types::events::client::SessionUpdateEvent::new(config),
+    #[test]
+    fn test_request_uses_configured_model_and_base_url() {
+        let config = config::Config::builder()
+            .with_base_url("ws://localhost:9000/v1")
+            .with_model("gpt-4o-realtime-preview-2024-12-17")
+            .with_api_key("test")
+            .build();
+        let request = utils::build_request(&config).unwrap();
+        assert_eq!(
+            request.uri().to_string(),
+            "ws://localhost:9000/v1/realtime?model=gpt-4o-realtime-preview-2024-12-17"
         );
-        self.send_client_event(event).await
+        assert_eq!(request.headers()["Authorization"], "Bearer test");
     }
 
-    // Function to send an input audio buffer event.
-    pub async fn append_input_audio_buffer(
-        &mut self,
-        audio: Base64EncodedAudioBytes,
-    ) -> Result<()> {
-        let event = types::ClientEvent::InputAudioBufferAppend(
-            types::events::client::InputAudioBufferAppendEvent::new(audio),
+    /// Creates a client whose outbound events are captured on the returned receiver.
+    fn capturing_client() -> (Client, tokio::sync::mpsc::Receiver<types::ClientEvent>) {
+        let (c_tx, c_rx) = tokio::sync::mpsc::channel(16);
+        let mut client = Client::new(16, config::Config::new());
+        client.c_tx = Some(c_tx);
+        (client, c_rx)
+    }
+
+    #[tokio::test]
+    async fn test_input_audio_buffer_commit_and_clear_serialization() {
+        let (mut client, mut c_rx) = capturing_client();
+
+        client.commit_input_audio_buffer().await.unwrap();
+        client.clear_input_audio_buffer().await.unwrap();
+
+        let commit = serde_json::to_value(c_rx.recv().await.unwrap()).unwrap();
+        assert_eq!(commit, serde_json::json!({ "type": "input_audio_buffer.commit" }));
+        let clear = serde_json::to_value(c_rx.recv().await.unwrap()).unwrap();
+        assert_eq!(clear, serde_json::json!({ "type": "input_audio_buffer.clear" }));
+    }
+
+    #[tokio::test]
+    async fn test_create_function_call_output() {
+        let (mut client, mut c_rx) = capturing_client();
+
+        client
+            .create_function_call_output("call_1", "42".to_string())
+            .await
+            .unwrap();
+
+        let event = serde_json::to_value(c_rx.recv().await.unwrap()).unwrap();
+        assert_eq!(
+            event,
+            serde_json::json!({
+                "type": "conversation.item.create",
+                "previous_item_id": null,
+                "item": {
+                    "type": "function_call_output",
+                    "call_id": "call_1",
+                    "output": "42",
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_conversation_item_after_sets_previous_item_id() {
+        let (mut client, mut c_rx) = capturing_client();
+
+        let item = types::MessageItem::builder()
+            .with_role(types::MessageRole::System)
+            .with_input_text("hold on")
+            .build();
+        client
+            .create_conversation_item_after(types::Item::Message(item), "item_1")
+            .await
+            .unwrap();
+
+        let event = serde_json::to_value(c_rx.recv().await.unwrap()).unwrap();
+        assert_eq!(event["type"], "conversation.item.create");
+        assert_eq!(event["previous_item_id"], "item_1");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_response_sends_cancel_event() {
+        let (mut client, mut c_rx) = capturing_client();
+
+        client.cancel_response().await.unwrap();
+
+        assert!(matches!(
+            c_rx.recv().await,
+            Some(types::ClientEvent::ResponseCancel(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_send_event_passes_event_through() {
+        let (mut client, mut c_rx) = capturing_client();
+        let event = types::ClientEvent::ConversationItemDelete(
+            types::events::client::ConversationItemDeleteEvent::new("item_1"),
+        );
+
+        client.send_event(event).await.unwrap();
+
+        let event = serde_json::to_value(c_rx.recv().await.unwrap()).unwrap();
+        assert_eq!(event["type"], "conversation.item.delete");
+        assert_eq!(event["item_id"], "item_1");
+    }
+
+    #[tokio::test]
+    async fn test_truncate_item_sends_truncate_event() {
+        let (mut client, mut c_rx) = capturing_client();
+
+        client.truncate_item("item_1", 0, 1500).await.unwrap();
+
+        let event = serde_json::to_value(c_rx.recv().await.unwrap()).unwrap();
+        assert_eq!(
+            event,
+            serde_json::json!({
+                "type": "conversation.item.truncate",
+                "item_id": "item_1",
+                "content_index": 0,
+                "audio_end_ms": 1500,
+            })
         );
-        self.send_client_event(event).await
     }
 
-    // Function to send a conversation item event.
-    pub async fn create_conversation_item(&mut self, item: types::Item) -> Result<()> {
-        let event = types::ClientEvent::ConversationItemCreate(
-            types::events::client::ConversationItemCreateEvent::new(item),
+    #[tokio::test]
+    async fn test_commit_input_audio_buffer_does_not_create_response() {
+        let (mut client, mut c_rx) = capturing_client();
+
+        client.commit_input_audio_buffer().await.unwrap();
+        drop(client);
+
+        assert!(matches!(
+            c_rx.recv().await,
+            Some(types::ClientEvent::InputAudioBufferCommit(_))
+        ));
+        assert!(c_rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_current_session_tracks_updates() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            use futures_util::SinkExt;
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            // Acknowledge the client's update with a server-side view of the session.
+            next_of_type(&mut ws, "session.update").await.unwrap();
+            let updated = serde_json::json!({
+                "type": "session.updated",
+                "event_id": "event_1",
+                "session": {
+                    "id": "sess_1",
+                    "object": "realtime.session",
+                    "modalities": ["text", "audio"],
+                    "instructions": "from the server",
+                    "voice": "echo",
+                    "tools": [],
+                    "temperature": 0.8,
+                },
+            });
+            ws.send(tokio_tungstenite::tungstenite::Message::Text(updated.to_string()))
+                .await
+                .unwrap();
+            next_of_type(&mut ws, "never").await;
+        });
+
+        let mut client = Client::new(16, fast_reconnect_config(&base_url, 0));
+        client.connect().await.unwrap();
+        let mut events = client.server_events().await.unwrap();
+        assert!(matches!(events.recv().await.unwrap(), types::ServerEvent::Connected));
+        assert!(client.current_session().is_none());
+
+        client
+            .update_session(Session::new().with_instructions("from the client").build())
+            .await
+            .unwrap();
+        let session = client.current_session().unwrap();
+        assert_eq!(session.instructions(), Some("from the client"));
+
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            types::ServerEvent::SessionUpdated(_)
+        ));
+        let session = client.current_session().unwrap();
+        assert_eq!(session.instructions(), Some("from the server"));
+        assert_eq!(session.voice(), Some(&types::audio::Voice::Echo));
+
+        // A partial update starts from the current session.
+        let partial = session
+            .into_configurator()
+            .with_voice(types::audio::Voice::Alloy)
+            .build();
+        assert_eq!(partial.instructions(), Some("from the server"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limits_cache_latest_update() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            use futures_util::SinkExt;
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for remaining in [4000, 3500] {
+                let updated = serde_json::json!({
+                    "type": "rate_limits.updated",
+                    "event_id": "event_1",
+                    "rate_limits": [
+                        {
+                            "name": "requests",
+                            "limit": 1000,
+                            "remaining": 999,
+                            "reset_seconds": 0.06,
+                        },
+                        {
+                            "name": "tokens",
+                            "limit": 5000,
+                            "remaining": remaining,
+                            "reset_seconds": 1.2,
+                        },
+                    ],
+                });
+                ws.send(tokio_tungstenite::tungstenite::Message::Text(updated.to_string()))
+                    .await
+                    .unwrap();
+            }
+            next_of_type(&mut ws, "never").await;
+        });
+
+        let mut client = Client::new(16, fast_reconnect_config(&base_url, 0));
+        assert!(client.rate_limits().is_none());
+        client.connect().await.unwrap();
+        let mut events = client.server_events().await.unwrap();
+        assert!(matches!(events.recv().await.unwrap(), types::ServerEvent::Connected));
+        for _ in 0..2 {
+            assert!(matches!(
+                events.recv().await.unwrap(),
+                types::ServerEvent::RateLimitsUpdated(_)
+            ));
+        }
+
+        let rate_limits = client.rate_limits().unwrap();
+        assert_eq!(rate_limits.len(), 2);
+        assert_eq!(rate_limits[1].name(), "tokens");
+        assert_eq!(rate_limits[1].remaining(), 3500);
+    }
+
+    #[tokio::test]
+    async fn test_stats_record_response_latency() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            use futures_util::SinkExt;
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let events = [
+                response_event("response.created", "in_progress"),
+                response_event("response.done", "completed"),
+            ];
+            for event in events {
+                ws.send(tokio_tungstenite::tungstenite::Message::Text(event.to_string()))
+                    .await
+                    .unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+            next_of_type(&mut ws, "never").await;
+        });
+
+        let mut client = Client::new(16, fast_reconnect_config(&base_url, 0));
+        client.connect().await.unwrap();
+        let mut events = client.server_events().await.unwrap();
+        assert!(matches!(events.recv().await.unwrap(), types::ServerEvent::Connected));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            types::ServerEvent::ResponseCreated(_)
+        ));
+        assert!(client.stats().last_latency().is_none());
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            types::ServerEvent::ResponseDone(_)
+        ));
+
+        let stats = client.stats();
+        let latency = stats.last_latency().unwrap();
+        assert!(
+            latency >= std::time::Duration::from_millis(20),
+            "latency was {:?}",
+            latency
         );
-        self.send_client_event(event).await
+        assert_eq!(stats.average_latency(), Some(latency));
+    }
+
+    fn response_event(kind: &str, status: &str) -> serde_json::Value {
+        serde_json::json!({
+            "type": kind,
+            "event_id": "event_1",
+            "response": {
+                "id": "resp_1",
+                "object": "realtime.response",
+                "status": status,
+                "status_details": null,
+                "output": [],
+                "usage": null,
+            },
+        })
+    }
+
+    /// Serves the given events once a client connects, then waits.
+    fn serve_events(listener: tokio::net::TcpListener, events: Vec<serde_json::Value>) {
+        tokio::spawn(async move {
+            use futures_util::SinkExt;
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for event in events {
+                ws.send(tokio_tungstenite::tungstenite::Message::Text(event.to_string()))
+                    .await
+                    .unwrap();
+            }
+            next_of_type(&mut ws, "never").await;
+        });
+    }
+
+    fn error_event(kind: &str, message: &str, event_id: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "type": "error",
+            "event_id": "event_2",
+            "error": {
+                "type": kind,
+                "code": null,
+                "message": message,
+                "param": null,
+                "event_id": event_id,
+            },
+        })
+    }
+
+    /// Connects to `base_url` and waits for the first `response.done`.
+    async fn client_after_response_done(base_url: &str) -> Client {
+        let mut client = Client::new(16, fast_reconnect_config(base_url, 0));
+        client.connect().await.unwrap();
+        let mut events = client.server_events().await.unwrap();
+        loop {
+            if let types::ServerEvent::ResponseDone(_) = events.recv().await.unwrap() {
+                break;
+            }
+        }
+        client
     }
 
-    // Function to send a create response event.
-    pub async fn create_response(&mut self) -> Result<()> {
-        let event =
-            types::ClientEvent::ResponseCreate(types::events::client::ResponseCreateEvent::new());
-        self.send_client_event(event).await
+    #[tokio::test]
+    async fn test_stats_skip_failed_responses() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        serve_events(
+            listener,
+            vec![
+                response_event("response.created", "in_progress"),
+                error_event("server_error", "The server had an error", None),
+                response_event("response.done", "failed"),
+            ],
+        );
+
+        let client = client_after_response_done(&base_url).await;
+        assert!(client.stats().last_latency().is_none());
     }
 
-    // Function to send a create response event with a specific config.
-    pub async fn create_response_with_config(&mut self, config: Session) -> Result<()> {
-        let event = types::ClientEvent::ResponseCreate(
-            types::events::client::ResponseCreateEvent::new().with_update_session(config),
+    #[tokio::test]
+    async fn test_stats_record_latency_through_unrelated_error() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("ws://{}", listener.local_addr().unwrap());
+        serve_events(
+            listener,
+            vec![
+                response_event("response.created", "in_progress"),
+                // A rejected client event, e.g. truncating an item that doesn't exist.
+                error_event(
+                    "invalid_request_error",
+                    "Item not found",
+                    Some("event_truncate"),
+                ),
+                response_event("response.done", "completed"),
+            ],
         );
-        self.send_client_event(event).await
+
+        let client = client_after_response_done(&base_url).await;
+        assert!(client.stats().last_latency().is_some());
+        assert!(client.stats().average_latency().is_some());
     }
-}
 
-// Public function to create a client with specific config and connect to OpenAI.
-pub async fn connect_with_config(capacity: usize, config: config::Config) -> Result<Client> {
-    let mut client = Client::new(capacity, config);
-    client.connect().await?;
-    Ok(client)
-}
+    #[test]
+    fn test_stats_survive_poisoned_lock() {
+        let client = Client::new(16, config::Config::new());
+        lock(&client.stats).update_usage(10, 4, 6);
 
-// Public function to connect with default settings.
-pub async fn connect() -> Result<Client> {
-    // Create the default config object.
-    let config = config::Config::new();
-    // Call connect_with_config using the default config.
-    connect_with_config(1024, config).await
+        // Panic while holding the lock to poison it.
+        let stats = client.stats.clone();
+        let result = std::thread::spawn(move || {
+            let _guard = stats.lock().unwrap();
+            panic!("poison the stats lock");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(client.stats.is_poisoned());
+
+        lock(&client.stats).update_usage(5, 2, 3);
+        let stats = client.stats();
+        assert_eq!(stats.total_tokens(), 15);
+        assert_eq!(stats.input_tokens(), 6);
+        assert_eq!(stats.output_tokens(), 9);
+    }
+
+    #[tokio::test]
+    async fn test_connection_state_after_failed_connect() {
+        // Bind and drop a listener to get a port nothing is listening on.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let config = config::Config::builder()
+            .with_base_url(&format!("ws://{}", addr))
+            .build();
+        let mut client = Client::new(16, config);
+        let state = client.connection_state();
+
+        assert!(client.connect().await.is_err());
+        assert_eq!(*state.borrow(), ConnectionState::Disconnected);
+    }
 }
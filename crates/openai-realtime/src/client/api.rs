@@ -0,0 +1,136 @@
+use crate::types;
+use anyhow::Result;
+use async_trait::async_trait;
+use openai_realtime_types::audio::Base64EncodedAudioBytes;
+use openai_realtime_types::session::Session;
+
+use super::ServerRx;
+
+/// The operations shared by `Client` and `ReplayClient`, so code can be written once against
+/// either a live connection or a scripted one.
+///
+/// Implementors only provide `send_event` and `server_events`; every typed method builds its
+/// client event and sends it with `send_event`.
+#[async_trait]
+pub trait RealtimeClient: Send {
+    /// Send any client event as-is. The typed methods below cover the common events; this is
+    /// the escape hatch for ones that don't have a method yet.
+    async fn send_event(&mut self, event: types::ClientEvent) -> Result<()>;
+
+    // Get a receiver for the events the server sends.
+    async fn server_events(&mut self) -> Result<ServerRx>;
+
+    // Function to send an update session event.
+    async fn update_session(&mut self, config: Session) -> Result<()> {
+        let event = types::ClientEvent::SessionUpdate(
+            types::events::client::SessionUpdateEvent::new(config),
+        );
+        self.send_event(event).await
+    }
+
+    // Function to send an input audio buffer event.
+    async fn append_input_audio_buffer(&mut self, audio: Base64EncodedAudioBytes) -> Result<()> {
+        let event = types::ClientEvent::InputAudioBufferAppend(
+            types::events::client::InputAudioBufferAppendEvent::new(audio),
+        );
+        self.send_event(event).await
+    }
+
+    // Function to return the output of a function the model called.
+    // `call_id` comes from the `response.function_call_arguments.done` event. The model does not
+    // respond to the output on its own; call `create_response` afterwards if a reply is wanted.
+    async fn create_function_call_output(&mut self, call_id: &str, output: String) -> Result<()> {
+        let item =
+            types::Item::FunctionCallOutput(types::FunctionCallOutputItem::new(call_id, output));
+        self.create_conversation_item(item).await
+    }
+
+    // Function to commit the input audio buffer as a user turn.
+    // This does not create a response; call `create_response` separately if one is wanted.
+    async fn commit_input_audio_buffer(&mut self) -> Result<()> {
+        let event = types::ClientEvent::InputAudioBufferCommit(
+            types::events::client::InputAudioBufferCommitEvent::new(),
+        );
+        self.send_event(event).await
+    }
+
+    // Function to send a conversation item event.
+    async fn create_conversation_item(&mut self, item: types::Item) -> Result<()> {
+        let event = types::ClientEvent::ConversationItemCreate(
+            types::events::client::ConversationItemCreateEvent::new(item),
+        );
+        self.send_event(event).await
+    }
+
+    // Function to insert an item right after `previous_item_id` instead of at the end of the
+    // conversation.
+    async fn create_conversation_item_after(
+        &mut self,
+        item: types::Item,
+        previous_item_id: &str,
+    ) -> Result<()> {
+        let event = types::ClientEvent::ConversationItemCreate(
+            types::events::client::ConversationItemCreateEvent::new(item)
+                .with_previous_item_id(previous_item_id),
+        );
+        self.send_event(event).await
+    }
+
+    // Function to discard any audio in the input buffer that hasn't been committed.
+    async fn clear_input_audio_buffer(&mut self) -> Result<()> {
+        let event = types::ClientEvent::InputAudioBufferClear(
+            types::events::client::InputAudioBufferClearEvent::new(),
+        );
+        self.send_event(event).await
+    }
+
+    // Function to delete an item from the server-side conversation.
+    // The client does not track item ids, so resetting the whole conversation means
+    // deleting each item the caller has seen (e.g. from `conversation.item.created` events).
+    async fn delete_conversation_item(&mut self, item_id: &str) -> Result<()> {
+        let event = types::ClientEvent::ConversationItemDelete(
+            types::events::client::ConversationItemDeleteEvent::new(item_id),
+        );
+        self.send_event(event).await
+    }
+
+    // Function to cut an assistant audio item off at `audio_end_ms`, e.g. when the user barges in,
+    // so the model's context only holds the audio that was actually played.
+    async fn truncate_item(
+        &mut self,
+        item_id: &str,
+        content_index: i32,
+        audio_end_ms: i32,
+    ) -> Result<()> {
+        let event = types::ClientEvent::ConversationItemTruncate(
+            types::events::client::ConversationItemTruncateEvent::new(
+                item_id,
+                content_index,
+                audio_end_ms,
+            ),
+        );
+        self.send_event(event).await
+    }
+
+    // Function to send a create response event.
+    async fn create_response(&mut self) -> Result<()> {
+        let event =
+            types::ClientEvent::ResponseCreate(types::events::client::ResponseCreateEvent::new());
+        self.send_event(event).await
+    }
+
+    // Function to cancel the in-progress response, e.g. when the user starts talking over the AI.
+    async fn cancel_response(&mut self) -> Result<()> {
+        let event =
+            types::ClientEvent::ResponseCancel(types::events::client::ResponseCancelEvent::new());
+        self.send_event(event).await
+    }
+
+    // Function to send a create response event with a specific config.
+    async fn create_response_with_config(&mut self, config: Session) -> Result<()> {
+        let event = types::ClientEvent::ResponseCreate(
+            types::events::client::ResponseCreateEvent::new().with_update_session(config),
+        );
+        self.send_event(event).await
+    }
+}
@@ -1,17 +1,29 @@
+use crate::client::consts::{BASE_URL, DEFAULT_MODEL, OPENAI_API_KEY};
+use crate::client::reconnect::ReconnectPolicy;
 use secrecy::SecretString;
-use tokio_tungstenite::tungstenite::client::IntoClientRequest;
-use tokio_tungstenite::tungstenite::handshake::client::Request;
+use std::path::PathBuf;
+use std::time::Duration;
 
 pub struct Config {
     base_url: String,
     api_key: SecretString,
     model: String,
+    reconnect_policy: ReconnectPolicy,
+    keepalive_interval: Option<Duration>,
+    keepalive_timeout: Option<Duration>,
+    recording_path: Option<PathBuf>,
 }
 
 pub struct ConfigBuilder {
     config: Config,
 }
 
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ConfigBuilder {
     pub fn new() -> Self {
         Self {
@@ -34,25 +46,61 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn with_reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.config.reconnect_policy = reconnect_policy;
+        self
+    }
+
+    // Ping the server after this long without sending anything, so proxies that reap idle
+    // sockets don't drop the connection between turns. Off by default.
+    pub fn with_keepalive_interval(mut self, keepalive_interval: Duration) -> Self {
+        self.config.keepalive_interval = Some(keepalive_interval);
+        self
+    }
+
+    // Treat the connection as dead, and reconnect, if nothing at all comes back within this long
+    // of a keepalive ping. Only applies when a keepalive interval is set.
+    pub fn with_keepalive_timeout(mut self, keepalive_timeout: Duration) -> Self {
+        self.config.keepalive_timeout = Some(keepalive_timeout);
+        self
+    }
+
+    // Append every event sent and received to this JSON-lines file, for debugging sessions
+    // after the fact. Read a recording back with `replay`.
+    pub fn with_recording_path(mut self, recording_path: impl Into<PathBuf>) -> Self {
+        self.config.recording_path = Some(recording_path.into());
+        self
+    }
+
     pub fn build(self) -> Config {
         self.config
     }
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Config {
     // Sets the default values.
     pub fn new() -> Self {
         Self {
             // Convert the &str to String.
-            base_url: "wss://api.openai.com/v1".to_string(),
+            base_url: BASE_URL.to_string(),
             // Get the API key from the environment variable.
             // Unwrap the Result and handle it so we don't panic.
             // Convert api_key to SecretString if necessary.
-            api_key: std::env::var("OPENAI_API_KEY")
+            api_key: std::env::var(OPENAI_API_KEY)
                 .unwrap_or_else(|_| "".to_string())
                 .into(),
             // Declare the default model.
-            model: "gpt-4o-realtime-preview-2024-10-01".to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            reconnect_policy: ReconnectPolicy::default(),
+            keepalive_interval: None,
+            keepalive_timeout: None,
+            recording_path: None,
         }
     }
 
@@ -71,4 +119,20 @@ impl Config {
     pub fn model(&self) -> &str {
         &self.model
     }
+
+    pub fn reconnect_policy(&self) -> &ReconnectPolicy {
+        &self.reconnect_policy
+    }
+
+    pub fn keepalive_interval(&self) -> Option<Duration> {
+        self.keepalive_interval
+    }
+
+    pub fn keepalive_timeout(&self) -> Option<Duration> {
+        self.keepalive_timeout
+    }
+
+    pub fn recording_path(&self) -> Option<&std::path::Path> {
+        self.recording_path.as_deref()
+    }
 }
@@ -0,0 +1,308 @@
+use crate::client::config::Config;
+use crate::client::recorder::Recorder;
+use crate::client::state::ConnectionState;
+use crate::client::stats::Stats;
+use crate::client::{ServerTx, lock, utils};
+use crate::types;
+use futures_util::{SinkExt, StreamExt};
+use openai_realtime_types::events::RateLimitInformation;
+use openai_realtime_types::session::Session;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// How long a response may go without a `response.done` before it is no longer timed.
+const STALE_RESPONSE_AGE: Duration = Duration::from_secs(600);
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Why the socket stopped being usable.
+enum Disconnect {
+    /// Every client handle was dropped, so nobody can send events any more.
+    ClientClosed,
+    /// The server closed the socket or the network failed.
+    Dropped(Option<String>),
+}
+
+/// Owns the WebSocket for the lifetime of a client, forwarding client events out and
+/// broadcasting server events in, and reconnecting according to the config's policy.
+pub(crate) struct Connection {
+    config: Arc<Config>,
+    c_rx: tokio::sync::mpsc::Receiver<types::ClientEvent>,
+    s_tx: ServerTx,
+    stats: Arc<Mutex<Stats>>,
+    session: Arc<Mutex<Option<Session>>>,
+    rate_limits: Arc<Mutex<Option<Vec<RateLimitInformation>>>>,
+    state_tx: tokio::sync::watch::Sender<ConnectionState>,
+    // The most recent `session.update`, re-sent after reconnecting to restore the session.
+    last_session_update: Option<types::ClientEvent>,
+    // When each in-flight response was created, keyed by response ID, for latency stats.
+    // The server ends every response with a `response.done`, whatever its status; entries
+    // are also dropped on reconnect, and after `STALE_RESPONSE_AGE` in case one never comes.
+    response_started: HashMap<String, Instant>,
+    // Logs every event sent and received, when recording is enabled.
+    recorder: Option<Recorder>,
+}
+
+impl Connection {
+    pub(crate) fn new(
+        config: Arc<Config>,
+        c_rx: tokio::sync::mpsc::Receiver<types::ClientEvent>,
+        s_tx: ServerTx,
+        stats: Arc<Mutex<Stats>>,
+        session: Arc<Mutex<Option<Session>>>,
+        rate_limits: Arc<Mutex<Option<Vec<RateLimitInformation>>>>,
+        state_tx: tokio::sync::watch::Sender<ConnectionState>,
+    ) -> Self {
+        Self {
+            config,
+            c_rx,
+            s_tx,
+            stats,
+            session,
+            rate_limits,
+            state_tx,
+            last_session_update: None,
+            response_started: HashMap::new(),
+            recorder: None,
+        }
+    }
+
+    pub(crate) fn with_recorder(mut self, recorder: Option<Recorder>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    /// Drives the connection until the client closes it or reconnecting fails.
+    pub(crate) async fn run(mut self, mut ws: WsStream) {
+        self.broadcast(types::ServerEvent::Connected);
+        loop {
+            let reason = match self.pump(&mut ws).await {
+                Disconnect::ClientClosed => break,
+                Disconnect::Dropped(reason) => reason,
+            };
+            // Responses on the old socket won't finish on the new one.
+            self.response_started.clear();
+            match self.reconnect().await {
+                Some(new_ws) => ws = new_ws,
+                None => {
+                    self.broadcast(types::ServerEvent::Close { reason });
+                    break;
+                }
+            }
+        }
+        self.state_tx.send_replace(ConnectionState::Disconnected);
+        // Closing the connection completes the recording.
+        if let Some(recorder) = self.recorder.take() {
+            recorder.finish().await;
+        }
+    }
+
+    // Forward events in both directions until the socket is no longer usable.
+    async fn pump(&mut self, ws: &mut WsStream) -> Disconnect {
+        // With keepalive on, a ping goes out whenever nothing has been sent for the interval.
+        let keepalive = self.config.keepalive_interval();
+        let mut ping_at = keepalive.map(|interval| tokio::time::Instant::now() + interval);
+        // Set while a ping is waiting for the server to show signs of life.
+        let mut pong_deadline: Option<tokio::time::Instant> = None;
+        loop {
+            tokio::select! {
+                event = self.c_rx.recv() => {
+                    let Some(event) = event else {
+                        // The client was dropped; close the socket cleanly without reconnecting.
+                        if let Err(e) = ws.close(None).await {
+                            tracing::debug!("failed to close connection: {}", e);
+                        }
+                        return Disconnect::ClientClosed;
+                    };
+                    if matches!(event, types::ClientEvent::SessionUpdate(_)) {
+                        self.last_session_update = Some(event.clone());
+                    }
+                    if let Err(e) = send_event(ws, &event).await {
+                        tracing::error!("failed to send message: {}", e);
+                        return Disconnect::Dropped(Some(e.to_string()));
+                    }
+                    if let Some(recorder) = &mut self.recorder {
+                        recorder.record_outbound(&event);
+                    }
+                    ping_at = keepalive.map(|interval| tokio::time::Instant::now() + interval);
+                }
+                _ = sleep_until_some(ping_at), if ping_at.is_some() => {
+                    tracing::trace!("sending keepalive ping");
+                    if let Err(e) = ws.send(Message::Ping(Vec::new())).await {
+                        tracing::error!("failed to send keepalive ping: {}", e);
+                        return Disconnect::Dropped(Some(e.to_string()));
+                    }
+                    ping_at = keepalive.map(|interval| tokio::time::Instant::now() + interval);
+                    if pong_deadline.is_none()
+                        && let Some(timeout) = self.config.keepalive_timeout()
+                    {
+                        pong_deadline = Some(tokio::time::Instant::now() + timeout);
+                    }
+                }
+                _ = sleep_until_some(pong_deadline), if pong_deadline.is_some() => {
+                    tracing::warn!("no response to keepalive ping, dropping connection");
+                    return Disconnect::Dropped(Some("keepalive timeout".to_string()));
+                }
+                message = ws.next() => {
+                    // Any frame, not only a pong, shows the server is still there.
+                    pong_deadline = None;
+                    match message {
+                        Some(Ok(Message::Text(text))) => self.handle_text(&text),
+                        // We received a binary message, not JSON.
+                        Some(Ok(Message::Binary(bin))) => {
+                            tracing::warn!("unexpected binary message: {:?}", bin);
+                        }
+                        // The WebSocket connection was closed.
+                        Some(Ok(Message::Close(reason))) => {
+                            tracing::info!("connection closed: {:?}", reason);
+                            return Disconnect::Dropped(reason.map(|v| format!("{:?}", v)));
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            tracing::error!("failed to read message: {}", e);
+                            return Disconnect::Dropped(Some(e.to_string()));
+                        }
+                        None => return Disconnect::Dropped(None),
+                    }
+                }
+            }
+        }
+    }
+
+    // Try to re-establish the socket, returning `None` once the policy's attempts are used up.
+    async fn reconnect(&mut self) -> Option<WsStream> {
+        let policy = self.config.reconnect_policy().clone();
+        for attempt in 1..=policy.max_retries {
+            if self.c_rx.is_closed() {
+                // The client was dropped while we were disconnected.
+                return None;
+            }
+            self.state_tx.send_replace(ConnectionState::Reconnecting);
+            self.broadcast(types::ServerEvent::Reconnecting { attempt });
+            tokio::time::sleep(policy.delay_for(attempt)).await;
+
+            let request = match utils::build_request(&self.config) {
+                Ok(request) => request,
+                Err(e) => {
+                    tracing::error!("failed to build reconnect request: {}", e);
+                    return None;
+                }
+            };
+            let mut ws = match tokio_tungstenite::connect_async(request).await {
+                Ok((ws, _)) => ws,
+                Err(e) => {
+                    tracing::warn!("reconnect attempt {} failed: {}", attempt, e);
+                    continue;
+                }
+            };
+            // Restore the session before anything else is sent on the new socket.
+            if let Some(event) = &self.last_session_update
+                && let Err(e) = send_event(&mut ws, event).await
+            {
+                tracing::warn!("failed to restore session on attempt {}: {}", attempt, e);
+                continue;
+            }
+            tracing::info!("reconnected after {} attempt(s)", attempt);
+            self.state_tx.send_replace(ConnectionState::Connected);
+            self.broadcast(types::ServerEvent::Reconnected);
+            return Some(ws);
+        }
+        None
+    }
+
+    fn handle_text(&mut self, text: &str) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(text) {
+            // Get the event type and ID as strings for logging.
+            let event_type = json.get("type").and_then(|v| v.as_str());
+            let event_id = json.get("event_id").and_then(|v| v.as_str());
+            // Track the received messages.
+            tracing::debug!(
+                "received message: {}, id={}",
+                event_type.unwrap_or("unknown"),
+                event_id.unwrap_or("unknown")
+            );
+        }
+        // Match the server event enum variant or handle the error.
+        match serde_json::from_str::<types::ServerEvent>(text) {
+            Ok(event) => {
+                if let Some(recorder) = &mut self.recorder {
+                    recorder.record_inbound(&event);
+                }
+                // Time each response from creation to completion.
+                if let types::ServerEvent::ResponseCreated(created) = &event {
+                    self.response_started
+                        .retain(|_, started| started.elapsed() < STALE_RESPONSE_AGE);
+                    self.response_started
+                        .insert(created.response().id().to_string(), Instant::now());
+                }
+                // A cancelled or failed response's latency says nothing about how fast the
+                // server is.
+                if let types::ServerEvent::ResponseDone(done) = &event
+                    && let Some(started) = self.response_started.remove(done.response().id())
+                    && done.response().status() == "completed"
+                {
+                    lock(&self.stats).record_latency(started.elapsed());
+                }
+                // If the server is done responding, record its usage stats.
+                if let types::ServerEvent::ResponseDone(response) = &event
+                    && let Some(usage) = response.response().usage()
+                {
+                    let total_tokens = usage.total_tokens();
+                    let input_tokens = usage.input_tokens();
+                    let output_tokens = usage.output_tokens();
+
+                    lock(&self.stats).update_usage(total_tokens, input_tokens, output_tokens);
+
+                    tracing::debug!(
+                        "total_tokens: {}, input_tokens: {}, output_tokens: {}",
+                        total_tokens,
+                        input_tokens,
+                        output_tokens
+                    );
+                }
+                // Remember the session the server reports as active.
+                if let types::ServerEvent::SessionUpdated(updated) = &event {
+                    *lock(&self.session) = Some(updated.session().session());
+                }
+                // Cache the latest rate limits so callers can check them without listening.
+                if let types::ServerEvent::RateLimitsUpdated(updated) = &event {
+                    *lock(&self.rate_limits) = Some(updated.rate_limits().to_vec());
+                }
+                // Send the server event across the transmitting server channel.
+                self.broadcast(event);
+            }
+            // Log an error if we couldn't properly deserialize the server event.
+            Err(e) => match serde_json::from_str::<serde_json::Value>(text) {
+                Ok(json) => {
+                    tracing::error!("failed to deserialize event: {}, type=> {:?}", e, json)
+                }
+                Err(_) => {
+                    tracing::error!("failed to deserialize event: {}, text=> {:?}", e, text)
+                }
+            },
+        }
+    }
+
+    fn broadcast(&self, event: types::ServerEvent) {
+        if let Err(e) = self.s_tx.send(event) {
+            tracing::error!("failed to send event: {}", e);
+        }
+    }
+}
+
+// Sleeps until `deadline`; callers guard the branch so it is never polled with `None`.
+async fn sleep_until_some(deadline: Option<tokio::time::Instant>) {
+    if let Some(deadline) = deadline {
+        tokio::time::sleep_until(deadline).await;
+    }
+}
+
+async fn send_event(ws: &mut WsStream, event: &types::ClientEvent) -> anyhow::Result<()> {
+    let text = serde_json::to_string(event)?;
+    ws.send(Message::Text(text)).await?;
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::ServerRx;
+
+/// Placeholder written in place of redacted base64 audio.
+const REDACTED: &str = "[redacted]";
+
+/// Options controlling how server events are exported.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Replace the base64 audio payload of `response.audio.delta` events with a placeholder.
+    /// Audio makes up most of the stream's volume and is rarely needed for analysis.
+    pub redact_audio: bool,
+}
+
+impl ExportOptions {
+    pub fn with_redact_audio(mut self, redact_audio: bool) -> Self {
+        self.redact_audio = redact_audio;
+        self
+    }
+}
+
+/// Which way a recorded event travelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Direction {
+    /// A `ServerEvent` received from the API.
+    Inbound,
+    /// A `ClientEvent` sent to the API.
+    Outbound,
+}
+
+/// When and which way an event crossed the socket, added to each line of a session recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Recorded {
+    pub(crate) direction: Direction,
+    /// Milliseconds since the Unix epoch when the event was sent or received.
+    pub(crate) timestamp_ms: u64,
+}
+
+/// Serializes an event as a single JSON line (without the trailing newline).
+///
+/// Exports and session recordings share this format: each line is the event's own JSON, which
+/// recordings extend with the `Recorded` fields since they hold events going both ways.
+pub(crate) fn event_to_json_line(
+    event: &impl Serialize,
+    recorded: Option<Recorded>,
+    options: &ExportOptions,
+) -> Result<String> {
+    let mut json = serde_json::to_value(event)?;
+    if options.redact_audio
+        && json["type"] == "response.audio.delta"
+        && let Some(delta) = json.get_mut("delta")
+    {
+        *delta = serde_json::Value::String(REDACTED.to_string());
+    }
+    if let Some(recorded) = recorded
+        && let (Some(line), serde_json::Value::Object(fields)) =
+            (json.as_object_mut(), serde_json::to_value(recorded)?)
+    {
+        line.extend(fields);
+    }
+    Ok(serde_json::to_string(&json)?)
+}
+
+/// Parses a line written by `event_to_json_line`, splitting off the direction a recording
+/// adds. Lines without one are exported server events.
+pub(crate) fn parse_json_line(line: &str) -> Result<(Option<Direction>, serde_json::Value)> {
+    let mut json: serde_json::Value = serde_json::from_str(line)?;
+    let Some(fields) = json.as_object_mut() else {
+        return Ok((None, json));
+    };
+    let direction = fields
+        .remove("direction")
+        .map(serde_json::from_value)
+        .transpose()
+        .context("invalid direction")?;
+    fields.remove("timestamp_ms");
+    Ok((direction, json))
+}
+
+/// Writes one event to `writer` as a JSON line.
+pub(crate) async fn write_event_line<W>(
+    writer: &mut W,
+    event: &impl Serialize,
+    recorded: Option<Recorded>,
+    options: &ExportOptions,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut line = event_to_json_line(event, recorded, options)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+/// Writes each event received on `rx` to `writer` as one JSON line, until the channel closes.
+pub(crate) async fn write_events_jsonl<W>(
+    mut rx: ServerRx,
+    mut writer: W,
+    options: ExportOptions,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    loop {
+        let event = match rx.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("event export lagged, skipped {} events", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        write_event_line(&mut writer, &event, None, &options).await?;
+    }
+    writer.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types;
+
+    fn event(json: serde_json::Value) -> types::ServerEvent {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn audio_delta() -> types::ServerEvent {
+        event(serde_json::json!({
+            "type": "response.audio.delta",
+            "event_id": "event_2",
+            "response_id": "resp_1",
+            "item_id": "item_1",
+            "output_index": 0,
+            "content_index": 0,
+            "delta": "AAAA",
+        }))
+    }
+
+    async fn export(
+        events: Vec<types::ServerEvent>,
+        options: ExportOptions,
+    ) -> Vec<serde_json::Value> {
+        let (tx, rx) = tokio::sync::broadcast::channel(16);
+        for event in events {
+            tx.send(event).unwrap();
+        }
+        drop(tx);
+
+        let mut out = Vec::new();
+        write_events_jsonl(rx, &mut out, options).await.unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_writes_one_line_per_event() {
+        let events = vec![
+            types::ServerEvent::Close { reason: None },
+            audio_delta(),
+            types::ServerEvent::Close {
+                reason: Some("bye".to_string()),
+            },
+        ];
+        let lines = export(events, ExportOptions::default()).await;
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            serde_json::json!({ "type": "close", "reason": null })
+        );
+        assert_eq!(lines[1]["type"], "response.audio.delta");
+        assert_eq!(lines[1]["delta"], "AAAA");
+        assert_eq!(lines[2]["reason"], "bye");
+    }
+
+    #[tokio::test]
+    async fn test_redacts_audio_deltas() {
+        let options = ExportOptions::default().with_redact_audio(true);
+        let lines = export(vec![audio_delta()], options).await;
+
+        assert_eq!(lines[0]["delta"], REDACTED);
+        assert_eq!(lines[0]["item_id"], "item_1");
+    }
+}
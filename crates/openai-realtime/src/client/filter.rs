@@ -0,0 +1,58 @@
+use crate::client::ServerRx;
+use crate::types::{ServerEvent, ServerEventKind};
+use tokio::sync::broadcast::error::RecvError;
+
+/// A server event receiver that only yields events of the chosen kinds.
+///
+/// It wraps a `ServerRx`, so events still arrive in order and lag is reported the same way.
+pub struct FilteredServerRx {
+    rx: ServerRx,
+    kinds: Vec<ServerEventKind>,
+}
+
+impl FilteredServerRx {
+    pub(crate) fn new(rx: ServerRx, kinds: &[ServerEventKind]) -> Self {
+        Self {
+            rx,
+            kinds: kinds.to_vec(),
+        }
+    }
+
+    /// Receives the next event of one of the chosen kinds, skipping the rest.
+    pub async fn recv(&mut self) -> Result<ServerEvent, RecvError> {
+        loop {
+            let event = self.rx.recv().await?;
+            if self.kinds.contains(&event.kind()) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_skips_other_kinds() {
+        let (tx, rx) = tokio::sync::broadcast::channel(16);
+        let mut filtered =
+            FilteredServerRx::new(rx, &[ServerEventKind::Reconnected, ServerEventKind::Close]);
+
+        tx.send(ServerEvent::Connected).unwrap();
+        tx.send(ServerEvent::Reconnecting { attempt: 1 }).unwrap();
+        tx.send(ServerEvent::Reconnected).unwrap();
+        tx.send(ServerEvent::Close { reason: None }).unwrap();
+        drop(tx);
+
+        assert!(matches!(
+            filtered.recv().await,
+            Ok(ServerEvent::Reconnected)
+        ));
+        assert!(matches!(
+            filtered.recv().await,
+            Ok(ServerEvent::Close { .. })
+        ));
+        assert!(matches!(filtered.recv().await, Err(RecvError::Closed)));
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+/// Controls how the client re-establishes a dropped WebSocket connection.
+///
+/// Delays grow exponentially from `base_delay`, doubling on each attempt, capped at `max_delay`.
+/// Reconnecting is on by default. A reconnected session keeps its config but starts an empty
+/// conversation; see `ServerEvent::Reconnected`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// The maximum number of consecutive reconnect attempts before giving up. Zero disables reconnecting.
+    pub max_retries: u32,
+    /// The delay before the first attempt.
+    pub base_delay: Duration,
+    /// The upper bound on the delay between attempts.
+    pub max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// A policy that never reconnects.
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// The delay before the given attempt, counting from 1.
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_grows_exponentially_and_is_capped() {
+        let policy = ReconnectPolicy::default()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_secs(1));
+
+        assert_eq!(policy.delay_for(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(4), Duration::from_millis(800));
+        assert_eq!(policy.delay_for(5), Duration::from_secs(1));
+        assert_eq!(policy.delay_for(100), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::types;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::BufRead;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+
+use super::export::{self, Direction, ExportOptions, Recorded};
+
+/// How many lines may wait for the disk before new events are left out of the recording.
+const QUEUED_LINES: usize = 1024;
+
+/// An event waiting to be written, with when and which way it crossed the socket.
+type QueuedEvent = (Recorded, serde_json::Value);
+
+/// Appends every event that crosses the socket to a JSON-lines file, in the same format as
+/// `Client::export_events_jsonl` plus each event's direction and timestamp.
+///
+/// Lines are written by a background task, so a slow disk never holds up the socket; if it
+/// falls too far behind, events are left out of the recording rather than queued without
+/// bound. The task flushes whenever it has caught up, so a recording survives the process
+/// crashing mid-session, which is usually when it's wanted. Dropping the recorder rather than
+/// calling `finish` lets the task write what is queued on its own.
+pub(crate) struct Recorder {
+    lines: tokio::sync::mpsc::Sender<QueuedEvent>,
+    writer: tokio::task::JoinHandle<()>,
+    // Events left out since the queue last had room.
+    dropped: usize,
+}
+
+impl Recorder {
+    /// Opens `path` for appending, creating it if needed. Must be called within a Tokio
+    /// runtime, which runs the writing task.
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open recording file {}", path.display()))?;
+        let (lines, queued) = tokio::sync::mpsc::channel(QUEUED_LINES);
+        let writer = tokio::spawn(write_lines(tokio::fs::File::from_std(file), queued));
+        Ok(Self {
+            lines,
+            writer,
+            dropped: 0,
+        })
+    }
+
+    /// Stops taking events and waits for the queued ones to be written.
+    pub(crate) async fn finish(self) {
+        drop(self.lines);
+        if let Err(e) = self.writer.await {
+            tracing::warn!("recording task failed: {}", e);
+        }
+    }
+
+    pub(crate) fn record_inbound(&mut self, event: &types::ServerEvent) {
+        self.record(Direction::Inbound, event);
+    }
+
+    pub(crate) fn record_outbound(&mut self, event: &types::ClientEvent) {
+        self.record(Direction::Outbound, event);
+    }
+
+    // A failed write is logged rather than returned; losing the recording shouldn't end
+    // the session.
+    fn record(&mut self, direction: Direction, event: &impl Serialize) {
+        if let Err(e) = self.write_line(direction, event) {
+            tracing::warn!("failed to record {:?} event: {}", direction, e);
+        }
+    }
+
+    fn write_line(&mut self, direction: Direction, event: &impl Serialize) -> Result<()> {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        let recorded = Recorded {
+            direction,
+            timestamp_ms,
+        };
+        match self
+            .lines
+            .try_send((recorded, serde_json::to_value(event)?))
+        {
+            Ok(()) => {
+                if self.dropped > 0 {
+                    tracing::warn!(
+                        "recording fell behind, left out {} events",
+                        std::mem::take(&mut self.dropped)
+                    );
+                }
+                Ok(())
+            }
+            Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+                self.dropped += 1;
+                Ok(())
+            }
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
+                anyhow::bail!("the recording task has stopped")
+            }
+        }
+    }
+}
+
+/// Writes queued events to `file` until the recorder is dropped.
+async fn write_lines(file: tokio::fs::File, mut queued: tokio::sync::mpsc::Receiver<QueuedEvent>) {
+    let mut file = tokio::io::BufWriter::new(file);
+    let options = ExportOptions::default();
+    while let Some((recorded, event)) = queued.recv().await {
+        let mut written =
+            export::write_event_line(&mut file, &event, Some(recorded), &options).await;
+        // Take whatever else is waiting too, so a burst of events costs one flush.
+        while written.is_ok()
+            && let Ok((recorded, event)) = queued.try_recv()
+        {
+            written = export::write_event_line(&mut file, &event, Some(recorded), &options).await;
+        }
+        if let Err(e) = written.and(file.flush().await.map_err(Into::into)) {
+            tracing::warn!("failed to write recording: {}", e);
+        }
+    }
+}
+
+/// Reads a session recording and sends its inbound events on `tx`, in the order they arrived.
+///
+/// Outbound events are skipped, and events are sent back to back rather than with their
+/// original timing. Returns how many events were sent.
+pub fn replay(
+    path: impl AsRef<Path>,
+    tx: &tokio::sync::broadcast::Sender<types::ServerEvent>,
+) -> Result<usize> {
+    let events = read_server_events(path.as_ref())?;
+    let sent = events.len();
+    for event in events {
+        // Nobody listening isn't an error; the caller may subscribe later or not at all.
+        let _ = tx.send(event);
+    }
+    Ok(sent)
+}
+
+/// Reads the server events from a JSON-lines file, in order.
+///
+/// Accepts both session recordings, keeping only their inbound events, and files written by
+/// `Client::export_events_jsonl`, which hold one bare server event per line.
+pub(crate) fn read_server_events(path: &Path) -> Result<Vec<types::ServerEvent>> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("failed to open recording file {}", path.display()))?;
+    let mut events = Vec::new();
+    for (index, line) in std::io::BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (direction, event) = export::parse_json_line(&line)
+            .with_context(|| format!("invalid JSON on line {}", index + 1))?;
+        if direction.is_some_and(|direction| direction != Direction::Inbound) {
+            continue;
+        }
+        let event = serde_json::from_value(event)
+            .with_context(|| format!("invalid server event on line {}", index + 1))?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.jsonl", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_inbound_events() {
+        let path = temp_path("recorder-roundtrip");
+        let _ = std::fs::remove_file(&path);
+
+        let mut recorder = Recorder::open(&path).unwrap();
+        recorder.record_outbound(&types::ClientEvent::ResponseCreate(
+            types::events::client::ResponseCreateEvent::new(),
+        ));
+        recorder.record_inbound(&types::ServerEvent::Close {
+            reason: Some("bye".to_string()),
+        });
+        recorder.finish().await;
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["direction"], "outbound");
+        assert_eq!(lines[0]["type"], "response.create");
+        assert_eq!(lines[1]["direction"], "inbound");
+        assert!(lines[1]["timestamp_ms"].as_u64().unwrap() > 0);
+
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        assert_eq!(replay(&path, &tx).unwrap(), 1);
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            types::ServerEvent::Close { reason: Some(ref reason) } if reason == "bye"
+        ));
+        assert!(rx.try_recv().is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_leaves_out_events_while_queue_is_full() {
+        let path = temp_path("recorder-full");
+        let _ = std::fs::remove_file(&path);
+
+        // The writing task can't run until the test yields, so nothing drains the queue.
+        let mut recorder = Recorder::open(&path).unwrap();
+        for _ in 0..QUEUED_LINES + 5 {
+            recorder.record_inbound(&types::ServerEvent::Close { reason: None });
+        }
+        assert_eq!(recorder.dropped, 5);
+        recorder.finish().await;
+
+        let recorded = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(recorded.lines().count(), QUEUED_LINES);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reads_exported_events() {
+        let path = temp_path("recorder-export");
+        std::fs::write(&path, "{\"type\":\"close\",\"reason\":null}\n\n").unwrap();
+
+        let events = read_server_events(&path).unwrap();
+        assert!(matches!(
+            events[..],
+            [types::ServerEvent::Close { reason: None }]
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,98 @@
+use crate::types;
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{RealtimeClient, ServerRx, recorder};
+
+/// A stand-in for `Client` that never opens a socket.
+///
+/// It plays back a fixed script of server events and keeps every client event it is asked to
+/// send, so code written against `RealtimeClient` can be tested deterministically and offline.
+#[derive(Debug, Default)]
+pub struct ReplayClient {
+    events: Vec<types::ServerEvent>,
+    sent: Vec<types::ClientEvent>,
+}
+
+impl ReplayClient {
+    /// Creates a client that plays back `events` in order.
+    pub fn from_events(events: Vec<types::ServerEvent>) -> Self {
+        Self {
+            events,
+            sent: Vec::new(),
+        }
+    }
+
+    /// Creates a client that plays back the server events in a JSON-lines file, either a
+    /// session recording or a file written by `Client::export_events_jsonl`.
+    pub fn from_jsonl(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let events = recorder::read_server_events(path.as_ref())?;
+        Ok(Self::from_events(events))
+    }
+
+    /// The client events sent so far, oldest first.
+    pub fn sent_events(&self) -> &[types::ClientEvent] {
+        &self.sent
+    }
+}
+
+#[async_trait]
+impl RealtimeClient for ReplayClient {
+    async fn send_event(&mut self, event: types::ClientEvent) -> Result<()> {
+        self.sent.push(event);
+        Ok(())
+    }
+
+    // Get a receiver that yields the whole script and then reports the channel closed, as if
+    // the server had hung up. Every call starts the script from the beginning.
+    async fn server_events(&mut self) -> Result<ServerRx> {
+        let (tx, rx) = tokio::sync::broadcast::channel(self.events.len().max(1));
+        for event in &self.events {
+            tx.send(event.clone())?;
+        }
+        Ok(rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_plays_script_then_closes() {
+        let mut client = ReplayClient::from_events(vec![
+            types::ServerEvent::Connected,
+            types::ServerEvent::Close { reason: None },
+        ]);
+
+        for _ in 0..2 {
+            let mut rx = client.server_events().await.unwrap();
+            assert!(matches!(
+                rx.recv().await.unwrap(),
+                types::ServerEvent::Connected
+            ));
+            assert!(matches!(
+                rx.recv().await.unwrap(),
+                types::ServerEvent::Close { reason: None }
+            ));
+            assert!(matches!(
+                rx.recv().await,
+                Err(tokio::sync::broadcast::error::RecvError::Closed)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_sent_events() {
+        let mut client = ReplayClient::default();
+        client.create_response().await.unwrap();
+        client.truncate_item("item_1", 0, 500).await.unwrap();
+
+        let sent: Vec<_> = client
+            .sent_events()
+            .iter()
+            .map(|event| serde_json::to_value(event).unwrap()["type"].clone())
+            .collect();
+        assert_eq!(sent, ["response.create", "conversation.item.truncate"]);
+    }
+}
@@ -0,0 +1,12 @@
+/// The state of the WebSocket connection to the Realtime API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No connection has been made, or the connection was lost.
+    Disconnected,
+    /// The WebSocket handshake is in progress.
+    Connecting,
+    /// The WebSocket is open and events are flowing.
+    Connected,
+    /// The connection dropped and the client is trying to re-establish it.
+    Reconnecting,
+}
@@ -1,8 +1,13 @@
+use std::time::Duration;
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Stats {
     total_tokens: i32,
     input_tokens: i32,
     output_tokens: i32,
+    completed_responses: u32,
+    total_latency: Duration,
+    last_latency: Option<Duration>,
 }
 
 impl Stats {
@@ -11,6 +16,9 @@ impl Stats {
             total_tokens: 0,
             input_tokens: 0,
             output_tokens: 0,
+            completed_responses: 0,
+            total_latency: Duration::ZERO,
+            last_latency: None,
         }
     }
 
@@ -19,16 +27,49 @@ impl Stats {
         self.input_tokens += input;
         self.output_tokens += output;
     }
-    
-    fn total_tokens(&self) -> i32 {
+
+    pub(crate) fn record_latency(&mut self, latency: Duration) {
+        self.completed_responses += 1;
+        self.total_latency += latency;
+        self.last_latency = Some(latency);
+    }
+
+    pub fn total_tokens(&self) -> i32 {
         self.total_tokens
     }
-    
-    fn input_tokens(&self) -> i32 {
+
+    pub fn input_tokens(&self) -> i32 {
         self.input_tokens
     }
-    
-    fn output_tokens(&self) -> i32 {
+
+    pub fn output_tokens(&self) -> i32 {
         self.output_tokens
     }
+
+    /// The mean time from `response.created` to `response.done` across completed responses.
+    pub fn average_latency(&self) -> Option<Duration> {
+        (self.completed_responses > 0).then(|| self.total_latency / self.completed_responses)
+    }
+
+    /// The time from `response.created` to `response.done` for the most recent response.
+    pub fn last_latency(&self) -> Option<Duration> {
+        self.last_latency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_average() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.average_latency(), None);
+        assert_eq!(stats.last_latency(), None);
+
+        stats.record_latency(Duration::from_millis(300));
+        stats.record_latency(Duration::from_millis(500));
+        assert_eq!(stats.average_latency(), Some(Duration::from_millis(400)));
+        assert_eq!(stats.last_latency(), Some(Duration::from_millis(500)));
+    }
 }
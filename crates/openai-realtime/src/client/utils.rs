@@ -4,7 +4,7 @@ use tokio_tungstenite::tungstenite::handshake::client::Request;
 use crate::client::config::Config;
 use crate::client::consts::{AUTHORIZATION_HEADER, OPENAI_BETA_HEADER};
 
-pub fn build_request(config: &Config) -> tokio_tungstenite::tungstenite::Result<Request> {
+pub fn build_request(config: &Config) -> anyhow::Result<Request> {
     let mut request = format!("{}/realtime?model={}", config.base_url(), config.model()).into_client_request()?;
     request.headers_mut()
         .insert(
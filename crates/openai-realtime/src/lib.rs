@@ -2,4 +2,8 @@ mod client;
 
 pub use openai_realtime_types as types;
 
-pub use client::{connect, Client, ServerRx};
\ No newline at end of file
+pub use client::{
+    connect, connect_with, connect_with_config, connect_with_key, replay, Client, Config,
+    ConfigBuilder, ConnectionState, ExportOptions, FilteredServerRx, RealtimeClient,
+    ReconnectPolicy, ReplayClient, ServerRx, Stats,
+};
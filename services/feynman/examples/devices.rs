@@ -1,9 +1,13 @@
 use feynman_native_utils as utils;
 
 fn main() {
-    let inputs = utils::device::get_available_inputs();
-    println!("Available inputs: {}", inputs);
+    match utils::device::get_available_inputs() {
+        Ok(inputs) => println!("Available inputs: {}", inputs),
+        Err(e) => eprintln!("Could not list input devices: {:#}", e),
+    }
 
-    let outputs = utils::device::get_available_outputs();
-    println!("Available outputs: {}", outputs);
+    match utils::device::get_available_outputs() {
+        Ok(outputs) => println!("Available outputs: {}", outputs),
+        Err(e) => eprintln!("Could not list output devices: {:#}", e),
+    }
 }
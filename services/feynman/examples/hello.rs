@@ -1,4 +1,5 @@
 use feynman_service::llm_types::{Item, MessageRole};
+use openai_realtime::RealtimeClient;
 
 #[tokio::main]
 async fn main() {
@@ -15,6 +16,12 @@ async fn main() {
         .expect("failed to get server events");
 
     println!("Connected to OpenAI Realtime API");
+    let mut connection_state = client.connection_state();
+    tokio::spawn(async move {
+        while connection_state.changed().await.is_ok() {
+            println!("Connection state: {:?}", *connection_state.borrow());
+        }
+    });
     tokio::spawn(async move {
         while let Ok(e) = server_events.recv().await {
             println!("{:?}", e);
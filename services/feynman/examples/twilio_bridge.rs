@@ -0,0 +1,234 @@
+//! Bridges a Twilio Media Stream to the OpenAI Realtime API.
+//!
+//! Twilio sends the caller's audio as base64 8kHz μ-law in JSON `media` messages, and plays
+//! back whatever `media` messages it receives in the same format. By default this example
+//! transcodes in both directions, μ-law 8kHz to PCM16 24kHz on the way in and back again on
+//! the way out. Pass `--passthrough` to instead ask the API for `g711_ulaw` on both sides and
+//! forward the payloads untouched.
+//!
+//! Point a TwiML `<Stream url="wss://.../">` at this server, e.g. through a tunnel:
+//!
+//! ```sh
+//! cargo run --example twilio_bridge -- [--passthrough]
+//! ```
+use base64::Engine;
+use feynman_native_utils::audio;
+use futures_util::{SinkExt, StreamExt};
+use openai_realtime::RealtimeClient;
+use openai_realtime::types::audio::{AudioFormat, Base64EncodedAudioBytes};
+use openai_realtime::types::events::ServerEvent;
+use rubato::{FastFixedIn, Resampler};
+use std::collections::VecDeque;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::Level;
+
+/// Twilio Media Streams carry 8kHz mono audio.
+const TWILIO_SAMPLE_RATE: f64 = 8000.0;
+/// Twilio sends 20ms frames, 160 samples at 8kHz.
+const TWILIO_CHUNK_SIZE: usize = 160;
+/// The same 20ms at the API's 24kHz.
+const API_CHUNK_SIZE: usize = 480;
+const DEFAULT_ADDR: &str = "0.0.0.0:8080";
+
+const INSTRUCTIONS: &str = "You are a friendly assistant answering a phone call. \
+Keep your replies short; the caller is listening, not reading.";
+
+/// The messages Twilio sends over a Media Stream that this bridge cares about.
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum TwilioEvent {
+    Start {
+        start: StreamStart,
+    },
+    Media {
+        media: MediaPayload,
+    },
+    Stop,
+    /// `connected`, `mark`, `dtmf` and anything newer.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct StreamStart {
+    #[serde(rename = "streamSid")]
+    stream_sid: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MediaPayload {
+    /// Base64 μ-law bytes.
+    payload: String,
+}
+
+/// Resamples a stream of arbitrarily sized batches through a fixed-chunk resampler.
+struct Transcoder {
+    resampler: FastFixedIn<f32>,
+    pending: VecDeque<f32>,
+}
+
+impl Transcoder {
+    fn new(from_rate: f64, to_rate: f64, chunk_size: usize) -> anyhow::Result<Self> {
+        Ok(Self {
+            resampler: audio::create_resampler(from_rate, to_rate, chunk_size)?,
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Resamples every whole chunk available, keeping the remainder for the next call.
+    fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.pending.extend(samples);
+        let mut out = Vec::new();
+        while self.pending.len() >= self.resampler.input_frames_next() {
+            let chunk: Vec<f32> = self
+                .pending
+                .drain(..self.resampler.input_frames_next())
+                .collect();
+            match self.resampler.process(&[chunk.as_slice()], None) {
+                Ok(resampled) => out.extend_from_slice(&resampled[0]),
+                Err(e) => tracing::warn!("failed to resample audio: {}", e),
+            }
+        }
+        out
+    }
+}
+
+/// Turns a Twilio payload into samples at the API's rate, base64 PCM16.
+fn twilio_to_api(payload: &str, upsampler: &mut Transcoder) -> anyhow::Result<String> {
+    let mulaw = base64::engine::general_purpose::STANDARD.decode(payload)?;
+    let pcm16 = audio::decode_mulaw(&mulaw);
+    let resampled = upsampler.process(&audio::convert_i16_to_f32(&pcm16));
+    Ok(audio::encode(&resampled))
+}
+
+/// Turns a base64 PCM16 delta from the API into a Twilio payload, base64 μ-law.
+fn api_to_twilio(delta: &str, downsampler: &mut Transcoder) -> anyhow::Result<String> {
+    let samples = audio::try_decode_f32(delta)?;
+    let resampled = downsampler.process(&samples);
+    let mulaw = audio::encode_mulaw(&audio::convert_f32_to_i16(&resampled));
+    Ok(base64::engine::general_purpose::STANDARD.encode(mulaw))
+}
+
+/// Relays one phone call between Twilio and a fresh realtime session until either side hangs up.
+async fn handle_call(stream: TcpStream, passthrough: bool) -> anyhow::Result<()> {
+    let twilio = tokio_tungstenite::accept_async(stream).await?;
+    let (mut twilio_tx, mut twilio_rx) = twilio.split();
+
+    let mut realtime = openai_realtime::connect().await?;
+    let mut server_events = realtime.server_events().await?;
+    // With passthrough the API speaks μ-law itself; otherwise it gets the PCM16 we transcode.
+    let format = if passthrough {
+        AudioFormat::Mulaw
+    } else {
+        AudioFormat::Pcm16
+    };
+    let session = openai_realtime::types::Session::new()
+        .with_modalities_enable_audio()
+        .with_instructions(INSTRUCTIONS)
+        .with_input_audio_format(format.clone())
+        .with_output_audio_format(format)
+        .build();
+    realtime.update_session(session).await?;
+
+    let mut upsampler = Transcoder::new(
+        TWILIO_SAMPLE_RATE,
+        audio::REALTIME_API_PCM16_SAMPLE_RATE,
+        TWILIO_CHUNK_SIZE,
+    )?;
+    let mut downsampler = Transcoder::new(
+        audio::REALTIME_API_PCM16_SAMPLE_RATE,
+        TWILIO_SAMPLE_RATE,
+        API_CHUNK_SIZE,
+    )?;
+    let mut stream_sid: Option<String> = None;
+
+    loop {
+        tokio::select! {
+            message = twilio_rx.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(e.into()),
+                };
+                match serde_json::from_str::<TwilioEvent>(&text) {
+                    Ok(TwilioEvent::Start { start }) => {
+                        tracing::info!("call started: stream {}", start.stream_sid);
+                        stream_sid = Some(start.stream_sid);
+                    }
+                    Ok(TwilioEvent::Media { media }) => {
+                        let audio = if passthrough {
+                            media.payload
+                        } else {
+                            twilio_to_api(&media.payload, &mut upsampler)?
+                        };
+                        if !audio.is_empty() {
+                            realtime
+                                .append_input_audio_buffer(Base64EncodedAudioBytes::from(audio))
+                                .await?;
+                        }
+                    }
+                    Ok(TwilioEvent::Stop) => {
+                        tracing::info!("call ended");
+                        break;
+                    }
+                    Ok(TwilioEvent::Other) => {}
+                    Err(e) => tracing::warn!("unrecognized Twilio message: {}", e),
+                }
+            }
+            event = server_events.recv() => {
+                let Ok(event) = event else { break };
+                if let ServerEvent::Close { reason } = &event {
+                    tracing::info!("realtime connection closed: {:?}", reason);
+                    break;
+                }
+                // Twilio ignores audio until it has told us which stream to send it on.
+                let Some(sid) = stream_sid.as_deref() else { continue };
+                match event {
+                    ServerEvent::ResponseAudioDelta(data) => {
+                        let payload = if passthrough {
+                            data.delta().to_string()
+                        } else {
+                            api_to_twilio(data.delta(), &mut downsampler)?
+                        };
+                        let message = serde_json::json!({
+                            "event": "media",
+                            "streamSid": sid,
+                            "media": { "payload": payload },
+                        });
+                        twilio_tx.send(Message::Text(message.to_string())).await?;
+                    }
+                    // The caller talked over the assistant; drop whatever Twilio still has queued.
+                    ServerEvent::InputAudioBufferSpeechStarted(_) => {
+                        let message = serde_json::json!({ "event": "clear", "streamSid": sid });
+                        twilio_tx.send(Message::Text(message.to_string())).await?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenvy::dotenv_override().ok();
+    tracing_subscriber::fmt().with_max_level(Level::INFO).init();
+
+    let passthrough = std::env::args().any(|arg| arg == "--passthrough");
+    let addr = std::env::var("TWILIO_BRIDGE_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+    let listener = TcpListener::bind(&addr).await?;
+    tracing::info!("listening for Twilio media streams on {}", addr);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        tracing::info!("incoming call from {}", peer);
+        tokio::spawn(async move {
+            if let Err(e) = handle_call(stream, passthrough).await {
+                tracing::error!("call from {} failed: {}", peer, e);
+            }
+        });
+    }
+}
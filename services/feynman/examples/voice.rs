@@ -2,16 +2,22 @@ use cpal::traits::{DeviceTrait, StreamTrait};
 use cpal::{FrameCount, StreamConfig};
 use feynman_native_utils::audio::REALTIME_API_PCM16_SAMPLE_RATE;
 use feynman_native_utils::{audio, device};
+use openai_realtime::RealtimeClient;
 use openai_realtime::types::audio::Base64EncodedAudioBytes;
-use ringbuf::traits::{Consumer, Producer, Split};
+use ringbuf::traits::{Consumer, Observer, Split};
 use rubato::Resampler;
 use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::Level;
 use tracing_subscriber::fmt::time::ChronoLocal;
 
 const INPUT_CHUNK_SIZE: usize = 1024;
 const OUTPUT_CHUNK_SIZE: usize = 1024;
 const OUTPUT_LATENCY_MS: usize = 1000;
+/// How long Ctrl-C waits for buffered audio to finish playing before exiting.
+const DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
 
 pub enum Input {
     Audio(Vec<f32>),
@@ -19,6 +25,8 @@ pub enum Input {
     Initialized(),
     AISpeaking(),
     AISpeakingDone(),
+    /// Make the AI say the given text.
+    SpeakText(String),
 }
 
 #[tokio::main]
@@ -79,13 +87,7 @@ async fn main() {
     // This callback function processes audio data from the input stream.
     // It converts stereo to mono if necessary and sends the audio data over the channel.
     let input_data_fn = move |data: &[f32], _: &cpal::InputCallbackInfo| {
-        let audio = if input_channel_count > 1 {
-            data.chunks(input_channel_count)
-                .map(|c| c.iter().sum::<f32>() / input_channel_count as f32)
-                .collect::<Vec<f32>>()
-        } else {
-            data.to_vec()
-        };
+        let audio = audio::downmix_to_mono(data, input_channel_count);
         if let Err(e) = audio_input.try_send(Input::Audio(audio)) {
             eprintln!("Failed to send audio data to buffer: {:?}", e);
         }
@@ -142,22 +144,27 @@ async fn main() {
     let audio_out_buffer = audio::shared_buffer(output_sample_rate as usize * OUTPUT_LATENCY_MS);
     // Create a producer and consumer for the audio output buffer.
     let (mut audio_out_tx, mut audio_out_rx) = audio_out_buffer.split();
+    // Lets Ctrl-C finish playing buffered audio instead of cutting the AI off mid-sentence.
+    let drain = audio::PlaybackDrain::new();
+    let output_drain = drain.clone();
 
     let client_ctrl = input_tx.clone();
+    // Decides from the output's energy whether the AI is speaking, tolerating short pauses.
+    let mut speaking_detector = audio::SpeakingDetector::for_stream(
+        output_sample_rate as u32,
+        output_channel_count,
+        audio::DEFAULT_SILENCE_THRESHOLD,
+        audio::DEFAULT_SPEECH_HANGOVER,
+    );
     // This callback function provides audio data to the output stream.
     // It pulls samples from the ring buffer and sends events to indicate if the AI is speaking.
     let output_data_fn = move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
         let mut sample_index = 0;
-        let mut silence = 0;
         // Fill the output buffer with samples from the ring buffer.
         while sample_index < data.len() {
             // Get a single sample value.
             let sample = audio_out_rx.try_pop().unwrap_or(0.0);
 
-            if sample == 0.0 {
-                silence += 1;
-            }
-
             // Left channel (ch:0).
             if sample_index < data.len() {
                 data[sample_index] = sample;
@@ -173,18 +180,18 @@ async fn main() {
             sample_index += output_channel_count.saturating_sub(2);
         }
         // At this point, the `data` buffer is filled.
+        if audio_out_rx.is_empty() {
+            output_drain.mark_drained();
+        }
 
         // Notify the client task when the AI is speaking or has finished.
         let client_ctrl = client_ctrl.clone();
-        if silence == (data.len() / output_channel_count) {
-            if let Err(e) = client_ctrl.try_send(Input::AISpeakingDone()) {
-                eprintln!("Failed to send speaking done event to client: {:?}", e);
-            }
-        } else {
-            // println!("speaking..., silence: {:?}, len: {}", silence, data.len());
+        if speaking_detector.update(data) {
             if let Err(e) = client_ctrl.try_send(Input::AISpeaking()) {
                 eprintln!("Failed to send speaking event to client: {:?}", e);
             }
+        } else if let Err(e) = client_ctrl.try_send(Input::AISpeakingDone()) {
+            eprintln!("Failed to send speaking done event to client: {:?}", e);
         }
     };
     // Build the output stream.
@@ -202,7 +209,8 @@ async fn main() {
 
     // OpenAI Realtime API
     // Connect to the OpenAI Realtime API.
-    let mut realtime_api = openai_realtime::connect()
+    let api_key = std::env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set");
+    let mut realtime_api = openai_realtime::connect_with_key(&api_key)
         .await
         .expect("failed to connect to OpenAI Realtime API");
 
@@ -217,24 +225,51 @@ async fn main() {
     // This channel receives base64 encoded audio from the server events task.
     let (post_tx, mut post_rx) = tokio::sync::mpsc::channel::<Base64EncodedAudioBytes>(100);
 
+    // Counts audio dropped because playback couldn't keep up.
+    let overflow = audio::OverflowCounter::new();
+    let post_drain = drain.clone();
+
     // This task receives audio from the server, decodes, resamples, and pushes it to the output buffer.
     let post_process = tokio::spawn(async move {
+        // Buffers reused across deltas, so steady playback doesn't allocate per chunk.
+        let mut decoded = Vec::new();
+        let mut chunk = Vec::new();
+        let mut corrupt_fragments = 0u64;
         // Receive audio from the server events task.
         while let Some(audio) = post_rx.recv().await {
-            // Decode audio into a vector of floats.
-            let audio_bytes = audio::decode(&audio);
+            // Once shutdown has started, only what is already buffered gets played.
+            if post_drain.is_draining() {
+                continue;
+            }
+            // Decode audio into a vector of floats, skipping fragments that arrive corrupt.
+            if let Err(e) = audio::try_decode_into(&audio, &mut decoded) {
+                corrupt_fragments += 1;
+                eprintln!(
+                    "Skipping corrupt audio fragment ({} so far): {}",
+                    corrupt_fragments, e
+                );
+                continue;
+            }
             // Get the resampler's required chunk size.
             let chunk_size = out_resampler.input_frames_next();
 
             // Send the received audio to the audio buffer for playback.
-            for samples in audio::split_for_chunks(&audio_bytes, chunk_size) {
-                if let Ok(resamples) = out_resampler.process(&[samples.as_slice()], None) {
-                    if let Some(resamples) = resamples.first() {
-                        for resample in resamples {
-                            if let Err(e) = audio_out_tx.try_push(*resample) {
-                                eprintln!("Failed to push samples to buffer: {:?}", e);
-                            }
-                        }
+            for samples in decoded.chunks(chunk_size) {
+                // The last chunk is zero-padded up to the size the resampler expects.
+                chunk.clear();
+                chunk.extend_from_slice(samples);
+                chunk.resize(chunk_size, 0.0);
+                if let Ok(resamples) = out_resampler.process(&[chunk.as_slice()], None)
+                    && let Some(resamples) = resamples.first()
+                {
+                    let pushed = audio::push_samples(&mut audio_out_tx, resamples);
+                    if pushed < resamples.len() {
+                        let total = overflow.record(resamples.len() - pushed);
+                        eprintln!(
+                            "Output buffer full, dropped {} samples ({} total)",
+                            resamples.len() - pushed,
+                            total
+                        );
                     }
                 }
             }
@@ -276,12 +311,18 @@ async fn main() {
                 openai_realtime::types::events::ServerEvent::InputAudioBufferSpeechStopped(data) => {
                     println!("speech stopped: {:?}", data);
                 }
+                // Show the user's words as they are transcribed, for live captions.
+                openai_realtime::types::events::ServerEvent::ConversationItemInputAudioTranscriptionDelta(data) => {
+                    print!("{}", data.delta());
+                    let _ = std::io::stdout().flush();
+                }
                 openai_realtime::types::events::ServerEvent::ConversationItemInputAudioTranscriptionCompleted(data ) => {
+                    println!();
                     println!("Human: {:?}, e:{:?} i:{:?}", data.transcript().trim(), data.event_id(), data.item_id());
                 }
                 // If we receive response audio, send it to the post-processing channel.
                 openai_realtime::types::events::ServerEvent::ResponseAudioDelta(data) => {
-                    if let Err(e) = post_tx.send(data.delta().to_string()).await {
+                    if let Err(e) = post_tx.send(data.delta().into()).await {
                         eprintln!("Failed to send audio data to resampler: {:?}", e);
                     }
                 }
@@ -310,6 +351,32 @@ async fn main() {
         }
     });
 
+    // Each line typed on stdin is spoken by the AI, to try out TTS on demand. `/mute` instead
+    // toggles the mic, which unlike the AI-speaking gate stays off until toggled back.
+    let muted = Arc::new(AtomicBool::new(false));
+    let stdin_muted = muted.clone();
+    let stdin_ctrl = input_tx.clone();
+    tokio::spawn(async move {
+        use tokio::io::AsyncBufReadExt;
+
+        let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let text = line.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if text == "/mute" {
+                let was_muted = stdin_muted.fetch_xor(true, Ordering::Relaxed);
+                println!("microphone {}", if was_muted { "unmuted" } else { "muted" });
+                continue;
+            }
+            if let Err(e) = stdin_ctrl.send(Input::SpeakText(text.to_string())).await {
+                eprintln!("Failed to send text to speak: {:?}", e);
+                break;
+            }
+        }
+    });
+
     // Create a resampler to convert the input device's sample rate to the one required by the API.
     let mut in_resampler = audio::create_resampler(
         input_sample_rate as f64,
@@ -368,18 +435,40 @@ async fn main() {
                     }
                     ai_speaking = false;
                 }
+                Input::SpeakText(text) => {
+                    if !initialized {
+                        println!("not initialized yet, dropping: {:?}", text);
+                        continue;
+                    }
+                    // Put the text in the conversation as a system message, then ask for a
+                    // response so the AI speaks it.
+                    let item = openai_realtime::types::MessageItem::builder()
+                        .with_role(openai_realtime::types::MessageRole::System)
+                        .with_input_text(&text)
+                        .build();
+                    if let Err(e) = realtime_api
+                        .create_conversation_item(openai_realtime::types::Item::Message(item))
+                        .await
+                    {
+                        eprintln!("Failed to create conversation item: {:?}", e);
+                        continue;
+                    }
+                    if let Err(e) = realtime_api.create_response().await {
+                        eprintln!("Failed to create response: {:?}", e);
+                    }
+                }
                 Input::Audio(audio) => {
-                    if initialized && !ai_speaking {
+                    if initialized && !ai_speaking && !muted.load(Ordering::Relaxed) {
                         for sample in audio {
                             buffer.push_back(sample);
                         }
                         let mut resampled: Vec<f32> = vec![];
                         while buffer.len() >= INPUT_CHUNK_SIZE {
                             let audio: Vec<f32> = buffer.drain(..INPUT_CHUNK_SIZE).collect();
-                            if let Ok(resamples) = in_resampler.process(&[audio.as_slice()], None) {
-                                if let Some(resamples) = resamples.first() {
-                                    resampled.extend(resamples.iter().cloned());
-                                }
+                            if let Ok(resamples) = in_resampler.process(&[audio.as_slice()], None)
+                                && let Some(resamples) = resamples.first()
+                            {
+                                resampled.extend(resamples.iter().cloned());
                             }
                         }
                         if resampled.is_empty() {
@@ -397,12 +486,21 @@ async fn main() {
         }
     });
 
-    tokio::select! {
-        _ = post_process => {},
-        _ = server_handle => {},
-        _ = client_handle => {},
+    let interrupted = tokio::select! {
+        _ = post_process => false,
+        _ = server_handle => false,
+        _ = client_handle => false,
         _ = tokio::signal::ctrl_c() => {
             println!("Received Ctrl-C, shutting down...");
+            true
+        }
+    };
+    if interrupted {
+        // Stop taking new audio and give the output stream time to play what is left.
+        drain.start();
+        let give_up_at = tokio::time::Instant::now() + DRAIN_TIMEOUT;
+        while !drain.is_drained() && tokio::time::Instant::now() < give_up_at {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
         }
     }
     println!("Shutting down...");
@@ -5,6 +5,8 @@
 //! shareable struct that can be passed throughout the application.
 
 use std::env;
+use std::str::FromStr;
+use std::time::Duration;
 use tracing::Level;
 
 // --- Application Constants ---
@@ -15,12 +17,52 @@ pub const INPUT_CHUNK_SIZE: usize = 1024;
 pub const OUTPUT_CHUNK_SIZE: usize = 1024;
 /// The latency for the output audio buffer in milliseconds.
 pub const OUTPUT_LATENCY_MS: usize = 1000;
+/// How long the output task waits for playback to free up buffer space before dropping audio.
+pub const OUTPUT_PUSH_TIMEOUT: Duration = Duration::from_millis(200);
+/// How often the output task retries a push while the buffer is full.
+pub const OUTPUT_PUSH_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+/// How long the realtime connection may sit idle before a keepalive ping is sent.
+/// The user can spend a while explaining between turns, and some proxies drop quiet sockets.
+pub const REALTIME_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(20);
+/// How long after a keepalive ping a silent connection is considered dead and reconnected.
+pub const REALTIME_KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long shutdown waits for buffered audio to finish playing, unless `DRAIN_TIMEOUT_MS` is set.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The default model used by the Reviewer AI.
+pub const DEFAULT_CHAT_MODEL: &str = "gpt-4o";
+/// The default model used for the Realtime API WebSocket session.
+pub const DEFAULT_REALTIME_MODEL: &str = "gpt-4o-realtime-preview-2024-10-01";
+/// The default model used to transcribe the user's speech.
+pub const DEFAULT_TRANSCRIPTION_MODEL: &str = "whisper-1";
+/// The default model used by the Reviewer AI when it runs on Gemini.
+pub const DEFAULT_GEMINI_MODEL: &str = "gemini-2.0-flash";
 
 /// Holds all configuration loaded from the environment.
 #[derive(Debug, Clone)]
 pub struct Config {
     pub openai_api_key: String,
+    /// The model used by the Reviewer AI.
     pub chat_model: String,
+    /// The model used for the Realtime API session.
+    pub realtime_model: String,
+    /// The model used to transcribe the user's speech within the realtime session.
+    pub transcription_model: String,
+    /// An OpenAI-compatible API for the Reviewer AI, e.g. Azure OpenAI or a local proxy.
+    /// `None` uses the OpenAI API.
+    pub reviewer_base_url: Option<String>,
+    /// When set, the Reviewer AI runs on Gemini with this key instead of on OpenAI.
+    pub gemini_api_key: Option<String>,
+    /// The Gemini model used by the Reviewer AI.
+    pub gemini_model: String,
+    /// Server VAD activation threshold (0.0 to 1.0). `None` uses the server's default.
+    pub vad_threshold: Option<f32>,
+    /// Audio kept from before speech starts, in milliseconds. `None` uses the server's default.
+    pub vad_prefix_padding_ms: Option<i32>,
+    /// Silence that ends the user's turn, in milliseconds. `None` uses the server's default.
+    pub vad_silence_duration_ms: Option<i32>,
+    /// On Ctrl-C, how long to keep playing audio that is already buffered before exiting.
+    pub drain_timeout: Duration,
     pub log_level: Level,
 }
 
@@ -31,6 +73,20 @@ pub enum ConfigError {
     MissingVar(String),
     #[error("Invalid log level provided for RUST_LOG: {0}")]
     InvalidLogLevel(String),
+    #[error("Invalid value for {0}: {1}")]
+    InvalidVar(String, String),
+}
+
+/// Reads an optional variable, failing if it is set but doesn't parse.
+fn parse_optional_var<T: FromStr>(name: &str) -> Result<Option<T>, ConfigError> {
+    match env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| ConfigError::InvalidVar(name.to_string(), value)),
+        Err(_) => Ok(None),
+    }
 }
 
 impl Config {
@@ -38,6 +94,15 @@ impl Config {
     ///
     // *   `OPENAI_API_KEY`: Your secret key for the OpenAI API.
     // *   `CHAT_MODEL`: (Optional) The model to use for the Reviewer AI. Defaults to "gpt-4o".
+    // *   `REALTIME_MODEL`: (Optional) The model for the Realtime API session. Defaults to "gpt-4o-realtime-preview-2024-10-01".
+    // *   `TRANSCRIPTION_MODEL`: (Optional) The model used to transcribe the user's speech. Defaults to "whisper-1".
+    // *   `REVIEWER_BASE_URL`: (Optional) An OpenAI-compatible base URL for the Reviewer AI. Defaults to the OpenAI API.
+    // *   `GEMINI_API_KEY`: (Optional) Runs the Reviewer AI on Gemini instead of OpenAI.
+    // *   `GEMINI_MODEL`: (Optional) The Gemini model for the Reviewer AI. Defaults to "gemini-2.0-flash".
+    // *   `VAD_THRESHOLD`: (Optional) Server VAD activation threshold, from 0.0 to 1.0. Defaults to the server's choice.
+    // *   `VAD_PREFIX_PADDING_MS`: (Optional) Audio kept from before speech starts, in milliseconds.
+    // *   `VAD_SILENCE_DURATION_MS`: (Optional) Silence that ends the user's turn, in milliseconds.
+    // *   `DRAIN_TIMEOUT_MS`: (Optional) How long shutdown lets buffered audio finish playing. Defaults to 3000. 0 exits immediately.
     // *   `RUST_LOG`: (Optional) The logging level. Defaults to "INFO". Can be "TRACE", "DEBUG", "INFO", "WARN", or "ERROR".
     pub fn from_env() -> Result<Self, ConfigError> {
         // Load .env file. This is useful for local development and is ignored if not present.
@@ -47,7 +112,21 @@ impl Config {
             .map_err(|_| ConfigError::MissingVar("OPENAI_API_KEY".to_string()))?;
 
         // Provide a default for non-critical variables.
-        let chat_model = env::var("CHAT_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
+        let chat_model = env::var("CHAT_MODEL").unwrap_or_else(|_| DEFAULT_CHAT_MODEL.to_string());
+        let realtime_model =
+            env::var("REALTIME_MODEL").unwrap_or_else(|_| DEFAULT_REALTIME_MODEL.to_string());
+        let transcription_model = env::var("TRANSCRIPTION_MODEL")
+            .unwrap_or_else(|_| DEFAULT_TRANSCRIPTION_MODEL.to_string());
+        let reviewer_base_url = env::var("REVIEWER_BASE_URL").ok();
+        let gemini_api_key = env::var("GEMINI_API_KEY").ok();
+        let gemini_model =
+            env::var("GEMINI_MODEL").unwrap_or_else(|_| DEFAULT_GEMINI_MODEL.to_string());
+        let vad_threshold = parse_optional_var("VAD_THRESHOLD")?;
+        let vad_prefix_padding_ms = parse_optional_var("VAD_PREFIX_PADDING_MS")?;
+        let vad_silence_duration_ms = parse_optional_var("VAD_SILENCE_DURATION_MS")?;
+        let drain_timeout = parse_optional_var("DRAIN_TIMEOUT_MS")?
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_DRAIN_TIMEOUT);
 
         // Configure logging level from RUST_LOG, with a sensible default.
         let log_level_str = env::var("RUST_LOG").unwrap_or_else(|_| "INFO".to_string());
@@ -58,7 +137,155 @@ impl Config {
         Ok(Self {
             openai_api_key,
             chat_model,
+            realtime_model,
+            transcription_model,
+            reviewer_base_url,
+            gemini_api_key,
+            gemini_model,
+            vad_threshold,
+            vad_prefix_padding_ms,
+            vad_silence_duration_ms,
+            drain_timeout,
             log_level,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // Environment variables are process-global, so tests that modify them must not run concurrently.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    const VARS: [&str; 12] = [
+        "OPENAI_API_KEY",
+        "CHAT_MODEL",
+        "REALTIME_MODEL",
+        "TRANSCRIPTION_MODEL",
+        "REVIEWER_BASE_URL",
+        "GEMINI_API_KEY",
+        "GEMINI_MODEL",
+        "VAD_THRESHOLD",
+        "VAD_PREFIX_PADDING_MS",
+        "VAD_SILENCE_DURATION_MS",
+        "DRAIN_TIMEOUT_MS",
+        "RUST_LOG",
+    ];
+
+    /// Runs `f` with exactly the given variables set, restoring the previous environment afterwards.
+    fn with_env<T>(vars: &[(&str, &str)], f: impl FnOnce() -> T) -> T {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let saved: Vec<_> = VARS.iter().map(|k| (*k, env::var(k).ok())).collect();
+        // SAFETY: access to the environment is serialized by `ENV_LOCK`.
+        unsafe {
+            for key in VARS {
+                env::remove_var(key);
+            }
+            for (key, value) in vars {
+                env::set_var(key, value);
+            }
+        }
+        let result = f();
+        unsafe {
+            for (key, value) in saved {
+                match value {
+                    Some(value) => env::set_var(key, value),
+                    None => env::remove_var(key),
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_from_env_reads_each_model() {
+        let config = with_env(
+            &[
+                ("OPENAI_API_KEY", "sk-test"),
+                ("CHAT_MODEL", "gpt-4o-mini"),
+                ("REALTIME_MODEL", "gpt-realtime"),
+                ("TRANSCRIPTION_MODEL", "gpt-4o-transcribe"),
+                ("REVIEWER_BASE_URL", "http://localhost:8080/v1"),
+                ("GEMINI_API_KEY", "gm-test"),
+                ("GEMINI_MODEL", "gemini-2.5-pro"),
+                ("RUST_LOG", "debug"),
+            ],
+            Config::from_env,
+        )
+        .unwrap();
+
+        assert_eq!(config.openai_api_key, "sk-test");
+        assert_eq!(config.chat_model, "gpt-4o-mini");
+        assert_eq!(config.realtime_model, "gpt-realtime");
+        assert_eq!(config.transcription_model, "gpt-4o-transcribe");
+        assert_eq!(
+            config.reviewer_base_url.as_deref(),
+            Some("http://localhost:8080/v1")
+        );
+        assert_eq!(config.log_level, Level::DEBUG);
+    }
+
+    #[test]
+    fn test_from_env_defaults_when_unset() {
+        let config = with_env(&[("OPENAI_API_KEY", "sk-test")], Config::from_env).unwrap();
+
+        assert_eq!(config.chat_model, DEFAULT_CHAT_MODEL);
+        assert_eq!(config.realtime_model, DEFAULT_REALTIME_MODEL);
+        assert_eq!(config.transcription_model, DEFAULT_TRANSCRIPTION_MODEL);
+        assert_eq!(config.reviewer_base_url, None);
+        assert_eq!(config.gemini_api_key, None);
+        assert_eq!(config.gemini_model, DEFAULT_GEMINI_MODEL);
+        assert_eq!(config.vad_threshold, None);
+        assert_eq!(config.vad_silence_duration_ms, None);
+        assert_eq!(config.drain_timeout, DEFAULT_DRAIN_TIMEOUT);
+        assert_eq!(config.log_level, Level::INFO);
+    }
+
+    #[test]
+    fn test_from_env_reads_vad_parameters() {
+        let config = with_env(
+            &[
+                ("OPENAI_API_KEY", "sk-test"),
+                ("VAD_THRESHOLD", "0.7"),
+                ("VAD_PREFIX_PADDING_MS", "250"),
+                ("VAD_SILENCE_DURATION_MS", " 900 "),
+            ],
+            Config::from_env,
+        )
+        .unwrap();
+        assert_eq!(config.vad_threshold, Some(0.7));
+        assert_eq!(config.vad_prefix_padding_ms, Some(250));
+        assert_eq!(config.vad_silence_duration_ms, Some(900));
+
+        let result = with_env(
+            &[
+                ("OPENAI_API_KEY", "sk-test"),
+                ("VAD_SILENCE_DURATION_MS", "soon"),
+            ],
+            Config::from_env,
+        );
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidVar(var, value))
+                if var == "VAD_SILENCE_DURATION_MS" && value == "soon"
+        ));
+    }
+
+    #[test]
+    fn test_from_env_reads_drain_timeout() {
+        let config = with_env(
+            &[("OPENAI_API_KEY", "sk-test"), ("DRAIN_TIMEOUT_MS", "500")],
+            Config::from_env,
+        )
+        .unwrap();
+        assert_eq!(config.drain_timeout, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_from_env_requires_api_key() {
+        let result = with_env(&[], Config::from_env);
+        assert!(matches!(result, Err(ConfigError::MissingVar(var)) if var == "OPENAI_API_KEY"));
+    }
+}
@@ -0,0 +1,126 @@
+//! Question Delivery Tracking
+//!
+//! The session only starts listening for an answer once the AI has finished speaking the
+//! question. The end of any other speech, e.g. a reply that was still playing when the
+//! question was asked, must not count. So the question's ID is followed from the response
+//! requested to speak it, to the audio item that response plays, to the moment that item
+//! goes quiet.
+
+use std::sync::Mutex;
+
+/// A response or audio item, and the question it is speaking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Speaking {
+    id: String,
+    question_id: u64,
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    /// A question whose response the server hasn't created yet.
+    requested: Option<u64>,
+    /// The response speaking the question.
+    response: Option<Speaking>,
+    /// The audio item speaking the question.
+    item: Option<Speaking>,
+}
+
+/// Tracks which response and audio item are speaking the current question.
+///
+/// Shared between the task asking for responses and the one receiving server events. Only the
+/// latest question is tracked, since the session asks one at a time.
+#[derive(Debug, Default)]
+pub struct DeliveryTracker {
+    pending: Mutex<Pending>,
+}
+
+impl DeliveryTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that a response was requested to speak the question with this ID. The next
+    /// response the server creates is taken to be that one.
+    pub fn question_requested(&self, question_id: u64) {
+        *self.pending() = Pending {
+            requested: Some(question_id),
+            ..Pending::default()
+        };
+    }
+
+    /// Records that the server created a response, claiming it for the requested question.
+    pub fn response_created(&self, response_id: &str) {
+        let mut pending = self.pending();
+        if let Some(question_id) = pending.requested.take() {
+            pending.response = Some(Speaking {
+                id: response_id.to_string(),
+                question_id,
+            });
+        }
+    }
+
+    /// Records that a response sent audio for an item.
+    pub fn audio_received(&self, response_id: &str, item_id: &str) {
+        let mut pending = self.pending();
+        if let Some(response) = &pending.response
+            && response.id == response_id
+        {
+            pending.item = Some(Speaking {
+                id: item_id.to_string(),
+                question_id: response.question_id,
+            });
+        }
+    }
+
+    /// Records that an item finished playing. Returns the ID of the question it spoke, if it
+    /// spoke one.
+    pub fn playback_finished(&self, item_id: &str) -> Option<u64> {
+        let mut pending = self.pending();
+        if pending.item.as_ref()?.id != item_id {
+            return None;
+        }
+        let item = pending.item.take()?;
+        pending.response = None;
+        Some(item.question_id)
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, Pending> {
+        // Plain values; a panicking holder can't leave them half-updated.
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_the_question_item_confirms_delivery() {
+        let tracker = DeliveryTracker::new();
+
+        // A reply that was already playing when the question was asked.
+        tracker.question_requested(7);
+        tracker.audio_received("resp_1", "item_1");
+        assert_eq!(tracker.playback_finished("item_1"), None);
+
+        tracker.response_created("resp_2");
+        tracker.audio_received("resp_2", "item_2");
+        assert_eq!(tracker.playback_finished("item_2"), Some(7));
+        // Each question is confirmed once.
+        assert_eq!(tracker.playback_finished("item_2"), None);
+    }
+
+    #[test]
+    fn test_new_question_replaces_unconfirmed_one() {
+        let tracker = DeliveryTracker::new();
+        tracker.question_requested(1);
+        tracker.response_created("resp_1");
+        tracker.audio_received("resp_1", "item_1");
+
+        tracker.question_requested(2);
+        assert_eq!(tracker.playback_finished("item_1"), None);
+        tracker.response_created("resp_2");
+        tracker.audio_received("resp_2", "item_2");
+        assert_eq!(tracker.playback_finished("item_2"), Some(2));
+    }
+}
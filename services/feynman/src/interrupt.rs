@@ -0,0 +1,163 @@
+//! Barge-in Tracking
+//!
+//! When the user talks over the AI, the assistant item being played is truncated to what
+//! they actually heard. The rest of that item's audio may still be on its way from the
+//! server, and must not be played. A cut-off response never finishes its item, so the
+//! interrupted item IDs are kept in a `CorrelationMap` that forgets them after a while.
+
+use feynman_core::correlation::CorrelationMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How long the late audio of an interrupted item keeps being dropped.
+const INTERRUPTED_TTL: Duration = Duration::from_secs(60);
+/// The maximum number of interrupted items remembered at once.
+const MAX_INTERRUPTED_ITEMS: usize = 32;
+
+/// An assistant audio item, identified the way the server expects for truncation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioItem {
+    pub item_id: String,
+    pub content_index: i32,
+}
+
+/// Tracks which assistant item is playing and which ones the user has cut off.
+///
+/// Shared between the task receiving the server's audio and the one handling barge-ins.
+pub struct InterruptTracker {
+    playing: Mutex<Option<AudioItem>>,
+    interrupted: CorrelationMap<String, ()>,
+    /// Set between `response.created` and `response.done`, while there is a response to cancel.
+    generating: AtomicBool,
+}
+
+impl Default for InterruptTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InterruptTracker {
+    pub fn new() -> Self {
+        Self::with_map(CorrelationMap::new(INTERRUPTED_TTL, MAX_INTERRUPTED_ITEMS))
+    }
+
+    /// Creates a tracker backed by the given map, e.g. one with an injected clock.
+    pub fn with_map(interrupted: CorrelationMap<String, ()>) -> Self {
+        Self {
+            playing: Mutex::new(None),
+            interrupted,
+            generating: AtomicBool::new(false),
+        }
+    }
+
+    /// Records that audio arrived for an item, making it the one playing.
+    /// Returns `false` if the item was interrupted, in which case the audio should be dropped.
+    pub fn audio_received(&self, item_id: &str, content_index: i32) -> bool {
+        if self.is_interrupted(item_id) {
+            return false;
+        }
+        let mut playing = self.playing();
+        if playing.as_ref().is_none_or(|item| item.item_id != item_id) {
+            *playing = Some(AudioItem {
+                item_id: item_id.to_string(),
+                content_index,
+            });
+        }
+        true
+    }
+
+    /// The item being played, if any.
+    pub fn playing_item(&self) -> Option<AudioItem> {
+        self.playing().clone()
+    }
+
+    /// Marks the item being played as interrupted and returns it, if there is one.
+    pub fn interrupt(&self) -> Option<AudioItem> {
+        let item = self.playing().take()?;
+        self.interrupted.insert(item.item_id.clone(), ());
+        Some(item)
+    }
+
+    /// Whether the user cut off the item, so the rest of its audio should be dropped.
+    pub fn is_interrupted(&self, item_id: &str) -> bool {
+        self.interrupted.get(&item_id.to_string()).is_some()
+    }
+
+    /// Records that the server started generating a response.
+    pub fn response_started(&self) {
+        self.generating.store(true, Ordering::Release);
+    }
+
+    /// Records that the server finished the response, or cancelled it.
+    pub fn response_done(&self) {
+        self.generating.store(false, Ordering::Release);
+    }
+
+    /// Whether the server is still generating a response, so there is one to cancel.
+    pub fn response_in_progress(&self) -> bool {
+        self.generating.load(Ordering::Acquire)
+    }
+
+    fn playing(&self) -> std::sync::MutexGuard<'_, Option<AudioItem>> {
+        // A plain value; a panicking holder can't leave it half-updated.
+        self.playing.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feynman_core::clock::ManualClock;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_interrupt_takes_playing_item_and_drops_its_late_audio() {
+        let tracker = InterruptTracker::new();
+        assert_eq!(tracker.interrupt(), None);
+
+        assert!(tracker.audio_received("item_1", 0));
+        assert!(tracker.audio_received("item_1", 0));
+        assert_eq!(
+            tracker.interrupt(),
+            Some(AudioItem {
+                item_id: "item_1".to_string(),
+                content_index: 0,
+            })
+        );
+        // Nothing is playing any more, and item_1's remaining audio is dropped.
+        assert_eq!(tracker.interrupt(), None);
+        assert!(!tracker.audio_received("item_1", 0));
+
+        // The next response plays normally.
+        assert!(tracker.audio_received("item_2", 0));
+        assert_eq!(tracker.interrupt().unwrap().item_id, "item_2");
+    }
+
+    #[test]
+    fn test_response_in_progress() {
+        let tracker = InterruptTracker::new();
+        assert!(!tracker.response_in_progress());
+        tracker.response_started();
+        assert!(tracker.response_in_progress());
+        tracker.response_done();
+        assert!(!tracker.response_in_progress());
+    }
+
+    #[test]
+    fn test_interrupted_items_are_forgotten_after_ttl() {
+        let clock = Arc::new(ManualClock::new());
+        let tracker = InterruptTracker::with_map(CorrelationMap::with_clock(
+            INTERRUPTED_TTL,
+            MAX_INTERRUPTED_ITEMS,
+            clock.clone(),
+        ));
+        tracker.audio_received("item_1", 0);
+        tracker.interrupt();
+        assert!(tracker.is_interrupted("item_1"));
+
+        clock.advance(INTERRUPTED_TTL);
+        assert!(!tracker.is_interrupted("item_1"));
+    }
+}
@@ -1,4 +1,8 @@
 pub mod config;
+pub mod delivery;
+pub mod interrupt;
 pub mod prompt_loader;
+pub mod push_to_talk;
+pub mod transcript;
 
 pub use openai_realtime::types as llm_types;
@@ -1,23 +1,36 @@
 mod config;
 mod prompt_loader;
 
-use crate::config::{Config, INPUT_CHUNK_SIZE, OUTPUT_CHUNK_SIZE, OUTPUT_LATENCY_MS};
+use crate::config::{
+    Config, INPUT_CHUNK_SIZE, OUTPUT_CHUNK_SIZE, OUTPUT_LATENCY_MS, OUTPUT_PUSH_RETRY_INTERVAL,
+    OUTPUT_PUSH_TIMEOUT, REALTIME_KEEPALIVE_INTERVAL, REALTIME_KEEPALIVE_TIMEOUT,
+};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use cpal::traits::{DeviceTrait, StreamTrait};
 use cpal::{FrameCount, StreamConfig};
-use feynman_core::reviewer::{Reviewer, ReviewerClient};
-use feynman_core::session_state::FeynmanSession;
+use feynman_core::gemini::GeminiReviewer;
+use feynman_core::prompts::PromptStore;
+use feynman_core::reviewer::{DEFAULT_SUBTOPIC_COUNT, REQUIRED_PROMPTS, Reviewer, ReviewerClient};
+use feynman_core::session_state::{FeynmanSession, format_stats};
 use feynman_core::topic::{SubTopic, SubTopicList, Topic};
 use feynman_native_utils::audio::REALTIME_API_PCM16_SAMPLE_RATE;
+use feynman_service::delivery::DeliveryTracker;
+use feynman_service::interrupt::InterruptTracker;
+use feynman_service::push_to_talk::{Action as PushToTalkAction, RawStdout, RawTerminal};
+use feynman_service::transcript::TranscriptAssembler;
 use openai_realtime::types::audio::Base64EncodedAudioBytes;
-use openai_realtime::types::audio::{ServerVadTurnDetection, TurnDetection};
-use ringbuf::traits::{Consumer, Producer, Split};
+use openai_realtime::types::audio::{
+    SemanticVadTurnDetection, ServerVadTurnDetection, TurnDetection,
+};
+use openai_realtime::types::events::ErrorKind;
+use ringbuf::traits::{Consumer, Observer, Split};
 use rubato::{Resampler};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tracing_subscriber::fmt::time::ChronoLocal;
 
 pub enum Input {
@@ -29,12 +42,83 @@ pub enum Input {
     /// Command to the `client_handle` to create a spoken response from the AI.
     /// This triggers a TTS synthesis and playback flow.
     CreateSpokenResponse(String),
+    /// Like `CreateSpokenResponse`, but for a session question, whose ID is reported back once
+    /// it has been spoken.
+    AskQuestion {
+        question_id: u64,
+        question: String,
+    },
+    /// Ends the user's turn manually: flushes any audio still buffered for the resampler and
+    /// commits the input buffer. Not needed while server VAD decides when turns end.
+    CommitTurn(),
+    /// Server VAD heard the user start speaking.
+    UserSpeechStarted(),
+    /// Has the AI speak the closing message, then shuts down once it has been said.
+    EndSession(String),
+}
+
+/// How far the closing message has got once the session is complete.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Closing {
+    /// The session is still going.
+    No,
+    /// The closing message has been requested but can't be heard yet.
+    Requested,
+    /// The closing message is playing; shut down when the AI goes quiet.
+    Speaking,
 }
 
 #[derive(Parser)]
 struct Cli {
     /// The main topic to teach
-    topic: String,
+    #[arg(required_unless_present = "list_devices")]
+    topic: Option<String>,
+    /// Input device to use, matched case-insensitively against part of its name
+    #[arg(long)]
+    input: Option<String>,
+    /// Output device to use, matched case-insensitively against part of its name
+    #[arg(long)]
+    output: Option<String>,
+    /// Voice the AI speaks with, e.g. alloy, echo, or shimmer
+    #[arg(long, default_value = "alloy")]
+    voice: String,
+    /// Sampling temperature for the AI's responses
+    #[arg(long)]
+    temperature: Option<f32>,
+    /// How the end of the user's turn is detected
+    #[arg(long, value_enum, default_value_t = TurnMode::ServerVad)]
+    turn_mode: TurnMode,
+    /// Reload prompts/*.md whenever a file changes, without restarting
+    #[arg(long)]
+    watch_prompts: bool,
+    /// Print the available input and output devices and exit
+    #[arg(long)]
+    list_devices: bool,
+    /// Multiply the mic signal by this before it is sent, e.g. 2.0 for a quiet mic
+    #[arg(long, default_value_t = 1.0)]
+    gain: f32,
+    /// Boost a quiet mic so its peaks reach this level, from 0.0 to 1.0. Louder input is left
+    /// as is.
+    #[arg(long)]
+    normalize_peak: Option<f32>,
+    /// Keep the mic open while the AI speaks, so talking over it cuts it off. Needs headphones
+    /// or echo cancellation; otherwise the AI hears itself through the speakers and stops
+    /// mid-sentence. By default the mic is held back while the AI speaks.
+    #[arg(long, visible_alias = "barge-in")]
+    full_duplex: bool,
+}
+
+/// Who decides when the user has finished speaking.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum TurnMode {
+    /// The server ends the turn after a stretch of silence.
+    ServerVad,
+    /// The server ends the turn once the user seems to have finished their thought.
+    SemanticVad,
+    /// Turn detection is off; pressing Enter ends the turn.
+    Manual,
+    /// Turn detection is off; the mic is open while Space is held, and letting go ends the turn.
+    PushToTalk,
 }
 
 /// A trait abstracting the `openai_realtime::Client` to allow for mocking in tests.
@@ -44,28 +128,84 @@ pub trait RealtimeApi: Send {
     async fn update_session(&mut self, config: openai_realtime::types::Session) -> Result<()>;
     async fn append_input_audio_buffer(&mut self, audio: Base64EncodedAudioBytes) -> Result<()>;
     async fn create_conversation_item(&mut self, item: openai_realtime::types::Item) -> Result<()>;
+    /// Inserts an item directly after `previous_item_id` rather than at the end.
+    async fn create_conversation_item_after(
+        &mut self,
+        item: openai_realtime::types::Item,
+        previous_item_id: &str,
+    ) -> Result<()>;
     async fn create_response(&mut self) -> Result<()>;
+    /// Commits the buffered user audio as a turn without asking the AI to respond.
+    /// Used for analysis-only turns, where the Feynman session decides separately whether to speak.
+    async fn commit_turn(&mut self) -> Result<()>;
+    /// Discards buffered user audio that hasn't been committed.
+    async fn clear_input_audio_buffer(&mut self) -> Result<()>;
+    /// Stops the AI's in-progress response, e.g. when the user barges in.
+    async fn cancel_response(&mut self) -> Result<()>;
+    async fn delete_conversation_item(&mut self, item_id: &str) -> Result<()>;
+    /// Cuts an assistant audio item off after `audio_end_ms` of audio.
+    async fn truncate_item(
+        &mut self,
+        item_id: &str,
+        content_index: i32,
+        audio_end_ms: i32,
+    ) -> Result<()>;
+    /// Sends a raw client event, for events without a dedicated method.
+    async fn send_event(&mut self, event: openai_realtime::types::ClientEvent) -> Result<()>;
     async fn server_events(&mut self) -> Result<openai_realtime::ServerRx>;
 }
 
-/// Implements the `RealtimeApi` trait for the actual `openai_realtime::Client`.
-/// This implementation simply delegates the calls to the real client.
+/// Implements the `RealtimeApi` trait for any `openai_realtime::RealtimeClient`: the real
+/// `Client`, and the offline `ReplayClient` that exercises the handlers against a scripted
+/// session. This implementation simply delegates the calls to the client.
 #[async_trait]
-impl RealtimeApi for openai_realtime::Client {
+impl<T: openai_realtime::RealtimeClient> RealtimeApi for T {
     async fn update_session(&mut self, config: openai_realtime::types::Session) -> Result<()> {
-        self.update_session(config).await
+        openai_realtime::RealtimeClient::update_session(self, config).await
     }
     async fn append_input_audio_buffer(&mut self, audio: Base64EncodedAudioBytes) -> Result<()> {
-        self.append_input_audio_buffer(audio).await
+        openai_realtime::RealtimeClient::append_input_audio_buffer(self, audio).await
     }
     async fn create_conversation_item(&mut self, item: openai_realtime::types::Item) -> Result<()> {
-        self.create_conversation_item(item).await
+        openai_realtime::RealtimeClient::create_conversation_item(self, item).await
+    }
+    async fn create_conversation_item_after(
+        &mut self,
+        item: openai_realtime::types::Item,
+        previous_item_id: &str,
+    ) -> Result<()> {
+        openai_realtime::RealtimeClient::create_conversation_item_after(self, item, previous_item_id)
+            .await
     }
     async fn create_response(&mut self) -> Result<()> {
-        self.create_response().await
+        openai_realtime::RealtimeClient::create_response(self).await
+    }
+    async fn commit_turn(&mut self) -> Result<()> {
+        self.commit_input_audio_buffer().await
+    }
+    async fn clear_input_audio_buffer(&mut self) -> Result<()> {
+        openai_realtime::RealtimeClient::clear_input_audio_buffer(self).await
+    }
+    async fn cancel_response(&mut self) -> Result<()> {
+        openai_realtime::RealtimeClient::cancel_response(self).await
+    }
+    async fn delete_conversation_item(&mut self, item_id: &str) -> Result<()> {
+        openai_realtime::RealtimeClient::delete_conversation_item(self, item_id).await
+    }
+    async fn truncate_item(
+        &mut self,
+        item_id: &str,
+        content_index: i32,
+        audio_end_ms: i32,
+    ) -> Result<()> {
+        openai_realtime::RealtimeClient::truncate_item(self, item_id, content_index, audio_end_ms)
+            .await
+    }
+    async fn send_event(&mut self, event: openai_realtime::types::ClientEvent) -> Result<()> {
+        openai_realtime::RealtimeClient::send_event(self, event).await
     }
     async fn server_events(&mut self) -> Result<openai_realtime::ServerRx> {
-        self.server_events().await
+        openai_realtime::RealtimeClient::server_events(self).await
     }
 }
 
@@ -77,6 +217,38 @@ struct ClientHandler<T: RealtimeApi, R: Resampler<f32> + Send> {
     initialized: bool,
     buffer: VecDeque<f32>,
     in_resampler: R,
+    /// Notified with a question's ID once the AI has finished speaking it.
+    delivered_tx: Option<tokio::sync::mpsc::Sender<u64>>,
+    /// Which response and audio item are speaking the current question.
+    delivery: Arc<DeliveryTracker>,
+    /// The model used to transcribe the user's speech.
+    transcription_model: openai_realtime::types::audio::TranscriptionModel,
+    /// The realtime session's system prompt, telling the AI to play the student.
+    instructions: String,
+    /// The voice the AI speaks with.
+    voice: openai_realtime::types::audio::Voice,
+    /// The sampling temperature, or the server's default when unset.
+    temperature: Option<f32>,
+    /// How the server decides when the user has finished speaking, or `None` when the
+    /// client commits each turn itself.
+    turn_detection: Option<TurnDetection>,
+    /// Which assistant item is playing, so a barge-in knows what to truncate.
+    interrupts: Arc<InterruptTracker>,
+    /// Empties the playback buffer when the user talks over the AI.
+    playback_flush: feynman_native_utils::audio::PlaybackFlush,
+    /// Holds the mic back while the AI speaks, so it doesn't hear itself without echo
+    /// cancellation. Barge-ins can't happen then. Off only with `--full-duplex`.
+    half_duplex: bool,
+    /// When the AI's audio started playing out of the speakers.
+    speaking_since: Option<std::time::Instant>,
+    /// Set while the user has muted their mic, or isn't holding the push-to-talk key; their
+    /// audio is dropped before it is resampled. Unlike `half_duplex`, which only holds the mic
+    /// back while the AI talks so it doesn't hear itself, this is under the user's control.
+    muted: Arc<AtomicBool>,
+    /// Whether the session is ending, and whether the closing message has started playing.
+    closing: Closing,
+    /// Notified once the closing message has been spoken, to shut down.
+    quit: Arc<tokio::sync::Notify>,
 }
 
 impl<T: RealtimeApi, R: Resampler<f32> + Send> ClientHandler<T, R> {
@@ -85,29 +257,25 @@ impl<T: RealtimeApi, R: Resampler<f32> + Send> ClientHandler<T, R> {
     async fn handle_input(&mut self, i: Input) -> Result<()> {
         match i {
             Input::Initialize() => {
-                let instructions = r#"You are a curious student in a Feynman session.
-                        - You know ONLY what the teacher just said.
-                        - When you receive one or more questions (one per line), read them and ask them out loud, one-by-one, in order.
-                        - Do NOT answer questions or add information. Do NOT speculate or rephrase the teacher's content.
-                        - If a single clarification question is received, just ask that one and stop.
-                        - Keep each spoken question concise and natural."#;
-
-                let turn_detection = TurnDetection::ServerVad(
-                    ServerVadTurnDetection::default()
-                        .with_interrupt_response(true)
-                        .with_create_response(false),
-                );
                 // Once a connection has been established, update the session with custom parameters.
                 tracing::info!("Initializing session with OpenAI...");
-                let session = openai_realtime::types::Session::new()
+                let mut session = openai_realtime::types::Session::new()
                     .with_modalities_enable_audio()
-                    .with_instructions(instructions)
-                    .with_voice(openai_realtime::types::audio::Voice::Alloy)
-                    .with_input_audio_transcription_enable(
-                        openai_realtime::types::audio::TranscriptionModel::Whisper,
-                    )
-                    .with_turn_detection_enable(turn_detection)
-                    .build();
+                    .with_instructions(&self.instructions)
+                    .with_voice(self.voice.clone())
+                    .with_input_audio_transcription_enable(self.transcription_model.clone());
+                session = match &self.turn_detection {
+                    Some(turn_detection) => {
+                        session.with_turn_detection_enable(turn_detection.clone())
+                    }
+                    None => session.with_turn_detection_disable(),
+                };
+                if let Some(temperature) = self.temperature {
+                    session = session
+                        .try_with_temperature(temperature)
+                        .context("Invalid session temperature")?;
+                }
+                let session = session.build();
                 tracing::debug!("Session config: {:?}", serde_json::to_string(&session)?);
                 self.realtime_api
                     .update_session(session)
@@ -121,18 +289,62 @@ impl<T: RealtimeApi, R: Resampler<f32> + Send> ClientHandler<T, R> {
             Input::AISpeaking() => {
                 if !self.ai_speaking {
                     tracing::debug!("AI speaking...");
+                    self.speaking_since = Some(std::time::Instant::now());
+                    // Speech that was already playing when the session ended doesn't count.
+                    if self.closing == Closing::Requested {
+                        self.closing = Closing::Speaking;
+                    }
+                }
+                if self.half_duplex {
+                    self.buffer.clear();
                 }
-                self.buffer.clear();
                 self.ai_speaking = true;
             }
             Input::AISpeakingDone() => {
                 if self.ai_speaking {
                     tracing::debug!("AI speaking done");
+                    // Only the end of the question's own audio means it was heard.
+                    if let Some(tx) = &self.delivered_tx
+                        && let Some(item) = self.interrupts.playing_item()
+                        && let Some(question_id) = self.delivery.playback_finished(&item.item_id)
+                        && let Err(e) = tx.try_send(question_id)
+                    {
+                        tracing::warn!("Failed to send question delivered notification: {:?}", e);
+                    }
+                    if self.closing == Closing::Speaking {
+                        self.quit.notify_one();
+                    }
                 }
                 self.ai_speaking = false;
+                self.speaking_since = None;
+            }
+            Input::UserSpeechStarted() => {
+                // The user talked over the AI. Trim the item to what they actually heard, so
+                // the model doesn't think it said the rest.
+                if self.ai_speaking
+                    && let Some(since) = self.speaking_since
+                    && let Some(item) = self.interrupts.interrupt()
+                {
+                    let audio_end_ms =
+                        i32::try_from(since.elapsed().as_millis()).unwrap_or(i32::MAX);
+                    tracing::debug!("User barged in after {}ms of {}", audio_end_ms, item.item_id);
+                    // Stop talking at once; what is still queued is what the user talked over.
+                    self.playback_flush.request();
+                    if self.interrupts.response_in_progress() {
+                        self.realtime_api
+                            .cancel_response()
+                            .await
+                            .context("Failed to cancel interrupted response")?;
+                    }
+                    self.realtime_api
+                        .truncate_item(&item.item_id, item.content_index, audio_end_ms)
+                        .await
+                        .context("Failed to truncate interrupted assistant audio")?;
+                }
             }
             Input::Audio(audio) => {
-                if self.initialized && !self.ai_speaking {
+                let held_back = self.half_duplex && self.ai_speaking;
+                if self.initialized && !held_back && !self.muted.load(Ordering::Relaxed) {
                     self.buffer.extend(audio);
                     let mut resampled: Vec<f32> = vec![];
                     while self.buffer.len() >= self.in_resampler.input_frames_next() {
@@ -143,76 +355,328 @@ impl<T: RealtimeApi, R: Resampler<f32> + Send> ClientHandler<T, R> {
                         // but simpler to use here. The real `FastFixedIn` also has this method.
                         if let Ok(output_chunk) =
                             self.in_resampler.process(&[audio_chunk.as_slice()], None)
+                            && let Some(channel_data) = output_chunk.first()
                         {
-                            if let Some(channel_data) = output_chunk.first() {
-                                resampled.extend_from_slice(channel_data);
-                            }
+                            resampled.extend_from_slice(channel_data);
                         }
                     }
-                    if !resampled.is_empty() {
-                        let audio_bytes = feynman_native_utils::audio::encode(&resampled);
-                        let audio_bytes = Base64EncodedAudioBytes::from(audio_bytes);
-                        self.realtime_api
-                            .append_input_audio_buffer(audio_bytes.clone())
-                            .await
-                            .context("Failed to send audio buffer")?;
-                    }
+                    self.send_audio(&resampled).await?;
                 }
             }
+            Input::CommitTurn() => {
+                // The resampler only consumes whole chunks, so the last few milliseconds of
+                // speech are still sitting in the buffer. Send them before committing.
+                let tail = feynman_native_utils::audio::flush_resampler(
+                    &mut self.in_resampler,
+                    &mut self.buffer,
+                )?;
+                self.send_audio(&tail).await?;
+                self.realtime_api.commit_turn().await.context("Failed to commit turn")?;
+            }
             // Handles the command to make the AI speak.
-            Input::CreateSpokenResponse(text) => {
-                // This is a two-step process to make the AI speak on demand:
-                // 1. Create a "system" message containing the text we want the AI to say.
-                //    This injects the text into the conversation history.
-                let item = openai_realtime::types::MessageItem::builder()
-                    .with_role(openai_realtime::types::MessageRole::System)
-                    .with_input_text(&text)
-                    .build();
-
-                self.realtime_api
-                    .create_conversation_item(openai_realtime::types::Item::Message(item))
-                    .await
-                    .context("Failed to create conversation item for AI speech")?;
-
-                // 2. Trigger a response. The API will see the last message (the one we just sent)
-                //    and generate audio for it, effectively making the AI speak our provided text.
-                self.realtime_api
-                    .create_response()
-                    .await
-                    .context("Failed to trigger response for AI speech")?;
+            Input::CreateSpokenResponse(text) => self.speak(&text).await?,
+            Input::AskQuestion {
+                question_id,
+                question,
+            } => {
+                self.delivery.question_requested(question_id);
+                self.speak(&question).await?;
+            }
+            Input::EndSession(text) => {
+                self.speak(&text).await?;
+                self.closing = Closing::Requested;
             }
         }
         Ok(())
     }
+
+    /// Makes the AI say `text`.
+    async fn speak(&mut self, text: &str) -> Result<()> {
+        // This is a two-step process to make the AI speak on demand:
+        // 1. Create a "system" message containing the text we want the AI to say.
+        //    This injects the text into the conversation history.
+        let item = openai_realtime::types::MessageItem::builder()
+            .with_role(openai_realtime::types::MessageRole::System)
+            .with_input_text(text)
+            .build();
+
+        self.realtime_api
+            .create_conversation_item(openai_realtime::types::Item::Message(item))
+            .await
+            .context("Failed to create conversation item for AI speech")?;
+
+        // 2. Trigger a response. The API will see the last message (the one we just sent)
+        //    and generate audio for it, effectively making the AI speak our provided text.
+        self.realtime_api
+            .create_response()
+            .await
+            .context("Failed to trigger response for AI speech")
+    }
+
+    /// Encodes resampled audio and appends it to the input buffer, skipping empty batches.
+    async fn send_audio(&mut self, resampled: &[f32]) -> Result<()> {
+        if resampled.is_empty() {
+            return Ok(());
+        }
+        let audio_bytes = feynman_native_utils::audio::encode(resampled);
+        let audio_bytes = Base64EncodedAudioBytes::from(audio_bytes);
+        self.realtime_api
+            .append_input_audio_buffer(audio_bytes)
+            .await
+            .context("Failed to send audio buffer")
+    }
+}
+
+/// Where the server task forwards a server event, besides the Feynman session logic.
+enum Routed {
+    /// An input for the client task.
+    Input(Input),
+    /// Assistant audio to queue for playback.
+    Playback {
+        item_id: String,
+        audio: Base64EncodedAudioBytes,
+    },
+}
+
+/// Decides where a server event goes, keeping `interrupts` up to date with the response
+/// being played and `delivery` with the one speaking a question. Audio for an item the user
+/// has cut off goes nowhere.
+fn route_server_event(
+    event: &openai_realtime::types::ServerEvent,
+    interrupts: &InterruptTracker,
+    delivery: &DeliveryTracker,
+) -> Option<Routed> {
+    use openai_realtime::types::ServerEvent;
+
+    match event {
+        // Once the socket is live, initialize the session. The server applies the session
+        // update after creating the session.
+        ServerEvent::Connected => Some(Routed::Input(Input::Initialize())),
+        ServerEvent::SessionUpdated(_) => Some(Routed::Input(Input::Initialized())),
+        ServerEvent::InputAudioBufferSpeechStarted(_) => {
+            Some(Routed::Input(Input::UserSpeechStarted()))
+        }
+        ServerEvent::ResponseCreated(data) => {
+            interrupts.response_started();
+            delivery.response_created(data.response().id());
+            None
+        }
+        ServerEvent::ResponseDone(_) => {
+            interrupts.response_done();
+            None
+        }
+        ServerEvent::ResponseAudioDelta(data) => {
+            delivery.audio_received(data.response_id(), data.item_id());
+            interrupts
+                .audio_received(data.item_id(), data.content_index())
+                .then(|| Routed::Playback {
+                    item_id: data.item_id().to_string(),
+                    audio: data.delta().into(),
+                })
+        }
+        _ => None,
+    }
+}
+
+/// The student persona used when `prompts/agent_system.md` is missing.
+const DEFAULT_STUDENT_INSTRUCTIONS: &str = concat!(
+    "You are a curious student in a Feynman session.\n",
+    "- You know ONLY what the teacher just said.\n",
+    "- When you receive one or more questions (one per line), read them and ask them out loud, ",
+    "one-by-one, in order.\n",
+    "- Do NOT answer questions or add information. Do NOT speculate or rephrase the teacher's ",
+    "content.\n",
+    "- If a single clarification question is received, just ask that one and stop.\n",
+    "- Keep each spoken question concise and natural.",
+);
+
+/// Renders the `agent_system` prompt for the realtime session, filling in `{main_topic}`.
+/// Falls back to the built-in persona if the prompt wasn't loaded.
+fn student_instructions(prompts: &HashMap<String, String>, main_topic: &str) -> String {
+    match prompts.get("agent_system") {
+        Some(template) => template.replace("{main_topic}", main_topic),
+        None => {
+            tracing::warn!("No agent_system prompt found, using the built-in instructions");
+            DEFAULT_STUDENT_INSTRUCTIONS.to_string()
+        }
+    }
+}
+
+/// Turn detection for `mode`. Either VAD lets the user interrupt the AI but leaves responses to
+/// the session; server VAD is tuned by any VAD parameters in the config. `Manual` turns it off.
+fn turn_detection_from_config(config: &Config, mode: TurnMode) -> Option<TurnDetection> {
+    match mode {
+        TurnMode::Manual | TurnMode::PushToTalk => return None,
+        TurnMode::SemanticVad => {
+            return Some(TurnDetection::SemanticVad(
+                SemanticVadTurnDetection::default()
+                    .with_interrupt_response(true)
+                    .with_create_response(false),
+            ));
+        }
+        TurnMode::ServerVad => {}
+    }
+    let mut server_vad = ServerVadTurnDetection::default()
+        .with_interrupt_response(true)
+        .with_create_response(false);
+    if let Some(threshold) = config.vad_threshold {
+        server_vad = server_vad.with_threshold(threshold);
+    }
+    if let Some(prefix_padding_ms) = config.vad_prefix_padding_ms {
+        server_vad = server_vad.with_prefix_padding_ms(prefix_padding_ms);
+    }
+    if let Some(silence_duration_ms) = config.vad_silence_duration_ms {
+        server_vad = server_vad.with_silence_duration_ms(silence_duration_ms);
+    }
+    Some(TurnDetection::ServerVad(server_vad))
+}
+
+/// Opens the mic while Space is held and closes it, committing the turn, when it's let go.
+///
+/// Returns the guard keeping the terminal in raw mode; dropping it restores the terminal.
+fn spawn_push_to_talk(
+    muted: Arc<AtomicBool>,
+    commit_ctrl: tokio::sync::mpsc::Sender<Input>,
+    quit: Arc<tokio::sync::Notify>,
+) -> Result<RawTerminal> {
+    let (raw_terminal, mut keys) =
+        RawTerminal::enable().context("Failed to put the terminal in raw mode")?;
+    if keys.reports_release() {
+        tracing::info!("Push-to-talk: hold Space while you speak. Esc quits.");
+    } else {
+        // Without release events there's no telling how long the key is held.
+        tracing::info!("Push-to-talk: press Space to start speaking and again to stop. Esc quits.");
+    }
+    std::thread::spawn(move || {
+        while let Ok(event) = crossterm::event::read() {
+            let crossterm::event::Event::Key(key) = event else {
+                continue;
+            };
+            match keys.handle_key(key) {
+                Some(PushToTalkAction::StartTalking) => muted.store(false, Ordering::Relaxed),
+                Some(PushToTalkAction::StopTalking) => {
+                    muted.store(true, Ordering::Relaxed);
+                    if let Err(e) = commit_ctrl.blocking_send(Input::CommitTurn()) {
+                        tracing::warn!("Failed to send commit to client: {:?}", e);
+                        break;
+                    }
+                }
+                Some(PushToTalkAction::Quit) => {
+                    quit.notify_one();
+                    break;
+                }
+                None => {}
+            }
+        }
+    });
+    Ok(raw_terminal)
+}
+
+/// Prints the devices `--input` and `--output` can pick from.
+fn print_devices() -> Result<()> {
+    let inputs = feynman_native_utils::device::get_available_inputs()
+        .context("Failed to list input devices")?;
+    println!("Input devices:\n{}", inputs);
+    let outputs = feynman_native_utils::device::get_available_outputs()
+        .context("Failed to list output devices")?;
+    println!("Output devices:\n{}", outputs);
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // --- 1. Load Configuration ---
+    // --- 1. Parse Command-Line Arguments ---
+    let args = Cli::parse();
+    if args.list_devices {
+        // Listing devices needs no configuration, so it works before the API key is set up.
+        return print_devices();
+    }
+    let main_topic = args.topic.clone().context("No topic given")?;
+    anyhow::ensure!(
+        args.gain.is_finite() && args.gain > 0.0,
+        "--gain must be a positive number"
+    );
+    if let Some(target_peak) = args.normalize_peak {
+        anyhow::ensure!(
+            target_peak > 0.0 && target_peak <= 1.0,
+            "--normalize-peak must be above 0.0 and at most 1.0"
+        );
+    }
+    if let Some(temperature) = args.temperature {
+        use openai_realtime::types::session::{MAX_TEMPERATURE, MIN_TEMPERATURE};
+        anyhow::ensure!(
+            (MIN_TEMPERATURE..=MAX_TEMPERATURE).contains(&temperature),
+            "--temperature must be between {MIN_TEMPERATURE} and {MAX_TEMPERATURE}"
+        );
+    }
+    let push_to_talk = args.turn_mode == TurnMode::PushToTalk;
+
+    // --- 2. Load Configuration ---
     let config = Config::from_env().context("Failed to load application configuration")?;
 
-    // --- 2. Initialize Logging ---
-    tracing_subscriber::fmt()
+    // --- 3. Initialize Logging ---
+    let subscriber = tracing_subscriber::fmt()
         .with_max_level(config.log_level)
-        .with_timer(ChronoLocal::rfc_3339())
-        .init();
+        .with_timer(ChronoLocal::rfc_3339());
+    if push_to_talk {
+        // The terminal will be in raw mode, which needs `\r\n` line endings.
+        subscriber.with_writer(|| RawStdout).init();
+    } else {
+        subscriber.init();
+    }
 
     tracing::info!("Configuration loaded successfully. Starting Feynman service...");
 
-    // --- 3. Parse Command-Line Arguments ---
-    let args = Cli::parse();
-
     // --- 4. Load Prompts ---
     let prompts = prompt_loader::load_prompts(Path::new("prompts"))
         .context("Failed to load LLM prompts")?;
+    prompt_loader::validate_prompts(&prompts, REQUIRED_PROMPTS).map_err(|missing| {
+        anyhow::anyhow!(
+            "Missing or empty prompts in prompts/: {}",
+            missing.join(", ")
+        )
+    })?;
     tracing::info!("Loaded {} prompts successfully.", prompts.len());
+    let instructions = student_instructions(&prompts, &main_topic);
+    // The reviewer reads from this store, so a reload reaches it on the next call. The
+    // realtime session's instructions are fixed once the session starts.
+    let prompts = PromptStore::new(prompts);
+    let _prompt_watcher = if args.watch_prompts {
+        tracing::info!("Watching prompts/ for changes");
+        Some(
+            prompts
+                .watch("prompts")
+                .context("Failed to watch LLM prompts")?,
+        )
+    } else {
+        None
+    };
 
     // --- 5. Initialize API Clients ---
-    let reviewer = Arc::new(ReviewerClient::new(
-        config.openai_api_key.clone(),
-        config.chat_model.clone(),
-        prompts,
-    ));
+    let reviewer: Arc<dyn Reviewer + Send + Sync> = match &config.gemini_api_key {
+        Some(gemini_api_key) => {
+            tracing::info!("Reviewer using Gemini model {}", config.gemini_model);
+            Arc::new(GeminiReviewer::new(
+                gemini_api_key.clone(),
+                config.gemini_model.clone(),
+                prompts.clone(),
+            )?)
+        }
+        None => {
+            let mut reviewer = ReviewerClient::new(
+                config.openai_api_key.clone(),
+                config.chat_model.clone(),
+                prompts.clone(),
+            )?;
+            if let Some(base_url) = &config.reviewer_base_url {
+                reviewer = reviewer
+                    .with_base_url(base_url)
+                    .context("Invalid REVIEWER_BASE_URL")?;
+                tracing::info!("Reviewer using {}", reviewer.completions_url());
+            }
+            Arc::new(reviewer)
+        }
+    };
 
     // --- 6. Application Setup ---
 
@@ -224,8 +688,12 @@ async fn main() -> Result<()> {
     let (command_tx, mut command_rx) = tokio::sync::mpsc::channel::<feynman_core::Command>(32);
 
     // Setup audio input device.
-    let input =
-        feynman_native_utils::device::get_or_default_input(None).context("Failed to get default audio input device")?;
+    let input = match &args.input {
+        Some(query) => feynman_native_utils::device::get_input_by_substring(query)
+            .context("Failed to select audio input device")?,
+        None => feynman_native_utils::device::get_or_default_input(None)
+            .context("Failed to get default audio input device")?,
+    };
 
     // Print out the supported configs for the input device.
     tracing::info!("Using input device: {:?}", &input.name()?);
@@ -255,17 +723,27 @@ async fn main() -> Result<()> {
 
     // Clone the audio input channel transmitter for the input callback.
     let audio_input = input_tx.clone();
+    let input_gain = args.gain;
+    let mut normalizer = args.normalize_peak.map(|target_peak| {
+        feynman_native_utils::audio::PeakNormalizer::new(
+            target_peak,
+            input_config.sample_rate.0,
+            feynman_native_utils::audio::DEFAULT_PEAK_ATTACK,
+            feynman_native_utils::audio::DEFAULT_PEAK_RELEASE,
+        )
+    });
 
     // This callback function processes audio data from the input stream.
-    // It converts stereo to mono if necessary and sends the audio data over the channel.
+    // It converts stereo to mono if necessary, boosts quiet mics, and sends the audio data
+    // over the channel.
     let input_data_fn = move |data: &[f32], _: &cpal::InputCallbackInfo| {
-        let audio = if input_channel_count > 1 {
-            data.chunks(input_channel_count)
-                .map(|c| c.iter().sum::<f32>() / input_channel_count as f32)
-                .collect::<Vec<f32>>()
-        } else {
-            data.to_vec()
-        };
+        let mut audio = feynman_native_utils::audio::downmix_to_mono(data, input_channel_count);
+        if input_gain != 1.0 {
+            feynman_native_utils::audio::apply_gain(&mut audio, input_gain);
+        }
+        if let Some(normalizer) = &mut normalizer {
+            normalizer.process(&mut audio);
+        }
         if let Err(e) = audio_input.try_send(Input::Audio(audio)) {
             tracing::warn!("Failed to send audio data to buffer: {:?}", e);
         }
@@ -284,9 +762,13 @@ async fn main() -> Result<()> {
 
     //------------------------------------------------------------/
 
-    // Get the default output device.
-    let output =
-        feynman_native_utils::device::get_or_default_output(None).context("Failed to get default audio output device")?;
+    // Get the requested output device, or the default one.
+    let output = match &args.output {
+        Some(query) => feynman_native_utils::device::get_output_by_substring(query)
+            .context("Failed to select audio output device")?,
+        None => feynman_native_utils::device::get_or_default_output(None)
+            .context("Failed to get default audio output device")?,
+    };
 
     tracing::info!("Using output device: {:?}", &output.name()?);
     for config in output.supported_output_configs()? {
@@ -312,20 +794,32 @@ async fn main() -> Result<()> {
         feynman_native_utils::audio::shared_buffer(output_sample_rate as usize * OUTPUT_LATENCY_MS);
     // Create a producer and consumer for the audio output buffer. This is used to receive audio from the AI and play it.
     let (mut audio_out_tx, mut audio_out_rx) = audio_out_buffer.split();
+    // Lets Ctrl-C finish playing buffered audio instead of cutting the AI off mid-sentence.
+    let drain = feynman_native_utils::audio::PlaybackDrain::new();
+    let output_drain = drain.clone();
+    // Lets a barge-in cut the AI off instead of playing out what is already buffered.
+    let playback_flush = feynman_native_utils::audio::PlaybackFlush::new();
+    let output_flush = playback_flush.clone();
 
     let client_ctrl = input_tx.clone();
+    // Decides from the output's energy whether the AI is speaking, tolerating short pauses.
+    let mut speaking_detector = feynman_native_utils::audio::SpeakingDetector::for_stream(
+        output_sample_rate as u32,
+        output_channel_count,
+        feynman_native_utils::audio::DEFAULT_SILENCE_THRESHOLD,
+        feynman_native_utils::audio::DEFAULT_SPEECH_HANGOVER,
+    );
     // This callback function provides audio data to the output stream.
     // It pulls samples from the ring buffer and sends events to indicate if the AI is speaking.
     let output_data_fn = move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+        if output_flush.take_request() {
+            audio_out_rx.clear();
+        }
         let mut sample_index = 0;
-        let mut silence = 0;
         // Fill the output buffer with samples from the ring buffer.
         while sample_index < data.len() {
             // Get a single sample value.
             let sample = audio_out_rx.try_pop().unwrap_or(0.0);
-            if sample == 0.0 {
-                silence += 1;
-            }
             // Left channel (ch:0).
             if sample_index < data.len() {
                 data[sample_index] = sample;
@@ -341,16 +835,17 @@ async fn main() -> Result<()> {
         }
 
         // At this point, the `data` buffer is filled.
+        if audio_out_rx.is_empty() {
+            output_drain.mark_drained();
+        }
         // Notify the client task when the AI is speaking or has finished.
         let client_ctrl = client_ctrl.clone();
-        if silence == (data.len() / output_channel_count) {
-            if let Err(e) = client_ctrl.try_send(Input::AISpeakingDone()) {
-                tracing::warn!("Failed to send speaking done event to client: {:?}", e);
-            }
-        } else {
+        if speaking_detector.update(data) {
             if let Err(e) = client_ctrl.try_send(Input::AISpeaking()) {
                 tracing::warn!("Failed to send speaking event to client: {:?}", e);
             }
+        } else if let Err(e) = client_ctrl.try_send(Input::AISpeakingDone()) {
+            tracing::warn!("Failed to send speaking done event to client: {:?}", e);
         }
     };
     // Build the output stream.
@@ -365,16 +860,30 @@ async fn main() -> Result<()> {
 
     // OpenAI Realtime API
     // Connect to the API. The `realtime_api` client is used to send events.
-    let mut realtime_api = openai_realtime::connect()
+    let realtime_config = openai_realtime::Config::builder()
+        .with_api_key(&config.openai_api_key)
+        .with_model(&config.realtime_model)
+        .with_keepalive_interval(REALTIME_KEEPALIVE_INTERVAL)
+        .with_keepalive_timeout(REALTIME_KEEPALIVE_TIMEOUT)
+        .build();
+    let mut realtime_api = openai_realtime::connect_with_config(1024, realtime_config)
         .await
         .context("Failed to connect to OpenAI Realtime API")?;
 
-    let topic = Topic {
-        main_topic: args.topic,
-    };
+    // Log connection state changes so a dropped connection is visible.
+    let mut connection_state = realtime_api.connection_state();
+    tokio::spawn(async move {
+        while connection_state.changed().await.is_ok() {
+            tracing::info!("Realtime connection state: {:?}", *connection_state.borrow());
+        }
+    });
+
+    let topic = Topic { main_topic };
 
     tracing::info!("Generating subtopics for main topic: '{}'", topic.main_topic);
-    let subtopic_names = reviewer.generate_subtopics(&topic.main_topic).await?;
+    let subtopic_names = reviewer
+        .generate_subtopics(&topic.main_topic, DEFAULT_SUBTOPIC_COUNT)
+        .await?;
     let subtopics: Vec<SubTopic> = subtopic_names.into_iter().map(SubTopic::new).collect();
     let subtopic_list = SubTopicList::new(subtopics);
     tracing::debug!("Generated subtopics: {:?}", subtopic_list.subtopics);
@@ -387,26 +896,70 @@ async fn main() -> Result<()> {
     )?;
 
     // This channel receives base64 encoded audio from the server events task.
-    let (post_tx, mut post_rx) = tokio::sync::mpsc::channel::<Base64EncodedAudioBytes>(100);
+    let (post_tx, mut post_rx) =
+        tokio::sync::mpsc::channel::<(String, Base64EncodedAudioBytes)>(100);
+    // Counts audio dropped because playback couldn't keep up.
+    let output_overflow = feynman_native_utils::audio::OverflowCounter::new();
+    let post_overflow = output_overflow.clone();
+    let post_drain = drain.clone();
+    let interrupts = Arc::new(InterruptTracker::new());
+    let post_interrupts = interrupts.clone();
 
     let post_process = tokio::spawn(async move {
         // This task receives audio from the server, decodes, resamples, and pushes it to the output buffer.
-        while let Some(audio) = post_rx.recv().await {
-            // Decode audio into a vector of floats.
-            let audio_bytes = feynman_native_utils::audio::decode(&audio);
+        // Buffers reused across deltas, so steady playback doesn't allocate per chunk.
+        let mut decoded = Vec::new();
+        let mut chunk = Vec::new();
+        let mut corrupt_fragments = 0u64;
+        while let Some((item_id, audio)) = post_rx.recv().await {
+            // Once shutdown has started, only what is already buffered gets played.
+            if post_drain.is_draining() {
+                continue;
+            }
+            // Audio queued before the user cut its item off is dropped too.
+            if post_interrupts.is_interrupted(&item_id) {
+                continue;
+            }
+            // Decode audio into a vector of floats, skipping fragments that arrive corrupt.
+            if let Err(e) = feynman_native_utils::audio::try_decode_into(&audio, &mut decoded) {
+                corrupt_fragments += 1;
+                tracing::warn!(
+                    "Skipping corrupt audio fragment ({} so far): {}",
+                    corrupt_fragments,
+                    e
+                );
+                continue;
+            }
             // Get the resampler's required chunk size.
             let chunk_size = out_resampler.input_frames_next();
 
             // Send the received audio to the audio buffer for playback.
-            for samples in feynman_native_utils::audio::split_for_chunks(&audio_bytes, chunk_size)
-            {
-                if let Ok(resamples) = out_resampler.process(&[samples.as_slice()], None) {
-                    if let Some(resamples) = resamples.first() {
-                        for resample in resamples {
-                            if let Err(e) = audio_out_tx.try_push(*resample) {
-                                tracing::warn!("Failed to push samples to buffer: {:?}", e);
-                            }
-                        }
+            for samples in decoded.chunks(chunk_size) {
+                // The last chunk is zero-padded up to the size the resampler expects.
+                chunk.clear();
+                chunk.extend_from_slice(samples);
+                chunk.resize(chunk_size, 0.0);
+                if let Ok(resamples) = out_resampler.process(&[chunk.as_slice()], None)
+                    && let Some(resamples) = resamples.first()
+                {
+                    let mut pushed =
+                        feynman_native_utils::audio::push_samples(&mut audio_out_tx, resamples);
+                    // Give playback a moment to drain the buffer before dropping anything.
+                    let give_up_at = tokio::time::Instant::now() + OUTPUT_PUSH_TIMEOUT;
+                    while pushed < resamples.len() && tokio::time::Instant::now() < give_up_at {
+                        tokio::time::sleep(OUTPUT_PUSH_RETRY_INTERVAL).await;
+                        pushed += feynman_native_utils::audio::push_samples(
+                            &mut audio_out_tx,
+                            &resamples[pushed..],
+                        );
+                    }
+                    if pushed < resamples.len() {
+                        let total = post_overflow.record(resamples.len() - pushed);
+                        tracing::warn!(
+                            "Output buffer full, dropped {} samples ({} total)",
+                            resamples.len() - pushed,
+                            total
+                        );
                     }
                 }
             }
@@ -421,65 +974,131 @@ async fn main() -> Result<()> {
         .context("Failed to get server events channel")?;
     let reviewer2 = reviewer.clone();
     let command_tx_for_server = command_tx.clone();
+    let server_interrupts = interrupts.clone();
+    let delivery = Arc::new(DeliveryTracker::new());
+    let server_delivery = delivery.clone();
+
+    // Signals the server task when the AI finishes speaking a question, confirming its delivery.
+    let (delivered_tx, mut delivered_rx) = tokio::sync::mpsc::channel::<u64>(8);
 
     let server_handle = tokio::spawn(async move {
         let mut session = FeynmanSession::new(subtopic_list);
+        let mut captions = TranscriptAssembler::new();
 
-        // Receive and process events from the server.
-        while let Ok(e) = server_events.recv().await {
-            // Match on the event type.
-            match e {
-                // When the session is created, send an `Initialize` event to the client task.
-                openai_realtime::types::events::ServerEvent::SessionCreated(data) => {
-                    tracing::info!("Session created: {:?}", data.session());
-                    if let Err(e) = client_ctrl2.try_send(Input::Initialize()) {
-                        tracing::warn!("Failed to send initialized event to client: {:?}", e);
-                    }
-                }
-                // When the session is updated, send an `Initialized` event.
-                openai_realtime::types::events::ServerEvent::SessionUpdated(data) => {
-                    tracing::info!("Session updated: {:?}", data.session());
-                    if let Err(e) = client_ctrl2.try_send(Input::Initialized()) {
-                        tracing::warn!("Failed to send initialized event to client: {:?}", e);
-                    }
+        // Receive and process events from the server, along with playback notifications
+        // from the client task.
+        loop {
+            let answer_deadline = session.answer_deadline();
+            let delivery_deadline = session.delivery_deadline();
+            tokio::select! {
+                Some(question_id) = delivered_rx.recv() => {
+                    // The AI finished speaking the question, so the user has heard it.
+                    session.question_delivered(question_id);
                 }
-                openai_realtime::types::events::ServerEvent::InputAudioBufferSpeechStarted(
-                    data,
-                ) => {
-                    tracing::debug!("User speech started: {:?}", data);
+                // The question was never confirmed as spoken, e.g. its audio failed to play.
+                _ = tokio::time::sleep_until(
+                    delivery_deadline.unwrap_or_else(tokio::time::Instant::now)
+                ), if delivery_deadline.is_some() => {
+                    session.check_delivery_timeout();
                 }
-                openai_realtime::types::events::ServerEvent::InputAudioBufferSpeechStopped(
-                    data,
-                ) => {
-                    tracing::debug!("User speech stopped: {:?}", data);
-                }
-                openai_realtime::types::events::ServerEvent::ConversationItemInputAudioTranscriptionCompleted(data ) => {
-                    let segment = data.transcript().trim().to_owned();
-                    tracing::info!("User said: \"{}\"", segment);
-                    FeynmanSession::process_segment(&mut session, &*reviewer2, segment, command_tx_for_server.clone()).await;
+                // The user hasn't answered the current question in time.
+                _ = tokio::time::sleep_until(
+                    answer_deadline.unwrap_or_else(tokio::time::Instant::now)
+                ), if answer_deadline.is_some() => {
+                    if let Err(e) =
+                        session.check_answer_timeout(command_tx_for_server.clone()).await
+                    {
+                        tracing::error!("Error handling answer timeout: {:?}", e);
+                    }
                 }
+                e = server_events.recv() => {
+                    let Ok(e) = e else { break };
+                    // Surface live captions of the AI's speech as its transcript streams in.
+                    if let Some(caption) = captions.handle_event(&e) {
+                        if caption.is_final {
+                            tracing::info!("AI said: {:?}", caption.text);
+                        } else {
+                            tracing::debug!("Caption [{}]: {}", caption.item_id, caption.text);
+                        }
+                    }
+                    // Pass the event on to the client task or to playback.
+                    match route_server_event(&e, &server_interrupts, &server_delivery) {
+                        Some(Routed::Input(input)) => {
+                            if let Err(e) = client_ctrl2.try_send(input) {
+                                tracing::warn!("Failed to send server event to client: {:?}", e);
+                            }
+                        }
+                        Some(Routed::Playback { item_id, audio }) => {
+                            if let Err(e) = post_tx.send((item_id, audio)).await {
+                                tracing::warn!("Failed to send audio data to resampler: {:?}", e);
+                            }
+                        }
+                        None => {}
+                    }
+                    // Match on the event type.
+                    match e {
+                        openai_realtime::types::events::ServerEvent::Connected => {
+                            tracing::info!("Connected to OpenAI.");
+                        }
+                        openai_realtime::types::events::ServerEvent::SessionCreated(data) => {
+                            tracing::info!("Session created: {:?}", data.session());
+                        }
+                        openai_realtime::types::events::ServerEvent::SessionUpdated(data) => {
+                            tracing::info!("Session updated: {:?}", data.session());
+                        }
+                        openai_realtime::types::events::ServerEvent::InputAudioBufferSpeechStarted(
+                            data,
+                        ) => {
+                            tracing::debug!("User speech started: {:?}", data);
+                        }
+                        openai_realtime::types::events::ServerEvent::InputAudioBufferSpeechStopped(
+                            data,
+                        ) => {
+                            tracing::debug!("User speech stopped: {:?}", data);
+                        }
+                        openai_realtime::types::events::ServerEvent::ConversationItemInputAudioTranscriptionCompleted(data ) => {
+                            let segment = data.transcript().trim().to_owned();
+                            tracing::info!("User said: \"{}\"", segment);
+                            FeynmanSession::process_segment(&mut session, &*reviewer2, segment, command_tx_for_server.clone()).await;
+                        }
                 
-                // If we receive response audio, send it to the post-processing channel.
-                openai_realtime::types::events::ServerEvent::ResponseAudioDelta(data) => {
-           
-                    if let Err(e) = post_tx.send(data.delta().to_string()).await {
-                        tracing::warn!("Failed to send audio data to resampler: {:?}", e);
+                        openai_realtime::types::events::ServerEvent::ResponseCreated(data) => {
+                            tracing::debug!("Response created: {:?}", data.response());
+                        }
+                        openai_realtime::types::events::ServerEvent::ResponseDone(data) => {
+                            tracing::debug!("Response done. Usage: {:?}", data.response().usage());
+                        }
+                        openai_realtime::types::events::ServerEvent::Error(data) => {
+                            let error = data.error();
+                            match error.kind() {
+                                // Transient: the server will keep going, so just note it.
+                                ErrorKind::RateLimited | ErrorKind::ServerInternal => {
+                                    tracing::warn!(
+                                        "Server error ({:?}): {}",
+                                        error.kind(),
+                                        error.message()
+                                    );
+                                }
+                                // The session can't be used any more; stop the run.
+                                ErrorKind::InvalidSession => {
+                                    tracing::error!(
+                                        "Session is no longer valid: {}",
+                                        error.message()
+                                    );
+                                    break;
+                                }
+                                ErrorKind::InvalidRequest | ErrorKind::Unknown => {
+                                    tracing::error!("Server error: {:?}", error);
+                                }
+                            }
+                        }
+                        openai_realtime::types::events::ServerEvent::Close { reason } => {
+                            tracing::info!("Connection closed: {:?}", reason);
+                            break;
+                        }
+                        _ => {}
                     }
                 }
-                openai_realtime::types::events::ServerEvent::ResponseCreated(data) => {
-                    tracing::debug!("Response created: {:?}", data.response());
-                }
-                openai_realtime::types::events::ServerEvent::ResponseAudioTranscriptDone(data) => {
-                    tracing::info!("AI said: {:?}", data.transcript());
-                }
-                openai_realtime::types::events::ServerEvent::ResponseDone(data) => {
-                    tracing::debug!("Response done. Usage: {:?}", data.response().usage());
-                }
-                openai_realtime::types::events::ServerEvent::Close { reason } => {
-                    tracing::info!("Connection closed: {:?}", reason);
-                    break;
-                }
-                _ => {}
             }
         }
     });
@@ -507,15 +1126,103 @@ async fn main() -> Result<()> {
                         tracing::error!("Failed to send CreateSpokenResponse command: {:?}", e);
                     }
                 }
-                feynman_core::Command::SessionComplete(message) => {
+                feynman_core::Command::QuestionAsked {
+                    question_id,
+                    subtopic,
+                    field,
+                    question,
+                } => {
+                    tracing::info!(
+                        "COMMAND RECEIVED: Question on {} ({}): '{}'",
+                        subtopic,
+                        field,
+                        question
+                    );
+                    if let Err(e) = input_tx_for_cmd_handler
+                        .send(Input::AskQuestion {
+                            question_id,
+                            question,
+                        })
+                        .await
+                    {
+                        tracing::error!("Failed to send AskQuestion command: {:?}", e);
+                    }
+                }
+                feynman_core::Command::SessionComplete { message, stats } => {
                     tracing::info!("COMMAND RECEIVED: Session Complete: '{}'", message);
-                    // Here you could break the loop or trigger a shutdown.
+                    tracing::info!("Subtopic stats:\n{}", format_stats(&stats));
+                    // Only the message is spoken. The client task says goodbye, then shuts the runtime down.
+                    if let Err(e) = input_tx_for_cmd_handler
+                        .send(Input::EndSession(message))
+                        .await
+                    {
+                        tracing::error!("Failed to send EndSession command: {:?}", e);
+                    }
+                }
+                feynman_core::Command::SkipQuestion => {
+                    // Nothing to say here: the next question, or the session's own
+                    // acknowledgement, follows as its own command.
+                    tracing::info!("COMMAND RECEIVED: Skip Question");
+                }
+                feynman_core::Command::Progress {
+                    covered,
+                    total,
+                    current_subtopic,
+                } => {
+                    tracing::info!(
+                        "COMMAND RECEIVED: Progress: {}/{} covered, current: {:?}",
+                        covered,
+                        total,
+                        current_subtopic
+                    );
                 }
             }
         }
     });
 
+    let voice: openai_realtime::types::audio::Voice =
+        args.voice.parse().unwrap_or_else(|e| match e {});
+    let drain_timeout = config.drain_timeout;
+    let transcription_model = config
+        .transcription_model
+        .parse::<openai_realtime::types::audio::TranscriptionModel>()?;
+    // With push-to-talk the mic starts closed and only opens while Space is held.
+    let muted = Arc::new(AtomicBool::new(push_to_talk));
+    // Notified when the user quits from the keyboard, which in raw mode replaces Ctrl-C, or
+    // once the closing message of a finished session has been spoken.
+    let quit = Arc::new(tokio::sync::Notify::new());
+    let _raw_terminal = if push_to_talk {
+        Some(spawn_push_to_talk(muted.clone(), input_tx.clone(), quit.clone())?)
+    } else {
+        let manual_turns = args.turn_mode == TurnMode::Manual;
+        if manual_turns {
+            // Without turn detection nothing ends the user's turn, so Enter does it.
+            tracing::info!("Manual turns: press Enter when you finish speaking.");
+        }
+        tracing::info!("Type /mute and press Enter to mute or unmute the microphone.");
+        let stdin_muted = muted.clone();
+        let commit_ctrl = input_tx.clone();
+        tokio::spawn(async move {
+            use tokio::io::AsyncBufReadExt;
+
+            let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.trim() == "/mute" {
+                    let was_muted = stdin_muted.fetch_xor(true, Ordering::Relaxed);
+                    tracing::info!("Microphone {}", if was_muted { "unmuted" } else { "muted" });
+                } else if manual_turns
+                    && let Err(e) = commit_ctrl.send(Input::CommitTurn()).await
+                {
+                    tracing::warn!("Failed to send commit to client: {:?}", e);
+                    break;
+                }
+            }
+        });
+        None
+    };
+
     // This task handles client-side logic: sending user audio and managing state.
+    let client_quit = quit.clone();
     let client_handle = tokio::spawn(async move {
         let mut handler = ClientHandler {
             realtime_api,
@@ -523,6 +1230,20 @@ async fn main() -> Result<()> {
             initialized: false,
             buffer: VecDeque::with_capacity(INPUT_CHUNK_SIZE * 2),
             in_resampler,
+            delivered_tx: Some(delivered_tx),
+            delivery,
+            transcription_model,
+            instructions,
+            voice,
+            temperature: args.temperature,
+            turn_detection: turn_detection_from_config(&config, args.turn_mode),
+            interrupts,
+            playback_flush,
+            half_duplex: !args.full_duplex,
+            speaking_since: None,
+            muted,
+            closing: Closing::No,
+            quit: client_quit,
         };
 
         // Receive and process inputs from the audio callbacks and server event handler.
@@ -534,14 +1255,36 @@ async fn main() -> Result<()> {
         }
     });
 
-    tokio::select! {
-        _ = post_process => {},
-        _ = server_handle => {},
-        _ = client_handle => {},
-        _ = command_handler => {},
+    let interrupted = tokio::select! {
+        _ = post_process => false,
+        _ = server_handle => false,
+        _ = client_handle => false,
+        _ = command_handler => false,
         _ = tokio::signal::ctrl_c() => {
             tracing::info!("Received Ctrl-C, shutting down...");
+            true
+        }
+        _ = quit.notified() => {
+            tracing::info!("Quit requested, shutting down...");
+            true
+        }
+    };
+    if interrupted && !drain_timeout.is_zero() {
+        // Stop taking new audio and give the output stream time to play what is left.
+        drain.start();
+        let give_up_at = tokio::time::Instant::now() + drain_timeout;
+        while !drain.is_drained() && tokio::time::Instant::now() < give_up_at {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
         }
+        if !drain.is_drained() {
+            tracing::warn!("Gave up waiting for buffered audio after {:?}", drain_timeout);
+        }
+    }
+    if output_overflow.dropped() > 0 {
+        tracing::warn!(
+            "Dropped {} output samples because playback fell behind",
+            output_overflow.dropped()
+        );
     }
     tracing::info!("Shutting down...");
     Ok(())
@@ -618,11 +1361,43 @@ mod tests {
             async fn update_session(&mut self, config: openai_realtime::types::Session) -> Result<()>;
             async fn append_input_audio_buffer(&mut self, audio: Base64EncodedAudioBytes) -> Result<()>;
             async fn create_conversation_item(&mut self, item: openai_realtime::types::Item) -> Result<()>;
+            async fn create_conversation_item_after(&mut self, item: openai_realtime::types::Item, previous_item_id: &str) -> Result<()>;
             async fn create_response(&mut self) -> Result<()>;
+            async fn commit_turn(&mut self) -> Result<()>;
+            async fn clear_input_audio_buffer(&mut self) -> Result<()>;
+            async fn cancel_response(&mut self) -> Result<()>;
+            async fn delete_conversation_item(&mut self, item_id: &str) -> Result<()>;
+            async fn truncate_item(&mut self, item_id: &str, content_index: i32, audio_end_ms: i32) -> Result<()>;
+            async fn send_event(&mut self, event: openai_realtime::types::ClientEvent) -> Result<()>;
             async fn server_events(&mut self) -> Result<openai_realtime::ServerRx>;
         }
     }
 
+    /// A handler for an initialized session with the default settings.
+    fn test_handler<T: RealtimeApi>(realtime_api: T) -> ClientHandler<T, DummyResampler> {
+        ClientHandler {
+            realtime_api,
+            ai_speaking: false,
+            initialized: true,
+            buffer: VecDeque::new(),
+            in_resampler: DummyResampler,
+            delivered_tx: None,
+            delivery: Arc::new(DeliveryTracker::new()),
+            transcription_model: openai_realtime::types::audio::TranscriptionModel::Whisper,
+            instructions: DEFAULT_STUDENT_INSTRUCTIONS.to_string(),
+            voice: openai_realtime::types::audio::Voice::Alloy,
+            temperature: None,
+            turn_detection: Some(TurnDetection::default()),
+            interrupts: Arc::new(InterruptTracker::new()),
+            playback_flush: feynman_native_utils::audio::PlaybackFlush::new(),
+            half_duplex: true,
+            speaking_since: None,
+            muted: Arc::new(AtomicBool::new(false)),
+            closing: Closing::No,
+            quit: Arc::new(tokio::sync::Notify::new()),
+        }
+    }
+
     #[tokio::test]
     async fn test_handle_input_create_spoken_response() {
         // --- Arrange ---
@@ -634,14 +1409,12 @@ mod tests {
         mock_api
             .expect_create_conversation_item()
             .withf(move |item| {
-                if let openai_realtime::types::Item::Message(msg) = item {
-                    if msg.role() == openai_realtime::types::MessageRole::System {
-                        if let Some(openai_realtime::types::Content::InputText(content)) =
-                            msg.content().get(0)
-                        {
-                            return content.text() == question_text;
-                        }
-                    }
+                if let openai_realtime::types::Item::Message(msg) = item
+                    && msg.role() == openai_realtime::types::MessageRole::System
+                    && let Some(openai_realtime::types::Content::InputText(content)) =
+                        msg.content().first()
+                {
+                    return content.text() == question_text;
                 }
                 false
             })
@@ -654,13 +1427,7 @@ mod tests {
             .times(1)
             .returning(|| Ok(()));
 
-        let mut handler = ClientHandler {
-            realtime_api: mock_api,
-            ai_speaking: false,
-            initialized: true,
-            buffer: VecDeque::new(),
-            in_resampler: DummyResampler,
-        };
+        let mut handler = test_handler(mock_api);
 
         let input = Input::CreateSpokenResponse("What is the meaning of life?".to_string());
 
@@ -671,4 +1438,331 @@ mod tests {
         assert!(result.is_ok());
         // The mock assertions automatically verify that the expected calls were made.
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_handle_input_notifies_when_question_spoken() {
+        let mut mock_api = MockRealtimeApi::new();
+        mock_api
+            .expect_create_conversation_item()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_api
+            .expect_create_response()
+            .times(1)
+            .returning(|| Ok(()));
+        let (delivered_tx, mut delivered_rx) = tokio::sync::mpsc::channel(8);
+        let mut handler = ClientHandler {
+            delivered_tx: Some(delivered_tx),
+            ..test_handler(mock_api)
+        };
+
+        // Silence without prior speech is not a delivery.
+        handler.handle_input(Input::AISpeakingDone()).await.unwrap();
+        assert!(delivered_rx.try_recv().is_err());
+
+        // An earlier reply finishes playing after the question was asked.
+        handler
+            .handle_input(Input::AskQuestion {
+                question_id: 3,
+                question: "What is a packet?".to_string(),
+            })
+            .await
+            .unwrap();
+        handler.interrupts.audio_received("item_1", 0);
+        handler.handle_input(Input::AISpeaking()).await.unwrap();
+        handler.handle_input(Input::AISpeakingDone()).await.unwrap();
+        assert!(delivered_rx.try_recv().is_err());
+
+        // Then the question's own audio plays out.
+        handler.delivery.response_created("resp_2");
+        handler.delivery.audio_received("resp_2", "item_2");
+        handler.interrupts.audio_received("item_2", 0);
+        handler.handle_input(Input::AISpeaking()).await.unwrap();
+        handler.handle_input(Input::AISpeakingDone()).await.unwrap();
+        assert_eq!(delivered_rx.try_recv().ok(), Some(3));
+
+        // Only the transition from speaking to silent is reported.
+        handler.handle_input(Input::AISpeakingDone()).await.unwrap();
+        assert!(delivered_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_end_session_quits_once_closing_message_is_spoken() {
+        let mut mock_api = MockRealtimeApi::new();
+        mock_api
+            .expect_create_conversation_item()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_api
+            .expect_create_response()
+            .times(1)
+            .returning(|| Ok(()));
+        let mut handler = test_handler(mock_api);
+        let quit = handler.quit.clone();
+        let quit_requested =
+            || tokio::time::timeout(std::time::Duration::from_millis(10), quit.notified());
+
+        // The AI is still finishing a question when the session ends.
+        handler.handle_input(Input::AISpeaking()).await.unwrap();
+        handler
+            .handle_input(Input::EndSession("Well done!".to_string()))
+            .await
+            .unwrap();
+        handler.handle_input(Input::AISpeakingDone()).await.unwrap();
+        assert!(quit_requested().await.is_err());
+
+        // Only the end of the closing message shuts down.
+        handler.handle_input(Input::AISpeaking()).await.unwrap();
+        handler.handle_input(Input::AISpeakingDone()).await.unwrap();
+        assert!(quit_requested().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_commit_turn_flushes_buffered_tail_audio() {
+        let mut mock_api = MockRealtimeApi::new();
+        let mut seq = mockall::Sequence::new();
+        mock_api
+            .expect_append_input_audio_buffer()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| Ok(()));
+        mock_api
+            .expect_commit_turn()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|| Ok(()));
+
+        let mut handler = test_handler(mock_api);
+
+        // Less than one resampler chunk: nothing is sent until the turn is committed.
+        handler.handle_input(Input::Audio(vec![0.1; 100])).await.unwrap();
+        assert_eq!(handler.buffer.len(), 100);
+
+        handler.handle_input(Input::CommitTurn()).await.unwrap();
+        assert!(handler.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_topic_is_only_optional_when_listing_devices() {
+        let args = Cli::try_parse_from(["feynman", "--list-devices"]).unwrap();
+        assert!(args.list_devices);
+        assert!(args.topic.is_none());
+
+        assert!(Cli::try_parse_from(["feynman"]).is_err());
+        let args = Cli::try_parse_from(["feynman", "photosynthesis", "--input", "usb"]).unwrap();
+        assert_eq!(args.topic.as_deref(), Some("photosynthesis"));
+        assert_eq!(args.input.as_deref(), Some("usb"));
+    }
+
+    #[test]
+    fn test_barge_in_is_opt_in() {
+        let args = Cli::try_parse_from(["feynman", "photosynthesis"]).unwrap();
+        assert!(!args.full_duplex);
+        let args = Cli::try_parse_from(["feynman", "photosynthesis", "--barge-in"]).unwrap();
+        assert!(args.full_duplex);
+    }
+
+    #[tokio::test]
+    async fn test_muted_audio_is_dropped() {
+        let muted = Arc::new(AtomicBool::new(true));
+        let mut handler = ClientHandler {
+            muted: muted.clone(),
+            ..test_handler(MockRealtimeApi::new())
+        };
+
+        // Muted audio never reaches the resampler's buffer, let alone the API.
+        handler.handle_input(Input::Audio(vec![0.1; 100])).await.unwrap();
+        assert!(handler.buffer.is_empty());
+
+        muted.store(false, Ordering::Relaxed);
+        handler.handle_input(Input::Audio(vec![0.1; 100])).await.unwrap();
+        assert_eq!(handler.buffer.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_initialize_uses_requested_voice_and_temperature() {
+        let mut mock_api = MockRealtimeApi::new();
+        mock_api
+            .expect_update_session()
+            .withf(|session| {
+                let json = serde_json::to_value(session).unwrap();
+                session.voice() == Some(&openai_realtime::types::audio::Voice::Shimmer)
+                    && json["temperature"].as_f64() == Some(0.9f32 as f64)
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut handler = ClientHandler {
+            initialized: false,
+            voice: "Shimmer".parse().unwrap(),
+            temperature: Some(0.9),
+            ..test_handler(mock_api)
+        };
+
+        handler.handle_input(Input::Initialize()).await.unwrap();
+    }
+
+    #[test]
+    fn test_student_instructions_fill_in_topic() {
+        let prompts = HashMap::from([(
+            "agent_system".to_string(),
+            "You are learning {main_topic}. Ask about {main_topic} only.".to_string(),
+        )]);
+        assert_eq!(
+            student_instructions(&prompts, "photosynthesis"),
+            "You are learning photosynthesis. Ask about photosynthesis only."
+        );
+        assert_eq!(
+            student_instructions(&HashMap::new(), "photosynthesis"),
+            DEFAULT_STUDENT_INSTRUCTIONS
+        );
+    }
+
+    #[test]
+    fn test_default_student_instructions_match_prompt_file() {
+        // Only the first line differs, since the fallback doesn't know the topic.
+        let prompt_file = include_str!("../prompts/agent_system.md");
+        assert!(
+            DEFAULT_STUDENT_INSTRUCTIONS
+                .lines()
+                .skip(1)
+                .eq(prompt_file.trim_end().lines().skip(1))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_manual_turn_mode_sends_null_turn_detection() {
+        let mut mock_api = MockRealtimeApi::new();
+        mock_api
+            .expect_update_session()
+            .withf(|session| {
+                let json = serde_json::to_value(session).unwrap();
+                json.get("turn_detection") == Some(&serde_json::Value::Null)
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut handler = ClientHandler {
+            initialized: false,
+            turn_detection: None,
+            ..test_handler(mock_api)
+        };
+
+        handler.handle_input(Input::Initialize()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_barge_in_truncates_playing_item() {
+        let mut mock_api = MockRealtimeApi::new();
+        mock_api
+            .expect_truncate_item()
+            .withf(|item_id, content_index, audio_end_ms| {
+                item_id == "item_1" && *content_index == 0 && (1500..2500).contains(audio_end_ms)
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let mut handler = test_handler(mock_api);
+
+        // Speech that starts while the AI is silent truncates nothing.
+        handler.handle_input(Input::UserSpeechStarted()).await.unwrap();
+
+        handler.interrupts.audio_received("item_1", 0);
+        handler.handle_input(Input::AISpeaking()).await.unwrap();
+        // Pretend the AI has been audible for 1.5 seconds.
+        handler.speaking_since =
+            Some(std::time::Instant::now() - std::time::Duration::from_millis(1500));
+        handler.handle_input(Input::UserSpeechStarted()).await.unwrap();
+        assert!(handler.interrupts.is_interrupted("item_1"));
+        assert!(handler.playback_flush.take_request());
+    }
+
+    #[tokio::test]
+    async fn test_replayed_barge_in_cancels_and_truncates() {
+        let event = |json: serde_json::Value| -> openai_realtime::types::ServerEvent {
+            serde_json::from_value(json).unwrap()
+        };
+        let audio_delta = event(serde_json::json!({
+            "type": "response.audio.delta",
+            "event_id": "event_3",
+            "response_id": "resp_1",
+            "item_id": "item_1",
+            "output_index": 0,
+            "content_index": 0,
+            "delta": "AAAA",
+        }));
+        let script = vec![
+            openai_realtime::types::ServerEvent::Connected,
+            event(serde_json::json!({
+                "type": "response.created",
+                "event_id": "event_2",
+                "response": {
+                    "id": "resp_1",
+                    "object": "realtime.response",
+                    "status": "in_progress",
+                    "status_details": null,
+                    "output": [],
+                    "usage": null,
+                },
+            })),
+            audio_delta.clone(),
+            event(serde_json::json!({
+                "type": "input_audio_buffer.speech_started",
+                "event_id": "event_4",
+                "audio_start_ms": 0,
+                "item_id": "item_2",
+            })),
+            // Sent before the server saw the cancel; it must not be played.
+            audio_delta,
+        ];
+        let mut handler = ClientHandler {
+            half_duplex: false,
+            ..test_handler(openai_realtime::ReplayClient::from_events(script))
+        };
+        let playback = handler.playback_flush.clone();
+
+        // Route the scripted events exactly as the server task does.
+        let mut played = Vec::new();
+        let mut events = handler.realtime_api.server_events().await.unwrap();
+        while let Ok(event) = events.recv().await {
+            match route_server_event(&event, &handler.interrupts, &handler.delivery) {
+                Some(Routed::Input(input)) => handler.handle_input(input).await.unwrap(),
+                Some(Routed::Playback { item_id, .. }) => {
+                    played.push(item_id);
+                    // The output callback reports the audio as it becomes audible.
+                    handler.handle_input(Input::AISpeaking()).await.unwrap();
+                    // The mic stays open while the AI talks, so the server can hear a barge-in.
+                    handler.handle_input(Input::Audio(vec![0.1; 100])).await.unwrap();
+                    assert_eq!(handler.buffer.len(), 100);
+                }
+                None => {}
+            }
+        }
+
+        assert_eq!(played, ["item_1"]);
+        assert!(playback.take_request());
+        let sent: Vec<_> = handler
+            .realtime_api
+            .sent_events()
+            .iter()
+            .map(|event| serde_json::to_value(event).unwrap())
+            .collect();
+        assert_eq!(sent.len(), 3);
+        assert_eq!(sent[0]["type"], "session.update");
+        assert_eq!(sent[1]["type"], "response.cancel");
+        assert_eq!(sent[2]["type"], "conversation.item.truncate");
+        assert_eq!(sent[2]["item_id"], "item_1");
+    }
+
+    #[tokio::test]
+    async fn test_half_duplex_holds_mic_while_ai_speaks() {
+        let mut handler = test_handler(MockRealtimeApi::new());
+        handler.handle_input(Input::AISpeaking()).await.unwrap();
+        handler.handle_input(Input::Audio(vec![0.1; 100])).await.unwrap();
+        assert!(handler.buffer.is_empty());
+
+        handler.handle_input(Input::AISpeakingDone()).await.unwrap();
+        handler.handle_input(Input::Audio(vec![0.1; 100])).await.unwrap();
+        assert_eq!(handler.buffer.len(), 100);
+    }
+}
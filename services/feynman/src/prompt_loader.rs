@@ -1,32 +1,33 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use std::collections::HashMap;
-use std::fs;
 use std::path::Path;
 
 pub fn load_prompts(dir_path: &Path) -> Result<HashMap<String, String>> {
-    let mut prompts = HashMap::new();
-
-    for entry in fs::read_dir(dir_path)
-        .with_context(|| format!("Failed to read prompts directory: {}", dir_path.display()))?
-    {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
-            let prompt_key = path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .context("Could not get file stem for prompt file")?
-                .to_string();
-
-            let content = fs::read_to_string(&path)
-                .with_context(|| format!("Failed to read prompt file: {}", path.display()))?;
-
-            prompts.insert(prompt_key, content);
-        }
-    }
+    feynman_core::prompts::load_dir(dir_path)
+}
 
-    Ok(prompts)
+/// Checks that every `required` prompt was loaded and isn't blank.
+///
+/// Returns the names of the missing ones, so startup can fail with a useful message rather
+/// than an LLM call failing mid-session.
+pub fn validate_prompts(
+    prompts: &HashMap<String, String>,
+    required: &[&str],
+) -> Result<(), Vec<String>> {
+    let missing: Vec<String> = required
+        .iter()
+        .filter(|name| {
+            prompts
+                .get(**name)
+                .is_none_or(|prompt| prompt.trim().is_empty())
+        })
+        .map(|name| name.to_string())
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing)
+    }
 }
 
 #[cfg(test)]
@@ -72,11 +73,11 @@ mod tests {
         assert_eq!(prompts.get("prompt2").unwrap(), "This is prompt 2.\n");
 
         assert!(
-            prompts.get("config").is_none(),
+            !prompts.contains_key("config"),
             "Should not load .txt files"
         );
         assert!(
-            prompts.get("config.txt").is_none(),
+            !prompts.contains_key("config.txt"),
             "Key should be the file stem, not the full name"
         );
 
@@ -95,6 +96,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_prompts_reports_missing_and_blank() {
+        let prompts = HashMap::from([
+            ("analyze_topic".to_string(), "{segment}".to_string()),
+            ("analyze_answer".to_string(), " \n".to_string()),
+        ]);
+
+        assert_eq!(validate_prompts(&prompts, &["analyze_topic"]), Ok(()));
+        assert_eq!(
+            validate_prompts(
+                &prompts,
+                &["analyze_topic", "generate_subtopics", "analyze_answer"]
+            ),
+            Err(vec![
+                "generate_subtopics".to_string(),
+                "analyze_answer".to_string()
+            ])
+        );
+    }
+
     #[test]
     fn test_load_prompts_from_empty_dir() -> Result<()> {
         // Arrange: Create an empty temporary directory.
@@ -0,0 +1,182 @@
+//! Push-to-Talk
+//!
+//! Open-mic turn detection hears background noise and side conversations. In push-to-talk
+//! mode the mic is only open while Space is held, and letting go ends the turn. The terminal
+//! is put in raw mode so key presses arrive one by one, without waiting for Enter.
+//!
+//! Only terminals that support the kitty keyboard protocol report key releases. In the
+//! others, Space toggles talking on and off instead.
+
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::{execute, terminal};
+use std::io::Write;
+
+/// What a key event asks the runtime to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Open the mic.
+    StartTalking,
+    /// Close the mic and end the turn.
+    StopTalking,
+    /// Shut down. In raw mode Ctrl-C is a key press, not a signal.
+    Quit,
+}
+
+/// Turns key events into push-to-talk actions.
+#[derive(Debug)]
+pub struct PushToTalk {
+    reports_release: bool,
+    talking: bool,
+}
+
+impl PushToTalk {
+    /// `reports_release` says whether the terminal sends an event when a key is let go.
+    pub fn new(reports_release: bool) -> Self {
+        Self {
+            reports_release,
+            talking: false,
+        }
+    }
+
+    /// Whether Space works as hold-to-talk rather than as a toggle.
+    pub fn reports_release(&self) -> bool {
+        self.reports_release
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let ctrl_c =
+            key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+        if key.kind == KeyEventKind::Press && (ctrl_c || key.code == KeyCode::Esc) {
+            return Some(Action::Quit);
+        }
+        if key.code != KeyCode::Char(' ') {
+            return None;
+        }
+        let talking = match key.kind {
+            KeyEventKind::Press if self.reports_release => true,
+            KeyEventKind::Release if self.reports_release => false,
+            KeyEventKind::Press => !self.talking,
+            // Holding the key repeats it; that changes nothing.
+            _ => return None,
+        };
+        if talking == self.talking {
+            return None;
+        }
+        self.talking = talking;
+        Some(if talking {
+            Action::StartTalking
+        } else {
+            Action::StopTalking
+        })
+    }
+}
+
+/// Keeps the terminal in raw mode, restoring it when dropped.
+pub struct RawTerminal {
+    enhanced: bool,
+}
+
+impl RawTerminal {
+    /// Puts the terminal in raw mode, asking it to report key releases if it can, and returns
+    /// a `PushToTalk` set up for what it will report.
+    pub fn enable() -> std::io::Result<(Self, PushToTalk)> {
+        let supports_release = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        // Built first so a failure below still restores the terminal.
+        let mut raw = Self { enhanced: false };
+        terminal::enable_raw_mode()?;
+        if supports_release {
+            execute!(
+                std::io::stdout(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+            raw.enhanced = true;
+        }
+        Ok((raw, PushToTalk::new(supports_release)))
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        if self.enhanced {
+            let _ = execute!(std::io::stdout(), PopKeyboardEnhancementFlags);
+        }
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Stdout for log output while the terminal is raw, where a bare `\n` moves down a line
+/// without returning to its start.
+pub struct RawStdout;
+
+impl Write for RawStdout {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut stdout = std::io::stdout().lock();
+        for line in buf.split_inclusive(|&byte| byte == b'\n') {
+            match line.strip_suffix(b"\n") {
+                Some(text) => {
+                    stdout.write_all(text)?;
+                    stdout.write_all(b"\r\n")?;
+                }
+                None => stdout.write_all(line)?,
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stdout().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn space(kind: KeyEventKind) -> KeyEvent {
+        KeyEvent::new_with_kind(KeyCode::Char(' '), KeyModifiers::NONE, kind)
+    }
+
+    #[test]
+    fn test_hold_to_talk_when_releases_are_reported() {
+        let mut keys = PushToTalk::new(true);
+        assert_eq!(
+            keys.handle_key(space(KeyEventKind::Press)),
+            Some(Action::StartTalking)
+        );
+        assert_eq!(keys.handle_key(space(KeyEventKind::Repeat)), None);
+        assert_eq!(keys.handle_key(space(KeyEventKind::Press)), None);
+        assert_eq!(
+            keys.handle_key(space(KeyEventKind::Release)),
+            Some(Action::StopTalking)
+        );
+        assert_eq!(keys.handle_key(space(KeyEventKind::Release)), None);
+    }
+
+    #[test]
+    fn test_space_toggles_without_release_events() {
+        let mut keys = PushToTalk::new(false);
+        assert_eq!(
+            keys.handle_key(space(KeyEventKind::Press)),
+            Some(Action::StartTalking)
+        );
+        assert_eq!(keys.handle_key(space(KeyEventKind::Release)), None);
+        assert_eq!(
+            keys.handle_key(space(KeyEventKind::Press)),
+            Some(Action::StopTalking)
+        );
+    }
+
+    #[test]
+    fn test_ctrl_c_and_esc_quit() {
+        let mut keys = PushToTalk::new(true);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(keys.handle_key(ctrl_c), Some(Action::Quit));
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(keys.handle_key(esc), Some(Action::Quit));
+        let other = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE);
+        assert_eq!(keys.handle_key(other), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Live Caption Assembly
+//!
+//! The Realtime API streams the transcript of the AI's spoken audio as a series of
+//! `response.audio_transcript.delta` events, followed by a single `done` event carrying
+//! the full text. This module stitches the deltas together per output item so a UI can
+//! render captions while the AI is still speaking.
+
+use feynman_core::correlation::CorrelationMap;
+use openai_realtime::types::ServerEvent;
+use std::time::Duration;
+
+/// How long an unfinished caption is kept before it's dropped, e.g. when a response is
+/// cancelled and its `done` event never arrives.
+const CAPTION_TTL: Duration = Duration::from_secs(120);
+/// The maximum number of captions assembled at once.
+const MAX_IN_PROGRESS_CAPTIONS: usize = 32;
+
+/// A snapshot of the caption for one AI output item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caption {
+    /// The ID of the output item being spoken.
+    pub item_id: String,
+    /// The transcript assembled so far (or the final transcript once `is_final` is set).
+    pub text: String,
+    /// Whether the server has finished transcribing this item.
+    pub is_final: bool,
+}
+
+/// Accumulates audio transcript deltas into captions, keyed by item ID.
+pub struct TranscriptAssembler {
+    in_progress: CorrelationMap<String, String>,
+}
+
+impl Default for TranscriptAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TranscriptAssembler {
+    pub fn new() -> Self {
+        Self::with_map(CorrelationMap::new(CAPTION_TTL, MAX_IN_PROGRESS_CAPTIONS))
+    }
+
+    /// Creates an assembler backed by the given map, e.g. one with an injected clock.
+    pub fn with_map(in_progress: CorrelationMap<String, String>) -> Self {
+        Self { in_progress }
+    }
+
+    /// Feeds a server event into the assembler.
+    /// Returns the updated caption if the event carried audio transcript text.
+    pub fn handle_event(&mut self, event: &ServerEvent) -> Option<Caption> {
+        match event {
+            ServerEvent::ResponseAudioTranscriptDelta(data) => {
+                let text = self.push_delta(data.item_id(), data.delta());
+                Some(Caption {
+                    item_id: data.item_id().to_string(),
+                    text,
+                    is_final: false,
+                })
+            }
+            ServerEvent::ResponseAudioTranscriptDone(data) => {
+                let text = self.finish(data.item_id(), data.transcript());
+                Some(Caption {
+                    item_id: data.item_id().to_string(),
+                    text,
+                    is_final: true,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Appends a transcript delta to the caption for `item_id` and returns the text so far.
+    pub fn push_delta(&mut self, item_id: &str, delta: &str) -> String {
+        self.in_progress
+            .upsert(item_id.to_string(), |text| text.push_str(delta))
+    }
+
+    /// Completes the caption for `item_id`, returning the final transcript.
+    /// The server's transcript is authoritative; the accumulated deltas are discarded.
+    pub fn finish(&mut self, item_id: &str, transcript: &str) -> String {
+        self.in_progress.remove(&item_id.to_string());
+        transcript.to_string()
+    }
+
+    /// The caption assembled so far for an item that is still being spoken.
+    pub fn caption(&self, item_id: &str) -> Option<String> {
+        self.in_progress.get(&item_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feynman_core::clock::ManualClock;
+    use std::sync::Arc;
+
+    fn delta_event(item_id: &str, delta: &str) -> ServerEvent {
+        serde_json::from_value(serde_json::json!({
+            "type": "response.audio_transcript.delta",
+            "event_id": "event_1",
+            "response_id": "resp_1",
+            "item_id": item_id,
+            "output_index": 0,
+            "content_index": 0,
+            "delta": delta,
+        }))
+        .unwrap()
+    }
+
+    fn done_event(item_id: &str, transcript: &str) -> ServerEvent {
+        serde_json::from_value(serde_json::json!({
+            "type": "response.audio_transcript.done",
+            "event_id": "event_2",
+            "response_id": "resp_1",
+            "item_id": item_id,
+            "output_index": 0,
+            "content_index": 0,
+            "transcript": transcript,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_accumulates_audio_transcript_deltas() {
+        let mut assembler = TranscriptAssembler::new();
+
+        let caption = assembler
+            .handle_event(&delta_event("item_1", "What "))
+            .unwrap();
+        assert_eq!(caption.text, "What ");
+        assert!(!caption.is_final);
+
+        assembler.handle_event(&delta_event("item_2", "Other"));
+        let caption = assembler
+            .handle_event(&delta_event("item_1", "is TCP?"))
+            .unwrap();
+        assert_eq!(caption.item_id, "item_1");
+        assert_eq!(caption.text, "What is TCP?");
+        assert_eq!(assembler.caption("item_2").as_deref(), Some("Other"));
+
+        let caption = assembler
+            .handle_event(&done_event("item_1", "What is TCP?"))
+            .unwrap();
+        assert_eq!(
+            caption,
+            Caption {
+                item_id: "item_1".to_string(),
+                text: "What is TCP?".to_string(),
+                is_final: true,
+            }
+        );
+        assert_eq!(assembler.caption("item_1"), None);
+    }
+
+    #[test]
+    fn test_abandoned_captions_expire() {
+        let clock = Arc::new(ManualClock::new());
+        let mut assembler = TranscriptAssembler::with_map(CorrelationMap::with_clock(
+            CAPTION_TTL,
+            MAX_IN_PROGRESS_CAPTIONS,
+            clock.clone(),
+        ));
+
+        assembler.handle_event(&delta_event("item_1", "Cancelled mid-"));
+        clock.advance(CAPTION_TTL);
+        assert_eq!(assembler.caption("item_1"), None);
+
+        let caption = assembler
+            .handle_event(&delta_event("item_1", "fresh"))
+            .unwrap();
+        assert_eq!(caption.text, "fresh");
+    }
+
+    #[test]
+    fn test_ignores_unrelated_events() {
+        let mut assembler = TranscriptAssembler::new();
+        let event = ServerEvent::Close { reason: None };
+        assert!(assembler.handle_event(&event).is_none());
+    }
+}